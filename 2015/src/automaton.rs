@@ -0,0 +1,394 @@
+use crate::parse::ParseError;
+use std::collections::{HashMap, HashSet};
+
+// which neighbour counts bring a dead cell to life, and which keep a live one alive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        assert!(
+            !birth.contains(&0),
+            "Cells cannot be born without neighbours."
+        );
+        return Self {
+            birth: birth.to_vec(),
+            survival: survival.to_vec(),
+        };
+    }
+
+    // reads the usual notation, like "B3/S23" for conway's game of life.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut birth = None;
+        let mut survival = None;
+
+        for part in text.split('/') {
+            let (slot, counts) = match part.chars().next() {
+                Some('B' | 'b') => (&mut birth, &part[1..]),
+                Some('S' | 's') => (&mut survival, &part[1..]),
+                _ => return Err(ParseError::at_token(1, text, part, "B or S")),
+            };
+            if slot.is_some() {
+                return Err(ParseError::at_token(
+                    1,
+                    text,
+                    part,
+                    "each of B and S only once",
+                ));
+            }
+
+            let mut out = Vec::new();
+            for (i, ch) in counts.char_indices() {
+                match ch.to_digit(10) {
+                    Some(0) if part.starts_with(['B', 'b']) => {
+                        return Err(ParseError::at_token(
+                            1,
+                            text,
+                            &counts[i..],
+                            "a count above zero",
+                        ));
+                    }
+                    Some(d) => out.push(d as usize),
+                    None => return Err(ParseError::at_token(1, text, &counts[i..], "a digit")),
+                }
+            }
+            *slot = Some(out);
+        }
+
+        return match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self { birth, survival }),
+            _ => Err(ParseError::at_end(1, text, "both B and S")),
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology<const D: usize> {
+    // cells outside of the size are always dead.
+    Bounded([i64; D]),
+    // each edge wraps around to the opposite one.
+    Toroidal([i64; D]),
+    // no edges at all.
+    Infinite,
+}
+
+// a generation repeats one that was seen before.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cycle {
+    // the first generation that is part of the cycle.
+    pub start: usize,
+    // one for a steady state.
+    pub period: usize,
+}
+
+// a life-like automaton in D dimensions. only live cells are stored, so
+// every topology works the same way.
+#[derive(Debug, Clone)]
+pub struct Automaton<const D: usize> {
+    rule: Rule,
+    topology: Topology<D>,
+    offsets: Vec<[i64; D]>,
+    alive: HashSet<[i64; D]>,
+    pinned: HashMap<[i64; D], bool>,
+    generation: usize,
+}
+
+impl<const D: usize> Automaton<D> {
+    pub fn new(rule: Rule, topology: Topology<D>) -> Self {
+        return Self {
+            rule,
+            topology,
+            offsets: make_offsets(),
+            alive: HashSet::new(),
+            pinned: HashMap::new(),
+            generation: 0,
+        };
+    }
+
+    pub fn set(&mut self, cell: [i64; D], alive: bool) {
+        let cell = match self.place(cell) {
+            Some(cell) => cell,
+            None => return,
+        };
+        if alive {
+            self.alive.insert(cell);
+        } else {
+            self.alive.remove(&cell);
+        }
+    }
+
+    // keeps a cell in one state whatever its neighbours say.
+    pub fn pin(&mut self, cell: [i64; D], alive: bool) {
+        self.set(cell, alive);
+        if let Some(cell) = self.place(cell) {
+            self.pinned.insert(cell, alive);
+        }
+    }
+
+    pub fn is_alive(&self, cell: [i64; D]) -> bool {
+        return self
+            .place(cell)
+            .map(|c| self.alive.contains(&c))
+            .unwrap_or(false);
+    }
+
+    pub fn population(&self) -> usize {
+        return self.alive.len();
+    }
+
+    pub fn generation(&self) -> usize {
+        return self.generation;
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &[i64; D]> {
+        return self.alive.iter();
+    }
+
+    // where a cell really is, or None when it is past a bounded edge.
+    fn place(&self, mut cell: [i64; D]) -> Option<[i64; D]> {
+        match self.topology {
+            Topology::Bounded(size) => {
+                if (0..D).any(|i| cell[i] < 0 || cell[i] >= size[i]) {
+                    return None;
+                }
+            }
+            Topology::Toroidal(size) => {
+                for i in 0..D {
+                    cell[i] = cell[i].rem_euclid(size[i]);
+                }
+            }
+            Topology::Infinite => {}
+        }
+        return Some(cell);
+    }
+
+    pub fn step(&mut self) {
+        let mut counts: HashMap<[i64; D], usize> = HashMap::new();
+
+        for cell in &self.alive {
+            for offset in &self.offsets {
+                let mut n = *cell;
+                for i in 0..D {
+                    n[i] += offset[i];
+                }
+                if let Some(n) = self.place(n) {
+                    *counts.entry(n).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut next = HashSet::new();
+
+        // live cells without any neighbours are not in "counts".
+        for cell in &self.alive {
+            if self.rule.survival.contains(counts.get(cell).unwrap_or(&0)) {
+                next.insert(*cell);
+            }
+        }
+        for (cell, count) in counts {
+            if !self.alive.contains(&cell) && self.rule.birth.contains(&count) {
+                next.insert(cell);
+            }
+        }
+        for (cell, alive) in &self.pinned {
+            if *alive {
+                next.insert(*cell);
+            } else {
+                next.remove(cell);
+            }
+        }
+
+        self.alive = next;
+        self.generation += 1;
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    // steps until a generation repeats, for at most "max_steps". on an
+    // infinite grid a pattern that moves, like a glider, never repeats.
+    pub fn find_cycle(&mut self, max_steps: usize) -> Option<Cycle> {
+        let mut seen: HashMap<Vec<[i64; D]>, usize> = HashMap::new();
+
+        for _ in 0..=max_steps {
+            let mut state: Vec<[i64; D]> = self.alive.iter().copied().collect();
+            state.sort();
+
+            if let Some(start) = seen.get(&state) {
+                return Some(Cycle {
+                    start: *start,
+                    period: self.generation - start,
+                });
+            }
+            seen.insert(state, self.generation);
+            self.step();
+        }
+
+        return None;
+    }
+}
+
+// every step to a neighbour, which is 3^D - 1 of them.
+fn make_offsets<const D: usize>() -> Vec<[i64; D]> {
+    let mut out = vec![[0; D]];
+    for i in 0..D {
+        out = out
+            .into_iter()
+            .flat_map(|o| {
+                return [-1, 0, 1].map(|d| {
+                    let mut o = o;
+                    o[i] = d;
+                    return o;
+                });
+            })
+            .collect();
+    }
+    out.retain(|o| o.iter().any(|d| *d != 0));
+    return out;
+}
+
+mod tests {
+    use crate::automaton::*;
+
+    fn life() -> Rule {
+        return Rule::parse("B3/S23").unwrap();
+    }
+
+    fn from_rows(automaton: &mut Automaton<2>, rows: &[&str]) {
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                automaton.set([x as i64, y as i64], ch == '#');
+            }
+        }
+    }
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!(Rule::new(&[3], &[2, 3]), life());
+        assert_eq!(Rule::new(&[3, 6], &[2, 3]), Rule::parse("s23/b36").unwrap());
+        assert_eq!(Rule::new(&[2], &[]), Rule::parse("B2/S").unwrap());
+
+        let check = |text: &str, column: usize, expected: &str| {
+            let err = Rule::parse(text).unwrap_err();
+            assert_eq!(
+                (column, expected.to_string()),
+                (err.column, err.expected),
+                "{}",
+                text
+            );
+        };
+        check("B3/X23", 4, "B or S");
+        check("B3/S2x", 6, "a digit");
+        check("B03/S23", 2, "a count above zero");
+        check("B3/B3", 4, "each of B and S only once");
+        check("B3", 3, "both B and S");
+    }
+
+    #[test]
+    fn count_neighbours_in_any_dimension() {
+        assert_eq!(2, make_offsets::<1>().len());
+        assert_eq!(8, make_offsets::<2>().len());
+        assert_eq!(26, make_offsets::<3>().len());
+        assert_eq!(80, make_offsets::<4>().len());
+    }
+
+    #[test]
+    fn detect_steady_states_and_oscillators() {
+        let mut block = Automaton::new(life(), Topology::Bounded([4, 4]));
+        from_rows(&mut block, &["....", ".##.", ".##.", "...."]);
+        assert_eq!(
+            Some(Cycle {
+                start: 0,
+                period: 1
+            }),
+            block.find_cycle(10)
+        );
+
+        let mut blinker = Automaton::new(life(), Topology::Bounded([5, 5]));
+        from_rows(&mut blinker, &[".....", "..#..", "..#..", "..#..", "....."]);
+        assert_eq!(
+            Some(Cycle {
+                start: 0,
+                period: 2
+            }),
+            blinker.find_cycle(10)
+        );
+
+        // everything dies out after the first step, and stays dead.
+        let mut lonely = Automaton::new(life(), Topology::Infinite);
+        lonely.set([0, 0], true);
+        assert_eq!(
+            Some(Cycle {
+                start: 1,
+                period: 1
+            }),
+            lonely.find_cycle(10)
+        );
+        assert_eq!(0, lonely.population());
+    }
+
+    #[test]
+    fn move_a_glider_around() {
+        let glider = [".#.", "..#", "###"];
+
+        // four steps move it one cell diagonally, so it comes back after 4 * 8.
+        let mut torus = Automaton::new(life(), Topology::Toroidal([8, 8]));
+        from_rows(&mut torus, &glider);
+        assert_eq!(
+            Some(Cycle {
+                start: 0,
+                period: 32
+            }),
+            torus.find_cycle(100)
+        );
+
+        let mut plane = Automaton::new(life(), Topology::Infinite);
+        from_rows(&mut plane, &glider);
+        assert_eq!(None, plane.find_cycle(40));
+        assert_eq!(5, plane.population());
+        assert!(plane.is_alive([11, 12]));
+
+        // it turns into a block when it hits a bounded corner.
+        let mut bounded = Automaton::new(life(), Topology::Bounded([8, 8]));
+        from_rows(&mut bounded, &glider);
+        bounded.run(40);
+        assert_eq!(4, bounded.population());
+    }
+
+    #[test]
+    fn keep_pinned_cells() {
+        let mut automaton = Automaton::new(life(), Topology::Bounded([3, 3]));
+        automaton.pin([0, 0], true);
+        automaton.pin([2, 2], false);
+        automaton.set([2, 2], true);
+        automaton.set([5, 5], true);
+
+        automaton.run(3);
+        assert_eq!(vec![[0, 0]], automaton.cells().copied().collect::<Vec<_>>());
+        assert_eq!(3, automaton.generation());
+    }
+
+    #[test]
+    fn run_conway_cubes() {
+        let mut cubes: Automaton<3> = Automaton::new(life(), Topology::Infinite);
+        let mut hypercubes: Automaton<4> = Automaton::new(life(), Topology::Infinite);
+
+        for (y, row) in [".#.", "..#", "###"].iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                cubes.set([x as i64, y as i64, 0], ch == '#');
+                hypercubes.set([x as i64, y as i64, 0, 0], ch == '#');
+            }
+        }
+        cubes.run(6);
+        hypercubes.run(6);
+
+        assert_eq!(112, cubes.population());
+        assert_eq!(848, hypercubes.population());
+    }
+}
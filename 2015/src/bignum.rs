@@ -0,0 +1,134 @@
+use std::fmt;
+
+// the limbs are base 10^9, lowest first, so printing needs no division
+// of the whole number.
+const BASE: u64 = 1_000_000_000;
+
+// an unsigned integer that grows as needed. it only does what counting
+// needs: adding and multiplying by small numbers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        return Self::default();
+    }
+
+    pub fn from_u64(mut value: u64) -> Self {
+        let mut limbs = Vec::new();
+        while value > 0 {
+            limbs.push((value % BASE) as u32);
+            value /= BASE;
+        }
+        return Self { limbs };
+    }
+
+    pub fn is_zero(&self) -> bool {
+        return self.limbs.is_empty();
+    }
+
+    pub fn add(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for i in 0..self.limbs.len() {
+            let sum =
+                self.limbs[i] as u64 + other.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            self.limbs[i] = (sum % BASE) as u32;
+            carry = sum / BASE;
+            if carry == 0 && i >= other.limbs.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    pub fn mul_u32(&self, factor: u32) -> BigUint {
+        if factor == 0 {
+            return Self::zero();
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0;
+        for limb in &self.limbs {
+            let product = *limb as u64 * factor as u64 + carry;
+            limbs.push((product % BASE) as u32);
+            carry = product / BASE;
+        }
+        while carry > 0 {
+            limbs.push((carry % BASE) as u32);
+            carry /= BASE;
+        }
+        return Self { limbs };
+    }
+
+    // None when the value does not fit.
+    pub fn to_u64(&self) -> Option<u64> {
+        let mut out: u64 = 0;
+        for limb in self.limbs.iter().rev() {
+            out = out.checked_mul(BASE)?.checked_add(*limb as u64)?;
+        }
+        return Some(out);
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.limbs.split_last() {
+            None => write!(f, "0"),
+            Some((top, rest)) => {
+                write!(f, "{}", top)?;
+                for limb in rest.iter().rev() {
+                    write!(f, "{:09}", limb)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+mod tests {
+    use crate::bignum::*;
+
+    #[test]
+    fn add_and_multiply() {
+        let mut n = BigUint::from_u64(999_999_999);
+        n.add(&BigUint::from_u64(1));
+        assert_eq!("1000000000", n.to_string());
+
+        let mut n = BigUint::from_u64(u64::MAX);
+        n.add(&BigUint::from_u64(u64::MAX));
+        assert_eq!("36893488147419103230", n.to_string());
+        assert_eq!(None, n.to_u64());
+
+        assert_eq!(Some(u64::MAX), BigUint::from_u64(u64::MAX).to_u64());
+        assert_eq!("0", BigUint::zero().to_string());
+        assert!(BigUint::from_u64(12).mul_u32(0).is_zero());
+        assert_eq!(
+            "1000000002000000001",
+            BigUint::from_u64(1_000_000_001)
+                .mul_u32(1_000_000_001)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn compute_a_large_power() {
+        let mut n = BigUint::from_u64(1);
+        for _ in 0..100 {
+            n = n.mul_u32(2);
+        }
+        assert_eq!("1267650600228229401496703205376", n.to_string());
+
+        let mut sum = BigUint::zero();
+        sum.add(&n);
+        sum.add(&n);
+        assert_eq!("2535301200456458802993406410752", sum.to_string());
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Signal {
+    Constant(u16),
+    Wire(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gate {
+    Buffer(Signal),
+    And(Signal, Signal),
+    Or(Signal, Signal),
+    LeftShift(Signal, Signal),
+    RightShift(Signal, Signal),
+    Not(Signal),
+}
+
+impl Gate {
+    pub fn inputs(&self) -> Vec<&Signal> {
+        return match self {
+            Gate::Buffer(a) | Gate::Not(a) => vec![a],
+            Gate::And(a, b) | Gate::Or(a, b) | Gate::LeftShift(a, b) | Gate::RightShift(a, b) => {
+                vec![a, b]
+            }
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircuitError {
+    MultipleDrivers(String),
+    UndefinedWire { wire: String, used_by: String },
+    // the wires of the loop. each one reads the next, and the last one reads the first.
+    Loop(Vec<String>),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::MultipleDrivers(wire) => {
+                write!(f, "wire {} is driven more than once", wire)
+            }
+            CircuitError::UndefinedWire { wire, used_by } => {
+                write!(f, "wire {} is used by {} but never driven", wire, used_by)
+            }
+            CircuitError::Loop(wires) => write!(
+                f,
+                "combinational loop: {} -> {}",
+                wires.join(" -> "),
+                wires[0]
+            ),
+        }
+    }
+}
+
+// the gates driving each wire, in the order they were connected.
+#[derive(Debug, Clone, Default)]
+pub struct Netlist {
+    wires: Vec<(String, Gate)>,
+    index: HashMap<String, usize>,
+}
+
+impl Netlist {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn connect(&mut self, wire: &str, gate: Gate) -> Result<(), CircuitError> {
+        if self.index.contains_key(wire) {
+            return Err(CircuitError::MultipleDrivers(wire.to_string()));
+        }
+        self.index.insert(wire.to_string(), self.wires.len());
+        self.wires.push((wire.to_string(), gate));
+        return Ok(());
+    }
+
+    pub fn len(&self) -> usize {
+        return self.wires.len();
+    }
+
+    // orders the gates so that every wire comes after the wires it reads.
+    pub fn compile(&self) -> Result<Circuit, CircuitError> {
+        let mut operands: Vec<Vec<Operand>> = Vec::with_capacity(self.wires.len());
+
+        for (wire, gate) in &self.wires {
+            let mut ops = Vec::new();
+            for input in gate.inputs() {
+                ops.push(match input {
+                    Signal::Constant(v) => Operand::Constant(*v),
+                    Signal::Wire(w) => match self.index.get(w) {
+                        Some(i) => Operand::Wire(*i),
+                        None => {
+                            return Err(CircuitError::UndefinedWire {
+                                wire: w.clone(),
+                                used_by: wire.clone(),
+                            });
+                        }
+                    },
+                });
+            }
+            operands.push(ops);
+        }
+
+        let mut state = vec![Visit::New; self.wires.len()];
+        let mut path = Vec::new();
+        let mut plan = Vec::with_capacity(self.wires.len());
+
+        for i in 0..self.wires.len() {
+            self.visit(i, &operands, &mut state, &mut path, &mut plan)?;
+        }
+
+        let steps = plan
+            .into_iter()
+            .map(|i| Step {
+                wire: i,
+                op: match &self.wires[i].1 {
+                    Gate::Buffer(_) => Op::Buffer,
+                    Gate::And(_, _) => Op::And,
+                    Gate::Or(_, _) => Op::Or,
+                    Gate::LeftShift(_, _) => Op::LeftShift,
+                    Gate::RightShift(_, _) => Op::RightShift,
+                    Gate::Not(_) => Op::Not,
+                },
+                operands: operands[i].clone(),
+            })
+            .collect();
+
+        return Ok(Circuit {
+            names: self.wires.iter().map(|(w, _)| w.clone()).collect(),
+            index: self.index.clone(),
+            steps,
+            overrides: HashMap::new(),
+        });
+    }
+
+    fn visit(
+        &self,
+        i: usize,
+        operands: &[Vec<Operand>],
+        state: &mut [Visit],
+        path: &mut Vec<usize>,
+        plan: &mut Vec<usize>,
+    ) -> Result<(), CircuitError> {
+        match state[i] {
+            Visit::Done => return Ok(()),
+            Visit::InProgress => {
+                let start = path.iter().position(|k| *k == i).unwrap();
+                let wires = path[start..]
+                    .iter()
+                    .map(|k| self.wires[*k].0.clone())
+                    .collect();
+                return Err(CircuitError::Loop(wires));
+            }
+            Visit::New => {}
+        }
+
+        state[i] = Visit::InProgress;
+        path.push(i);
+
+        for op in &operands[i] {
+            if let Operand::Wire(k) = op {
+                self.visit(*k, operands, state, path, plan)?;
+            }
+        }
+
+        path.pop();
+        state[i] = Visit::Done;
+        plan.push(i);
+
+        return Ok(());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Constant(u16),
+    Wire(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Buffer,
+    And,
+    Or,
+    LeftShift,
+    RightShift,
+    Not,
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    wire: usize,
+    op: Op,
+    operands: Vec<Operand>,
+}
+
+// a netlist compiled into an evaluation plan.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    steps: Vec<Step>,
+    overrides: HashMap<usize, u16>,
+}
+
+impl Circuit {
+    // forces a wire to a value, whatever its gate says. the plan stays
+    // valid since an overridden wire just stops reading its inputs.
+    pub fn set_override(&mut self, wire: &str, value: u16) -> Result<(), CircuitError> {
+        let i = self.wire_index(wire)?;
+        self.overrides.insert(i, value);
+        return Ok(());
+    }
+
+    pub fn clear_override(&mut self, wire: &str) -> Result<(), CircuitError> {
+        let i = self.wire_index(wire)?;
+        self.overrides.remove(&i);
+        return Ok(());
+    }
+
+    fn wire_index(&self, wire: &str) -> Result<usize, CircuitError> {
+        return self
+            .index
+            .get(wire)
+            .copied()
+            .ok_or_else(|| CircuitError::UndefinedWire {
+                wire: wire.to_string(),
+                used_by: "an override".to_string(),
+            });
+    }
+
+    // every wire is computed exactly once, in plan order.
+    pub fn evaluate(&self) -> Signals {
+        let mut values = vec![0u16; self.names.len()];
+
+        for step in &self.steps {
+            if let Some(v) = self.overrides.get(&step.wire) {
+                values[step.wire] = *v;
+                continue;
+            }
+
+            let read = |k: usize| -> u16 {
+                return match step.operands[k] {
+                    Operand::Constant(v) => v,
+                    Operand::Wire(w) => values[w],
+                };
+            };
+
+            values[step.wire] = match step.op {
+                Op::Buffer => read(0),
+                Op::Not => !read(0),
+                Op::And => read(0) & read(1),
+                Op::Or => read(0) | read(1),
+                Op::LeftShift => read(0).checked_shl(read(1) as u32).unwrap_or(0),
+                Op::RightShift => read(0).checked_shr(read(1) as u32).unwrap_or(0),
+            };
+        }
+
+        return Signals {
+            circuit: self,
+            values,
+        };
+    }
+}
+
+pub struct Signals<'a> {
+    circuit: &'a Circuit,
+    values: Vec<u16>,
+}
+
+impl<'a> Signals<'a> {
+    pub fn get(&self, wire: &str) -> Option<u16> {
+        return self.circuit.index.get(wire).map(|i| self.values[*i]);
+    }
+
+    // every wire with its value, in the order the wires were connected.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u16)> + '_ {
+        return self
+            .circuit
+            .names
+            .iter()
+            .zip(self.values.iter())
+            .map(|(name, v)| (name.as_str(), *v));
+    }
+}
+
+mod tests {
+    use crate::circuit::*;
+
+    fn wire(name: &str) -> Signal {
+        return Signal::Wire(name.to_string());
+    }
+
+    #[test]
+    fn evaluate_out_of_order() {
+        let mut netlist = Netlist::new();
+        netlist
+            .connect("c", Gate::And(wire("a"), wire("b")))
+            .unwrap();
+        netlist
+            .connect("a", Gate::Buffer(Signal::Constant(12)))
+            .unwrap();
+        netlist.connect("b", Gate::Not(wire("a"))).unwrap();
+
+        let circuit = netlist.compile().unwrap();
+        let signals = circuit.evaluate();
+
+        assert_eq!(Some(12), signals.get("a"));
+        assert_eq!(Some(!12), signals.get("b"));
+        assert_eq!(Some(0), signals.get("c"));
+        assert_eq!(None, signals.get("d"));
+        assert_eq!(
+            vec!["c", "a", "b"],
+            signals.iter().map(|(w, _)| w).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn override_and_reevaluate() {
+        let mut netlist = Netlist::new();
+        netlist
+            .connect("a", Gate::Buffer(Signal::Constant(3)))
+            .unwrap();
+        netlist
+            .connect("b", Gate::LeftShift(wire("a"), Signal::Constant(2)))
+            .unwrap();
+        let mut circuit = netlist.compile().unwrap();
+
+        assert_eq!(Some(12), circuit.evaluate().get("b"));
+
+        circuit.set_override("a", 5).unwrap();
+        assert_eq!(Some(20), circuit.evaluate().get("b"));
+
+        circuit.clear_override("a").unwrap();
+        assert_eq!(Some(12), circuit.evaluate().get("b"));
+
+        assert!(circuit.set_override("x", 1).is_err());
+    }
+
+    #[test]
+    fn report_broken_netlists() {
+        let mut netlist = Netlist::new();
+        netlist.connect("a", Gate::Buffer(wire("c"))).unwrap();
+        netlist.connect("b", Gate::Not(wire("a"))).unwrap();
+        netlist
+            .connect("c", Gate::Or(wire("b"), Signal::Constant(1)))
+            .unwrap();
+
+        let err = netlist.compile().unwrap_err();
+        assert_eq!(
+            CircuitError::Loop(vec!["a".to_string(), "c".to_string(), "b".to_string()]),
+            err
+        );
+        assert_eq!("combinational loop: a -> c -> b -> a", err.to_string());
+
+        assert_eq!(
+            Err(CircuitError::MultipleDrivers("a".to_string())),
+            netlist.connect("a", Gate::Buffer(Signal::Constant(1)))
+        );
+
+        let mut netlist = Netlist::new();
+        netlist.connect("a", Gate::Buffer(wire("zz"))).unwrap();
+        assert_eq!(
+            "wire zz is used by a but never driven",
+            netlist.compile().unwrap_err().to_string()
+        );
+    }
+}
@@ -1,28 +1,33 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+
 pub fn run() {
     let input = std::fs::read_to_string("src/day02_input.txt").unwrap();
-    let lines: Vec<&str> = input.trim().lines().collect();
-    let sizes: Vec<Size> = lines
-        .iter()
-        .map(|line| {
-            let parts: Vec<u64> = line
-                .split('x')
-                .map(|num| num.parse::<u64>().unwrap())
-                .collect();
-
-            assert_eq!(3, parts.len());
-
-            return Size {
-                length: parts[0],
-                width: parts[1],
-                height: parts[2],
-            };
-        })
-        .collect();
+    let sizes = match parse_sizes(&input) {
+        Ok(sizes) => sizes,
+        Err(err) => {
+            println!("Day 2: {}", err);
+            return;
+        }
+    };
 
     part1(&sizes);
     part2(&sizes);
 }
 
+fn parse_sizes(input: &str) -> Result<Vec<Size>, ParseError> {
+    let mut out = Vec::new();
+    for (n, line) in numbered_lines(input) {
+        let (length, width, height) = scan!((n, line), "{}x{}x{}", u64, u64, u64)?;
+        out.push(Size {
+            length,
+            width,
+            height,
+        });
+    }
+    return Ok(out);
+}
+
 #[derive(Debug)]
 struct Size {
     length: u64,
@@ -65,3 +70,29 @@ fn part2(sizes: &[Size]) {
 
     println!("Day 2B: {:?}", ribbon);
 }
+
+mod tests {
+    use crate::day02::*;
+
+    #[test]
+    fn wrap_the_examples() {
+        let sizes = parse_sizes("2x3x4\n1x1x10\n").unwrap();
+        assert_eq!(
+            vec![58, 43],
+            sizes.iter().map(Size::get_paper_area).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![34, 14],
+            sizes
+                .iter()
+                .map(Size::get_ribbon_length)
+                .collect::<Vec<_>>()
+        );
+
+        let err = parse_sizes("2x3x4\n1x1\n").unwrap_err();
+        assert_eq!(
+            (2, 4, "'x'".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
+}
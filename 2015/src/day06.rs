@@ -1,119 +1,174 @@
+use crate::image;
+use crate::lights::{Action, Brightness, Instruction, LightGrid, Rect};
+use crate::parse::{numbered_lines, parse_token, ParseError};
+
 pub fn run() {
     let input = std::fs::read_to_string("src/day06_input.txt").unwrap();
-    let lines: Vec<&str> = input.lines().collect();
-    let commands = parse_commands(&lines);
+    let commands = match parse_commands(&input) {
+        Ok(commands) => commands,
+        Err(err) => {
+            println!("Day 6: {}", err);
+            return;
+        }
+    };
     part1(&commands);
     part2(&commands);
 }
 
-struct Point {
-    x: usize,
-    y: usize,
-}
+const ACTIONS: [(&'static str, Action); 3] = [
+    ("turn on ", Action::TurnOn),
+    ("turn off ", Action::TurnOff),
+    ("toggle ", Action::Toggle),
+];
 
-struct Command {
-    start: Point,
-    end: Point,
-    kind: CommandKind,
+// "x,y", somewhere inside of "line".
+fn parse_corner(n: usize, line: &str, corner: &str) -> Result<(u64, u64), ParseError> {
+    let (x, y) = match corner.split_once(',') {
+        Some((x, y)) => (x.trim(), y.trim()),
+        None => return Err(ParseError::at_token(n, line, corner, "'x,y'")),
+    };
+    return Ok((
+        parse_token(n, line, x, "a number")?,
+        parse_token(n, line, y, "a number")?,
+    ));
 }
 
-enum CommandKind {
-    TurnOn,
-    TurnOff,
-    Toggle,
-}
+fn parse_commands(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut commands: Vec<Instruction> = Vec::new();
 
-fn parse_commands(lines: &[&str]) -> Vec<Command> {
-    let mut commands: Vec<Command> = Vec::new();
-    const SPLIT_CHARS: [char; 4] = [' ', '\n', '\t', ','];
-
-    for line in lines {
-        let parts: Vec<&str> = line.trim().split(&SPLIT_CHARS[..]).collect();
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        let found = ACTIONS.iter().find_map(|(prefix, action)| {
+            return text.strip_prefix(prefix).map(|rest| (*action, rest));
+        });
+        let (action, rest) = match found {
+            Some(found) => found,
+            None => {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    text,
+                    "'turn on', 'turn off' or 'toggle'",
+                ))
+            }
+        };
 
-        if parts[0] == "turn" && parts[1] == "on" {
-            commands.push(Command {
-                start: Point {
-                    x: parts[2].parse().unwrap(),
-                    y: parts[3].parse().unwrap(),
-                },
-                end: Point {
-                    x: parts[5].parse().unwrap(),
-                    y: parts[6].parse().unwrap(),
-                },
-                kind: CommandKind::TurnOn,
-            });
-        } else if parts[0] == "turn" && parts[1] == "off" {
-            commands.push(Command {
-                start: Point {
-                    x: parts[2].parse().unwrap(),
-                    y: parts[3].parse().unwrap(),
-                },
-                end: Point {
-                    x: parts[5].parse().unwrap(),
-                    y: parts[6].parse().unwrap(),
-                },
-                kind: CommandKind::TurnOff,
-            });
-        } else if parts[0] == "toggle" {
-            commands.push(Command {
-                start: Point {
-                    x: parts[1].parse().unwrap(),
-                    y: parts[2].parse().unwrap(),
-                },
-                end: Point {
-                    x: parts[4].parse().unwrap(),
-                    y: parts[5].parse().unwrap(),
-                },
-                kind: CommandKind::Toggle,
-            });
-        } else {
-            panic!("Unknown command: {:?}", parts);
+        let (from, to) = match rest.split_once(" through ") {
+            Some((from, to)) => (from.trim(), to.trim()),
+            None => return Err(ParseError::at_end(n, line, "' through '")),
+        };
+        let (x0, y0) = parse_corner(n, line, from)?;
+        let (x1, y1) = parse_corner(n, line, to)?;
+        if x0 > x1 || y0 > y1 {
+            return Err(ParseError::at_token(
+                n,
+                line,
+                to,
+                "a corner right of and below the first one",
+            ));
         }
+
+        commands.push(Instruction {
+            rect: Rect::new(x0, y0, x1, y1),
+            action,
+        });
     }
 
-    return commands;
+    return Ok(commands);
 }
 
-fn part1(commands: &[Command]) {
-    let mut lights = [[false; 1000]; 1000];
-
-    for cmd in commands {
-        for y in cmd.start.y..=cmd.end.y {
-            for x in cmd.start.x..=cmd.end.x {
-                lights[y][x] = match cmd.kind {
-                    CommandKind::TurnOn => true,
-                    CommandKind::TurnOff => false,
-                    CommandKind::Toggle => !lights[y][x],
-                };
-            }
-        }
-    }
+const LIGHT_PALETTE: [image::Rgb; 2] = [[20, 20, 30], [255, 220, 90]];
+
+fn part1(commands: &[Instruction]) {
+    let lights: LightGrid<bool> = LightGrid::new(commands);
 
-    let lights_on = lights
-        .iter()
-        .flatten()
-        .fold(0, |carry, state| carry + u64::from(*state == true));
+    println!("Day 6A: {:?}", lights.total());
 
-    println!("Day 6A: {:?}", lights_on);
+    if image::is_enabled() {
+        let img = image::Image::from_fn(1000, 1000, &LIGHT_PALETTE, |x, y| {
+            return u8::from(lights.get(x as u64, y as u64));
+        });
+        image::save("day06_lights.png", &img.to_png());
+    }
 }
 
-fn part2(commands: &[Command]) {
-    let mut brightness = [[0; 1000]; 1000];
-
-    for cmd in commands {
-        for y in cmd.start.y..=cmd.end.y {
-            for x in cmd.start.x..=cmd.end.x {
-                brightness[y][x] += match cmd.kind {
-                    CommandKind::TurnOn => 1,
-                    CommandKind::TurnOff => -1,
-                    CommandKind::Toggle => 2,
-                };
-                brightness[y][x] = std::cmp::max(0, brightness[y][x]);
-            }
-        }
+fn part2(commands: &[Instruction]) {
+    let brightness: LightGrid<Brightness> = LightGrid::new(commands);
+
+    println!("Day 6B: {}", brightness.total());
+
+    if image::is_enabled() {
+        let at = |x: usize, y: usize| brightness.get(x as u64, y as u64).0;
+        let max_brightness = (0..1000)
+            .flat_map(|y| (0..1000).map(move |x| at(x, y)))
+            .max()
+            .unwrap();
+        let palette: Vec<image::Rgb> = (0..=255)
+            .map(|v| [v as u8, v as u8, (v / 2) as u8])
+            .collect();
+        let img = image::Image::from_fn(1000, 1000, &palette, |x, y| {
+            return (at(x, y) * 255 / std::cmp::max(1, max_brightness)) as u8;
+        });
+        image::save("day06_brightness.png", &img.to_png());
     }
+}
 
-    let total_brightness = brightness.iter().flatten().fold(0, |carry, b| carry + b);
+mod tests {
+    use crate::day06::*;
 
-    println!("Day 6B: {}", total_brightness);
+    #[test]
+    fn parse_the_commands() {
+        let commands =
+            parse_commands("turn on 0,0 through 999,999\n\ntoggle 0,0 through 999,0").unwrap();
+        assert_eq!(
+            vec![
+                Instruction {
+                    rect: Rect::new(0, 0, 999, 999),
+                    action: Action::TurnOn
+                },
+                Instruction {
+                    rect: Rect::new(0, 0, 999, 0),
+                    action: Action::Toggle
+                }
+            ],
+            commands
+        );
+
+        let check = |err: ParseError, line: usize, column: usize, expected: &str| {
+            assert_eq!(
+                (line, column, expected.to_string()),
+                (err.line, err.column, err.expected)
+            );
+        };
+        check(
+            parse_commands("turn on 0,0 through 1,1\nturn up 0,0 through 1,1").unwrap_err(),
+            2,
+            1,
+            "'turn on', 'turn off' or 'toggle'",
+        );
+        check(
+            parse_commands("toggle 0,0 to 1,1").unwrap_err(),
+            1,
+            18,
+            "' through '",
+        );
+        check(
+            parse_commands("turn off 0;0 through 1,1").unwrap_err(),
+            1,
+            10,
+            "'x,y'",
+        );
+        check(
+            parse_commands("toggle 0,x through 1,1").unwrap_err(),
+            1,
+            10,
+            "a number",
+        );
+        check(
+            parse_commands("toggle 5,5 through 1,1").unwrap_err(),
+            1,
+            20,
+            "a corner right of and below the first one",
+        );
+    }
 }
@@ -1,129 +1,255 @@
-use std::collections::HashMap;
+use crate::circuit::{Circuit, CircuitError, Gate, Netlist, Signal};
+use crate::dot;
+use crate::parse::{numbered_lines, parse_token, ParseError};
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day07_input.txt").unwrap();
-    let lines: Vec<&str> = input.trim().lines().collect();
-    let commands = parse_commands(&lines);
-    part1(&commands);
-    part2(&commands);
-}
-
-type WireCache = HashMap<String, u16>;
-
-#[derive(Debug)]
-enum Argument {
-    Constant(u16),
-    Wire(String),
-}
-
-impl Argument {
-    fn parse(arg: &str) -> Self {
-        let chars: Vec<char> = arg.chars().collect();
-        if chars[0].is_numeric() {
-            return Argument::Constant(arg.parse().unwrap());
+    let commands = match parse_commands(&input) {
+        Ok(commands) => commands,
+        Err(err) => {
+            println!("Day 7: {}", err);
+            return;
         }
-        return Argument::Wire(arg.to_string());
+    };
+    if dot::is_enabled() {
+        dot::save("day07_wires.dot", &commands_to_graph(&commands));
     }
 
-    fn resolve(&self, commands: &[Command], cache: &mut WireCache) -> u16 {
-        return match self {
-            Argument::Constant(v) => *v,
-            Argument::Wire(w) => resolve_wire(commands, &w, cache),
-        };
-    }
-}
+    let result = build_circuit(&commands).and_then(|mut circuit| {
+        let a = part1(&circuit);
+        return part2(&mut circuit, a);
+    });
 
-#[derive(Debug)]
-enum Expression {
-    Equal(Argument),
-    And(Argument, Argument),
-    Or(Argument, Argument),
-    LeftShift(Argument, Argument),
-    RightShift(Argument, Argument),
-    Not(Argument),
+    if let Err(err) = result {
+        println!("Day 7: {}", err);
+    }
 }
 
-impl Expression {
-    fn evaluate(&self, commands: &[Command], cache: &mut WireCache) -> u16 {
-        return match self {
-            Expression::Equal(arg) => arg.resolve(commands, cache),
-            Expression::Not(arg) => !arg.resolve(commands, cache),
-            Expression::And(a, b) => a.resolve(commands, cache) & b.resolve(commands, cache),
-            Expression::RightShift(a, b) => {
-                a.resolve(commands, cache) >> b.resolve(commands, cache)
-            }
-            Expression::Or(a, b) => a.resolve(commands, cache) | b.resolve(commands, cache),
-            Expression::LeftShift(a, b) => a.resolve(commands, cache) << b.resolve(commands, cache),
-        };
+fn parse_signal(line_number: usize, line: &str, arg: &str) -> Result<Signal, ParseError> {
+    if arg.starts_with(|ch: char| ch.is_numeric()) {
+        let value = parse_token(line_number, line, arg, "a 16-bit integer")?;
+        return Ok(Signal::Constant(value));
+    }
+    if !arg.chars().all(|ch| ch.is_ascii_lowercase()) {
+        return Err(ParseError::at_token(line_number, line, arg, "a wire name"));
     }
+    return Ok(Signal::Wire(arg.to_string()));
 }
 
 #[derive(Debug)]
 struct Command {
-    input: Expression,
+    input: Gate,
     output: String,
 }
 
-fn parse_commands(lines: &[&str]) -> Vec<Command> {
+fn parse_commands(input: &str) -> Result<Vec<Command>, ParseError> {
     let mut out: Vec<Command> = Vec::new();
 
-    for line in lines {
+    for (n, line) in numbered_lines(input) {
         let parts: Vec<&str> = line.split_whitespace().collect();
-        let chars: Vec<char> = parts[0].chars().collect();
-
-        let expr = if parts[0] == "NOT" {
-            Expression::Not(Argument::parse(parts[1]))
-        } else if parts[1] == "AND" {
-            Expression::And(Argument::parse(parts[0]), Argument::parse(parts[2]))
-        } else if parts[1] == "RSHIFT" {
-            Expression::RightShift(Argument::parse(parts[0]), Argument::parse(parts[2]))
-        } else if parts[1] == "LSHIFT" {
-            Expression::LeftShift(Argument::parse(parts[0]), Argument::parse(parts[2]))
-        } else if parts[1] == "OR" {
-            Expression::Or(Argument::parse(parts[0]), Argument::parse(parts[2]))
-        } else if chars[0].is_numeric() {
-            Expression::Equal(Argument::parse(parts[0]))
-        } else if !chars[0].is_numeric() && parts[1] == "->" {
-            Expression::Equal(Argument::parse(parts[0]))
-        } else {
-            panic!("Unknown expression: {:?}.", parts)
+        let arg = |i: usize| -> Result<Signal, ParseError> {
+            return match parts.get(i) {
+                Some(token) => parse_signal(n, line, token),
+                None => Err(ParseError::at_end(n, line, "a wire or a value")),
+            };
+        };
+
+        // the operator decides where the arrow should be.
+        let (expr, arrow_index) = match parts.as_slice() {
+            ["NOT", ..] => (Gate::Not(arg(1)?), 2),
+            [_, "AND", ..] => (Gate::And(arg(0)?, arg(2)?), 3),
+            [_, "OR", ..] => (Gate::Or(arg(0)?, arg(2)?), 3),
+            [_, "LSHIFT", ..] => (Gate::LeftShift(arg(0)?, arg(2)?), 3),
+            [_, "RSHIFT", ..] => (Gate::RightShift(arg(0)?, arg(2)?), 3),
+            [_, "->", ..] => (Gate::Buffer(arg(0)?), 1),
+            [_, op, ..] => {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    op,
+                    "AND, OR, LSHIFT, RSHIFT or ->",
+                ));
+            }
+            _ => return Err(ParseError::at_end(n, line, "an expression")),
+        };
+
+        match parts.get(arrow_index) {
+            Some(&"->") => {}
+            Some(token) => return Err(ParseError::at_token(n, line, token, "->")),
+            None => return Err(ParseError::at_end(n, line, "->")),
+        };
+
+        let output = match parts.get(arrow_index + 1) {
+            Some(token) => match parse_signal(n, line, token)? {
+                Signal::Wire(w) => w,
+                Signal::Constant(_) => {
+                    return Err(ParseError::at_token(n, line, token, "a wire name"));
+                }
+            },
+            None => return Err(ParseError::at_end(n, line, "a wire name")),
         };
+
+        if let Some(token) = parts.get(arrow_index + 2) {
+            return Err(ParseError::at_token(n, line, token, "the end of the line"));
+        }
+
         out.push(Command {
             input: expr,
-            output: parts.last().unwrap().to_string(),
+            output,
         })
     }
 
-    return out;
+    return Ok(out);
 }
 
-fn resolve_wire(commands: &[Command], wire: &str, cache: &mut WireCache) -> u16 {
-    let maybe_cached = cache.get(wire);
+// every wire is a node, labelled with its gate and any constant inputs.
+fn commands_to_graph(commands: &[Command]) -> dot::Graph {
+    let mut graph = dot::Graph::directed("day07");
+    graph.attr("rankdir", "LR");
+
+    for cmd in commands {
+        let op = match &cmd.input {
+            Gate::Buffer(_) => "=",
+            Gate::And(_, _) => "AND",
+            Gate::Or(_, _) => "OR",
+            Gate::LeftShift(_, _) => "LSHIFT",
+            Gate::RightShift(_, _) => "RSHIFT",
+            Gate::Not(_) => "NOT",
+        };
+        let mut label = format!("{}\n{}", cmd.output, op);
 
-    if let Some(v) = maybe_cached {
-        return *v;
-    }
+        for input in cmd.input.inputs() {
+            match input {
+                Signal::Constant(v) => label.push_str(&format!(" {}", v)),
+                Signal::Wire(w) => graph.edge(w, &cmd.output, &[]),
+            }
+        }
 
-    let found = commands.iter().find(|cmd| cmd.output == wire);
-    let result = found.unwrap().input.evaluate(commands, cache);
+        graph.node(&cmd.output, &[("label", label.trim_end())]);
+    }
 
-    cache.insert(wire.to_string(), result);
+    return graph;
+}
 
-    return result;
+fn build_circuit(commands: &[Command]) -> Result<Circuit, CircuitError> {
+    let mut netlist = Netlist::new();
+    for cmd in commands {
+        netlist.connect(&cmd.output, cmd.input.clone())?;
+    }
+    return netlist.compile();
 }
 
-fn part1(commands: &[Command]) {
-    let mut cache = WireCache::new();
-    let value = resolve_wire(commands, "a", &mut cache);
+fn part1(circuit: &Circuit) -> u16 {
+    let value = circuit.evaluate().get("a").unwrap();
 
     println!("Day 7A: {:?}", value);
-}
 
-fn part2(commands: &[Command]) {
-    let mut cache = WireCache::new();
-    cache.insert("b".to_string(), 16076);
+    return value;
+}
 
-    let value = resolve_wire(commands, "a", &mut cache);
+// the signal of "a" is fed back into "b", and everything is evaluated again.
+fn part2(circuit: &mut Circuit, a: u16) -> Result<(), CircuitError> {
+    circuit.set_override("b", a)?;
+    let value = circuit.evaluate().get("a").unwrap();
 
     println!("Day 7B: {:?}", value);
+
+    return Ok(());
+}
+
+mod tests {
+    use crate::day07::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+123 -> x
+456 -> y
+x AND y -> d
+x OR y -> e
+x LSHIFT 2 -> f
+y RSHIFT 2 -> g
+NOT x -> h
+NOT y -> i
+"#;
+
+    #[test]
+    fn resolve_the_example() {
+        let commands = parse_commands(EXAMPLE_INPUT).unwrap();
+        let circuit = build_circuit(&commands).unwrap();
+        let signals = circuit.evaluate();
+
+        assert_eq!(8, commands.len());
+        assert_eq!(Some(72), signals.get("d"));
+        assert_eq!(Some(507), signals.get("e"));
+        assert_eq!(Some(492), signals.get("f"));
+        assert_eq!(Some(114), signals.get("g"));
+        assert_eq!(Some(65412), signals.get("h"));
+        assert_eq!(Some(65079), signals.get("i"));
+        assert_eq!(Some(123), signals.get("x"));
+        assert_eq!(Some(456), signals.get("y"));
+    }
+
+    #[test]
+    fn draw_the_example() {
+        let commands = parse_commands(EXAMPLE_INPUT).unwrap();
+        let graph = commands_to_graph(&commands);
+
+        assert_eq!(include_str!("day07_example.dot"), graph.to_dot());
+    }
+
+    #[test]
+    fn report_circuit_errors() {
+        let commands = parse_commands(
+            "x AND y -> z
+1 -> x",
+        )
+        .unwrap();
+        let err = build_circuit(&commands).unwrap_err();
+        assert_eq!("wire y is used by z but never driven", err.to_string());
+
+        let commands = parse_commands(
+            "b -> a
+NOT a -> b",
+        )
+        .unwrap();
+        let err = build_circuit(&commands).unwrap_err();
+        assert_eq!("combinational loop: a -> b -> a", err.to_string());
+
+        let commands = parse_commands(
+            "1 -> a
+2 -> a",
+        )
+        .unwrap();
+        let err = build_circuit(&commands).unwrap_err();
+        assert_eq!("wire a is driven more than once", err.to_string());
+    }
+
+    #[test]
+    fn report_unknown_operator() {
+        let err = parse_commands("123 -> x\nx XOR y -> z").unwrap_err();
+
+        assert_eq!(2, err.line);
+        assert_eq!(3, err.column);
+        assert_eq!("AND, OR, LSHIFT, RSHIFT or ->", err.expected);
+    }
+
+    #[test]
+    fn report_bad_values() {
+        let err = parse_commands("99999 -> x").unwrap_err();
+        assert_eq!(
+            (1, 1, "a 16-bit integer".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse_commands("x AND y z").unwrap_err();
+        assert_eq!(
+            (1, 9, "->".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse_commands("NOT x ->").unwrap_err();
+        assert_eq!(
+            (1, 9, "a wire name".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
 }
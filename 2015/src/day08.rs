@@ -1,74 +1,173 @@
+use crate::parse::{numbered_lines, ParseError};
+
 pub fn run() {
     let input = std::fs::read_to_string("src/day08_input.txt").unwrap();
-    let lines: Vec<&str> = input.trim().lines().collect();
+    let mut literals = Vec::new();
 
-    part1(&lines);
-    part2(&lines);
-}
+    for (n, line) in numbered_lines(&input) {
+        let line = line.trim();
+        match decode(n, line) {
+            Ok(bytes) => literals.push((line, bytes)),
+            Err(err) => {
+                println!("Day 8: {}", err);
+                return;
+            }
+        }
+    }
 
-fn count_bytes_in_encoded_str(code: &str) -> usize {
-    assert_eq!("\"", &code[0..1]);
-    assert_eq!('"', code.chars().last().unwrap());
+    part1(&literals);
+    part2(&literals);
+}
 
-    let mut byte_count = 0;
-    let mut i = 1;
+// reads a literal like "a\"b\\c\x27", quotes included, into the bytes
+// it stands for. "line" is only there for the error.
+fn decode(line: usize, literal: &str) -> Result<Vec<u8>, ParseError> {
+    let code = literal.as_bytes();
+    let error_at = |i: usize, expected: &str| ParseError::new(line, literal, i + 1, expected);
 
-    while i < (code.len() - 1) {
-        let chr = &code[i..(i + 1)];
+    if code.first() != Some(&b'"') {
+        return Err(error_at(0, "'\"'"));
+    }
 
-        if chr == "\\" {
-            let next = &code[(i + 1)..(i + 2)];
+    let mut out = Vec::new();
+    let mut i = 1;
 
-            if next == "\"" || next == "\\" {
-                byte_count += 1;
-                i += 2;
-            } else if next == "x" {
-                byte_count += 1;
-                i += 4;
-            } else {
-                panic!("Unexpected escaped character: {} in {}", chr, code);
+    loop {
+        match code.get(i) {
+            None => return Err(error_at(i, "a closing '\"'")),
+            Some(b'"') => break,
+            Some(b'\\') => match code.get(i + 1) {
+                Some(b'\\') | Some(b'"') => {
+                    out.push(code[i + 1]);
+                    i += 2;
+                }
+                Some(b'x') => {
+                    let digits = code.get((i + 2)..(i + 4)).unwrap_or(&[]);
+                    let value = std::str::from_utf8(digits)
+                        .ok()
+                        .filter(|d| d.len() == 2 && d.chars().all(|c| c.is_ascii_hexdigit()))
+                        .and_then(|d| u8::from_str_radix(d, 16).ok());
+                    match value {
+                        Some(value) => out.push(value),
+                        None => return Err(error_at(i + 2, "two hex digits")),
+                    }
+                    i += 4;
+                }
+                _ => return Err(error_at(i + 1, "'\\\\', '\\\"' or '\\x'")),
+            },
+            Some(_) => {
+                // anything else stands for itself, one byte at a time.
+                out.push(code[i]);
+                i += 1;
             }
-        } else {
-            byte_count += 1;
-            i += 1;
         }
     }
 
-    return byte_count;
+    if i + 1 != code.len() {
+        return Err(error_at(i + 1, "the end of the line"));
+    }
+    return Ok(out);
 }
 
-fn encode_str_and_count_bytes(value: &str) -> usize {
-    // two mandatory double quotes.
-    let mut count = 2;
-
-    for chr in value.chars() {
-        count += match chr {
-            '\\' | '"' => 2,
-            _ => 1,
-        };
+// the shortest literal for "bytes". anything that is not printable ascii
+// becomes "\xNN", so decoding it again always gives the same bytes.
+fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 2);
+    out.push('"');
+    for byte in bytes {
+        match byte {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b' '..=b'~' => out.push(*byte as char),
+            _ => out.push_str(&format!("\\x{:02x}", byte)),
+        }
     }
+    out.push('"');
+    return out;
+}
+
+fn part1(literals: &[(&str, Vec<u8>)]) {
+    let code_len: usize = literals.iter().map(|(code, _)| code.len()).sum();
+    let memory_len: usize = literals.iter().map(|(_, bytes)| bytes.len()).sum();
+    println!("Day 8A: {:?}", code_len - memory_len);
+}
 
-    return count;
+fn part2(literals: &[(&str, Vec<u8>)]) {
+    let code_len: usize = literals.iter().map(|(code, _)| code.len()).sum();
+    let encoded_len: usize = literals
+        .iter()
+        .map(|(code, _)| encode(code.as_bytes()).len())
+        .sum();
+    println!("Day 8B: {:?}", encoded_len - code_len);
 }
 
-fn part1(lines: &[&str]) {
-    let mut char_len: usize = 0;
-    let mut memory_len: usize = 0;
+mod tests {
+    use crate::day08::*;
+
+    const EXAMPLE_INPUT: [&'static str; 4] = [r#""""#, r#""abc""#, r#""aaa\"aaa""#, r#""\x27""#];
 
-    for line in lines {
-        char_len += line.trim().len();
-        memory_len += count_bytes_in_encoded_str(line);
+    #[test]
+    fn decode_the_examples() {
+        let decoded: Vec<Vec<u8>> = EXAMPLE_INPUT
+            .iter()
+            .map(|l| decode(1, l).unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                b"".to_vec(),
+                b"abc".to_vec(),
+                b"aaa\"aaa".to_vec(),
+                b"'".to_vec()
+            ],
+            decoded
+        );
+        assert_eq!(b"\\\xff".to_vec(), decode(1, r#""\\\xFF""#).unwrap());
     }
-    println!("Day 8A: {:?}", char_len - memory_len);
-}
 
-fn part2(lines: &[&str]) {
-    let mut char_len: usize = 0;
-    let mut encoded_len: usize = 0;
+    #[test]
+    fn encode_the_examples() {
+        let encoded: Vec<String> = EXAMPLE_INPUT.iter().map(|l| encode(l.as_bytes())).collect();
+        assert_eq!(
+            vec![
+                r#""\"\"""#,
+                r#""\"abc\"""#,
+                r#""\"aaa\\\"aaa\"""#,
+                r#""\"\\x27\"""#
+            ],
+            encoded
+        );
+        assert_eq!(r#""\x00\x7f\xe9""#, encode(&[0, 127, 233]));
+    }
+
+    #[test]
+    fn round_trip() {
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(all, decode(1, &encode(&all)).unwrap());
+
+        let input = std::fs::read_to_string("src/day08_input.txt").unwrap();
+        for (n, line) in numbered_lines(&input) {
+            let bytes = decode(n, line.trim()).unwrap();
+            assert_eq!(bytes, decode(n, &encode(&bytes)).unwrap());
+        }
+    }
 
-    for line in lines {
-        char_len += line.trim().len();
-        encoded_len += encode_str_and_count_bytes(line);
+    #[test]
+    fn point_at_malformed_escapes() {
+        let check = |literal: &str, column: usize, expected: &str| {
+            let err = decode(3, literal).unwrap_err();
+            assert_eq!(
+                (3, column, expected.to_string()),
+                (err.line, err.column, err.expected),
+                "{}",
+                literal
+            );
+        };
+        check(r#"abc""#, 1, "'\"'");
+        check(r#""abc"#, 5, "a closing '\"'");
+        check(r#""ab\q""#, 5, "'\\\\', '\\\"' or '\\x'");
+        check(r#""ab\x4g""#, 6, "two hex digits");
+        check(r#""ab\x4"#, 6, "two hex digits");
+        check(r#""ab\"#, 5, "'\\\\', '\\\"' or '\\x'");
+        check(r#""ab"cd""#, 5, "the end of the line");
     }
-    println!("Day 8B: {:?}", encoded_len - char_len);
 }
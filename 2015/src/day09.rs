@@ -1,5 +1,8 @@
+use crate::dot;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+use crate::tsp::{self, Goal, Shape, Tour, UNREACHABLE};
 use std::collections::HashMap;
-use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 struct Distance {
@@ -10,27 +13,28 @@ struct Distance {
 
 type DistanceMap = HashMap<String, Vec<Distance>>;
 
-const CITY_COUNT: usize = 8;
-
 pub fn run() {
     let input = std::fs::read_to_string("src/day09_input.txt").unwrap();
-    let dists = parse_distances(&input);
-
-    assert_eq!(CITY_COUNT, dists.len());
+    let dists = match parse_distances(&input) {
+        Ok(dists) => dists,
+        Err(err) => {
+            println!("Day 9: {}", err);
+            return;
+        }
+    };
 
-    let routes = get_routes_and_distances(&dists);
+    if dot::is_enabled() {
+        dot::save("day09_distances.dot", &distances_to_graph(&dists));
+    }
 
-    part1(&routes);
-    part2(&routes);
+    part1(&dists);
+    part2(&dists);
 }
 
-fn parse_distances(input: &str) -> DistanceMap {
+fn parse_distances(input: &str) -> Result<DistanceMap, ParseError> {
     let mut out = DistanceMap::new();
-    for line in input.trim().lines() {
-        let chunks: Vec<&str> = line.split_whitespace().collect();
-        let from = chunks[0];
-        let to = chunks[2];
-        let value: i64 = chunks[4].parse().unwrap();
+    for (n, line) in numbered_lines(input) {
+        let (from, to, value) = scan!((n, line), "{} to {} = {}", &str, &str, i64)?;
 
         let a = out.entry(from.to_string()).or_insert(Vec::new());
         a.push(Distance {
@@ -46,70 +50,123 @@ fn parse_distances(input: &str) -> DistanceMap {
             value,
         });
     }
-    return out;
+    return Ok(out);
 }
 
-fn make_routes(stack: &[String], dists: &DistanceMap, out: &mut Vec<[String; CITY_COUNT]>) {
-    if stack.len() == dists.len() {
-        let thing: [String; CITY_COUNT] = stack.to_owned().try_into().unwrap();
-        out.push(thing);
-        return;
-    }
+// one edge per pair of cities, sorted so that the output is stable.
+fn distances_to_graph(dists: &DistanceMap) -> dot::Graph {
+    let mut graph = dot::Graph::undirected("day09");
+    let mut cities: Vec<&String> = dists.keys().collect();
+    cities.sort();
 
-    if stack.is_empty() {
-        for (k, _) in dists {
-            let s = &[k.clone()][..];
-            make_routes(s, dists, out)
-        }
-        return;
+    for city in &cities {
+        graph.node(city, &[]);
     }
 
-    let last = &stack[stack.len() - 1];
-    let next = &dists[last];
+    for city in &cities {
+        let mut roads: Vec<&Distance> = dists[*city].iter().filter(|d| d.from < d.to).collect();
+        roads.sort_by(|a, b| a.to.cmp(&b.to));
 
-    for n in next {
-        if stack.contains(&n.to) {
-            continue;
+        for d in roads {
+            graph.edge(&d.from, &d.to, &[("label", &d.value.to_string())]);
         }
-
-        let mut s = stack.to_vec();
-        s.push(n.to.clone());
-        make_routes(&s, dists, out);
     }
+
+    return graph;
 }
 
-fn get_routes_and_distances(dists: &DistanceMap) -> Vec<Distance> {
-    let mut out = Vec::new();
-    make_routes(&[], dists, &mut out);
+// the cities in a stable order, and the distance between each pair of them.
+// cities without a road between them cannot follow each other on a route.
+fn make_costs(dists: &DistanceMap) -> (Vec<&String>, Vec<Vec<i64>>) {
+    let mut cities: Vec<&String> = dists.keys().collect();
+    cities.sort();
 
-    let mut distances: Vec<Distance> = out
+    let costs = cities
         .iter()
-        .map(|route| {
-            let mut dist = 0;
-
-            for i in 0..(route.len() - 1) {
-                let from = &route[i];
-                let to = &route[i + 1];
-                let found = dists[from].iter().find(|d| d.to == *to);
-                dist += found.unwrap().value;
-            }
-
-            return Distance {
-                from: route[0].clone(),
-                to: route.last().unwrap().clone(),
-                value: dist,
-            };
+        .map(|from| {
+            return cities
+                .iter()
+                .map(|to| match dists[*from].iter().find(|d| d.to == **to) {
+                    Some(d) => d.value,
+                    None => UNREACHABLE,
+                })
+                .collect();
         })
         .collect();
 
-    distances.sort_by(|a, b| a.value.cmp(&b.value));
+    return (cities, costs);
+}
 
-    return distances;
+fn find_route(dists: &DistanceMap, goal: Goal) -> Option<(Vec<&String>, Tour)> {
+    let (cities, costs) = make_costs(dists);
+    let tour = tsp::solve(&costs, Shape::Path, goal)?;
+    let route = tour.order.iter().map(|i| cities[*i]).collect();
+    return Some((route, tour));
 }
 
-fn part1(dists: &[Distance]) {
-    println!("Day 9A: {:?}", dists[0]);
+fn format_route(route: &[&String]) -> String {
+    return route
+        .iter()
+        .map(|c| c.as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ");
 }
-fn part2(dists: &[Distance]) {
-    println!("Day 9B: {:?}", dists.last().unwrap());
+
+fn part1(dists: &DistanceMap) {
+    match find_route(dists, Goal::Shortest) {
+        Some((route, tour)) => println!("Day 9A: {} ({})", tour.cost, format_route(&route)),
+        None => println!("Day 9A: no route visits every city"),
+    }
+}
+
+fn part2(dists: &DistanceMap) {
+    match find_route(dists, Goal::Longest) {
+        Some((route, tour)) => println!("Day 9B: {} ({})", tour.cost, format_route(&route)),
+        None => println!("Day 9B: no route visits every city"),
+    }
+}
+
+mod tests {
+    use crate::day09::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+London to Dublin = 464
+London to Belfast = 518
+Dublin to Belfast = 141
+"#;
+
+    #[test]
+    fn route_the_example() {
+        let dists = parse_distances(EXAMPLE_INPUT).unwrap();
+        let (shortest, tour) = find_route(&dists, Goal::Shortest).unwrap();
+        assert_eq!(605, tour.cost);
+        assert_eq!("London -> Dublin -> Belfast", format_route(&shortest));
+
+        let (longest, tour) = find_route(&dists, Goal::Longest).unwrap();
+        assert_eq!(982, tour.cost);
+        assert_eq!("Dublin -> London -> Belfast", format_route(&longest));
+    }
+
+    #[test]
+    fn route_without_a_road_between_two_cities() {
+        // the missing road between london and belfast is not a free one.
+        let dists = parse_distances("London to Dublin = 464\nDublin to Belfast = 141").unwrap();
+        let (route, tour) = find_route(&dists, Goal::Shortest).unwrap();
+        assert_eq!(605, tour.cost);
+        let route = format_route(&route);
+        assert!(route == "London -> Dublin -> Belfast" || route == "Belfast -> Dublin -> London");
+
+        let dists = parse_distances("London to Dublin = 464\nParis to Belfast = 141").unwrap();
+        assert!(find_route(&dists, Goal::Longest).is_none());
+    }
+
+    #[test]
+    fn draw_the_example() {
+        let dists = parse_distances(EXAMPLE_INPUT).unwrap();
+
+        assert_eq!(
+            include_str!("day09_example.dot"),
+            distances_to_graph(&dists).to_dot()
+        );
+    }
 }
@@ -1,3 +1,7 @@
+use crate::bignum::BigUint;
+use crate::memo::Memo;
+use std::collections::HashMap;
+
 pub fn run() {
     let input = "3113322113";
 
@@ -30,20 +34,162 @@ fn look_and_say(input: &str) -> String {
     return out;
 }
 
-fn part1(input: &str) {
-    let mut yee = input.to_string();
-    for _ in 0..40 {
-        yee = look_and_say(&yee);
+// how many rounds the start of the right side is followed for when deciding
+// on a split. it settles into a short cycle well before that.
+const SPLIT_CHECK_ROUNDS: usize = 12;
+
+// conway showed that a sequence splits into parts that never touch again,
+// and that after a few rounds those parts are always among 92 "elements".
+// a cut between two digits holds when the digit at the start of the right
+// side never becomes the last digit of the left side, which never changes.
+fn can_split(left_last: char, right: &str) -> bool {
+    let mut right = right.to_string();
+    for _ in 0..SPLIT_CHECK_ROUNDS {
+        if right.starts_with(left_last) {
+            return false;
+        }
+        right = look_and_say(&right);
+    }
+    return true;
+}
+
+fn split_into_atoms(input: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let bytes = input.as_bytes();
+
+    for i in 1..bytes.len() {
+        if bytes[i - 1] != bytes[i] && can_split(bytes[i - 1] as char, &input[i..]) {
+            out.push(input[start..i].to_string());
+            start = i;
+        }
+    }
+    if start < input.len() {
+        out.push(input[start..].to_string());
+    }
+
+    return out;
+}
+
+// an atom evolves on its own, so what it turns into only has to be worked out once.
+fn decay(atom: &str, memo: &mut Memo<String, Vec<String>>) -> Vec<String> {
+    return memo.get_or_compute(atom.to_string(), |_| split_into_atoms(&look_and_say(atom)));
+}
+
+// follows how many of each atom there are instead of the sequence itself.
+fn count_atoms(
+    input: &str,
+    rounds: usize,
+    memo: &mut Memo<String, Vec<String>>,
+) -> HashMap<String, BigUint> {
+    let mut counts: HashMap<String, BigUint> = HashMap::new();
+    for atom in split_into_atoms(input) {
+        counts.entry(atom).or_default().add(&BigUint::from_u64(1));
+    }
+
+    for _ in 0..rounds {
+        let mut next: HashMap<String, BigUint> = HashMap::new();
+        for (atom, count) in &counts {
+            for product in decay(atom, memo) {
+                next.entry(product).or_default().add(count);
+            }
+        }
+        counts = next;
+    }
+
+    return counts;
+}
+
+fn length_after(input: &str, rounds: usize, memo: &mut Memo<String, Vec<String>>) -> BigUint {
+    let mut total = BigUint::zero();
+    for (atom, count) in count_atoms(input, rounds, memo) {
+        total.add(&count.mul_u32(atom.len() as u32));
     }
+    return total;
+}
 
-    println!("Day 10A: {}", yee.len());
+fn part1(input: &str) {
+    let mut memo = Memo::new();
+    println!("Day 10A: {}", length_after(input, 40, &mut memo));
 }
 
 fn part2(input: &str) {
-    let mut yee = input.to_string();
-    for _ in 0..50 {
-        yee = look_and_say(&yee);
+    let mut memo = Memo::new();
+    println!("Day 10B: {}", length_after(input, 50, &mut memo));
+}
+
+mod tests {
+    use crate::day10::*;
+
+    #[test]
+    fn say_the_examples() {
+        let mut s = "1".to_string();
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            s = look_and_say(&s);
+            seen.push(s.clone());
+        }
+
+        assert_eq!(vec!["11", "21", "1211", "111221", "312211"], seen);
+    }
+
+    #[test]
+    fn split_into_elements() {
+        // radon decays into holmium and astatine.
+        assert_eq!(
+            vec!["1321132", "1322113"],
+            split_into_atoms("13211321322113")
+        );
+        assert_eq!(vec!["3113"], split_into_atoms("3113"));
+        assert_eq!(vec!["22"], split_into_atoms("22"));
     }
 
-    println!("Day 10B: {}", yee.len());
+    #[test]
+    fn match_the_string_lengths() {
+        let mut memo = Memo::new();
+
+        for input in ["1", "3113322113", "1113222113"] {
+            let mut s = input.to_string();
+            for rounds in 0..=30 {
+                assert_eq!(
+                    Some(s.len() as u64),
+                    length_after(input, rounds, &mut memo).to_u64(),
+                    "{}",
+                    input
+                );
+                s = look_and_say(&s);
+            }
+        }
+    }
+
+    #[test]
+    fn end_up_with_the_92_elements() {
+        let mut memo = Memo::new();
+        let counts = count_atoms("1", 100, &mut memo);
+
+        assert_eq!(92, counts.len());
+        assert_eq!(Some(42), counts.keys().map(|atom| atom.len()).max());
+
+        // the elements only ever decay into each other.
+        for atom in counts.keys() {
+            assert!(decay(atom, &mut memo)
+                .iter()
+                .all(|product| counts.contains_key(product)));
+        }
+    }
+
+    #[test]
+    fn grow_by_conways_constant() {
+        let mut memo = Memo::new();
+        let a: f64 = length_after("1", 1000, &mut memo)
+            .to_string()
+            .parse()
+            .unwrap();
+        let b: f64 = length_after("1", 1001, &mut memo)
+            .to_string()
+            .parse()
+            .unwrap();
+
+        assert!(((b / a) - 1.303577269).abs() < 1e-6);
+    }
 }
@@ -1,80 +1,51 @@
-pub fn run() {
-    let mut pwd = "hxbxwxba".to_string();
-    let pairs = generate_pairs();
-    let pairs_as_slice: Vec<&str> = pairs.iter().map(|s| s.as_str()).collect();
-
-    loop {
-        pwd = increment_password(&pwd);
-        if is_valid_password(&pwd, &pairs_as_slice) {
-            println!("Day 11A: {}", pwd);
-            break;
-        }
-    }
-
-    loop {
-        pwd = increment_password(&pwd);
-        if is_valid_password(&pwd, &pairs_as_slice) {
-            println!("Day 11B: {}", pwd);
-            break;
-        }
-    }
-}
-
-fn increment_password(pwd: &str) -> String {
-    let mut chars: Vec<char> = pwd.chars().collect();
-    let mut did_wrap = true;
-    let mut i = chars.len() - 1;
-
-    while did_wrap {
-        let mut byte = (chars[i] as u8) + 1;
-        did_wrap = byte > 'z' as u8;
-
-        if did_wrap {
-            byte = 'a' as u8;
-        }
+use crate::password::{Forbidden, Length, Pairs, Policy, Straight};
 
-        chars[i] = byte as char;
-
-        if i == 0 {
-            i = chars.len() - 1;
-        } else {
-            i -= 1;
-        }
-    }
+pub fn run() {
+    let policy = santas_policy();
+    let mut next = policy.after("hxbxwxba");
 
-    return String::from_iter(chars);
+    println!("Day 11A: {}", next.next().unwrap());
+    println!("Day 11B: {}", next.next().unwrap());
 }
 
-fn generate_pairs() -> Vec<String> {
-    return ('a'..='z').map(|ch| format!("{}{}", ch, ch)).collect();
+// eight letters with a straight of three, no "i", "o" or "l", and two different pairs.
+fn santas_policy() -> Policy {
+    return Policy::new()
+        .rule(Length(8))
+        .rule(Straight(3))
+        .rule(Forbidden("iol"))
+        .rule(Pairs(2));
 }
 
-const INVALID_CHARS: [char; 3] = ['i', 'o', 'l'];
-
-fn is_valid_password(pwd: &str, pairs: &[&str]) -> bool {
-    if pwd.contains(&INVALID_CHARS[..]) {
-        return false;
-    }
+mod tests {
+    use crate::day11::*;
 
-    let contains_pair_count = pairs
-        .iter()
-        .fold(0, |carry, p| carry + i32::from(pwd.contains(p)));
+    #[test]
+    fn check_the_examples() {
+        let policy = santas_policy();
 
-    if contains_pair_count < 2 {
-        return false;
+        assert!(!policy.is_valid("hijklmmn"));
+        assert!(!policy.is_valid("abbceffg"));
+        assert!(!policy.is_valid("abbcegjk"));
+        assert!(policy.is_valid("abcdffaa"));
+        assert!(policy.is_valid("ghjaabcc"));
     }
 
-    let chars: Vec<char> = pwd.chars().collect();
-
-    for i in 0..(chars.len() - 2) {
-        let a = chars[i] as u8;
-        let b = chars[i + 1] as u8;
-        let c = chars[i + 2] as u8;
-
-        if a == (b - 1) && b == (c - 1) {
-            return true;
-        }
+    #[test]
+    fn find_the_next_passwords() {
+        let policy = santas_policy();
+
+        assert_eq!(
+            Some("abcdffaa".to_string()),
+            policy.after("abcdefgh").next()
+        );
+        assert_eq!(
+            Some("ghjaabcc".to_string()),
+            policy.after("ghijklmn").next()
+        );
+        assert_eq!(
+            vec!["hxbxxyzz", "hxcaabcc"],
+            policy.after("hxbxwxba").take(2).collect::<Vec<_>>()
+        );
     }
-
-    return false;
 }
@@ -1,3 +1,7 @@
+use crate::dot;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+use crate::tsp::{self, Goal, Shape};
 use std::collections::HashMap;
 
 type Seatings = HashMap<String, Vec<Seating>>;
@@ -11,25 +15,39 @@ struct Seating {
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day13_input.txt").unwrap();
-    let lines: Vec<&str> = input.trim().lines().collect();
-    let s = parse_seatings(&lines);
+    let s = match parse_seatings(&input) {
+        Ok(s) => s,
+        Err(err) => {
+            println!("Day 13: {}", err);
+            return;
+        }
+    };
+    if dot::is_enabled() {
+        dot::save("day13_happiness.dot", &seatings_to_graph(&s));
+    }
+
     part1(&s);
 
-    let mut s = parse_seatings(&lines);
+    let mut s = s;
     s.insert("Helmut".to_string(), Vec::new());
     part2(&s);
 }
 
-fn parse_seatings(lines: &[&str]) -> Seatings {
+fn parse_seatings(input: &str) -> Result<Seatings, ParseError> {
     let mut map = Seatings::new();
-    for line in lines {
-        let parts: Vec<&str> = line.trim_matches(&['.'][..]).split_whitespace().collect();
-        let name_a = parts[0];
-        let name_b = parts[10];
-        let value = if parts[2] == "gain" {
-            parts[3].parse().unwrap()
-        } else {
-            -parts[3].parse::<i64>().unwrap()
+    for (n, line) in numbered_lines(input) {
+        let (name_a, direction, amount, name_b) = scan!(
+            (n, line),
+            "{} would {} {} happiness units by sitting next to {}.",
+            &str,
+            &str,
+            i64,
+            &str
+        )?;
+        let value = match direction {
+            "gain" => amount,
+            "lose" => -amount,
+            _ => return Err(ParseError::at_token(n, line, direction, "'gain' or 'lose'")),
         };
 
         let e = map.entry(name_a.to_string()).or_insert(Vec::new());
@@ -39,63 +57,125 @@ fn parse_seatings(lines: &[&str]) -> Seatings {
             value,
         })
     }
-    return map;
+    return Ok(map);
+}
+
+fn get_happiness(seatings: &Seatings, name: &str, next_to: &str) -> i64 {
+    return seatings[name]
+        .iter()
+        .find(|s| s.name_b == next_to)
+        .map(|s| s.value)
+        .unwrap_or(0);
 }
 
-fn make_permutations(stack: &[String], seatings: &Seatings, out: &mut Vec<Vec<String>>) {
-    if stack.len() == seatings.len() {
-        out.push(stack.to_vec());
+// sitting next to each other changes the happiness of both guests, so the
+// cost of an edge is the sum of both directions.
+fn make_costs(seatings: &Seatings) -> (Vec<&String>, Vec<Vec<i64>>) {
+    let mut names: Vec<&String> = seatings.keys().collect();
+    names.sort();
+
+    let costs = names
+        .iter()
+        .map(|a| {
+            return names
+                .iter()
+                .map(|b| get_happiness(seatings, a, b) + get_happiness(seatings, b, a))
+                .collect();
+        })
+        .collect();
+
+    return (names, costs);
+}
+
+// an edge from each guest to each neighbour, labelled with the change in happiness.
+fn seatings_to_graph(seatings: &Seatings) -> dot::Graph {
+    let mut graph = dot::Graph::directed("day13");
+    let mut names: Vec<&String> = seatings.keys().collect();
+    names.sort();
+
+    for name in &names {
+        graph.node(name, &[]);
     }
 
-    for (name, _) in seatings {
-        if stack.contains(name) {
-            continue;
+    for name in &names {
+        let mut neighbours: Vec<&Seating> = seatings[*name].iter().collect();
+        neighbours.sort_by(|a, b| a.name_b.cmp(&b.name_b));
+
+        for s in neighbours {
+            let color = if s.value < 0 { "red" } else { "darkgreen" };
+            graph.edge(
+                &s.name_a,
+                &s.name_b,
+                &[("label", &s.value.to_string()), ("color", color)],
+            );
         }
-        let mut s = stack.to_vec();
-        s.push(name.to_string());
-        make_permutations(&s, seatings, out);
     }
-}
 
-fn get_score(stack: &[String], seatings: &Seatings) -> i64 {
-    let mut score = 0;
-
-    for i in 0..stack.len() {
-        let left_index = if i == 0 { stack.len() - 1 } else { i - 1 };
-        let right_index = if i == stack.len() - 1 { 0 } else { i + 1 };
-        let name = &stack[i];
-
-        score += seatings[name]
-            .iter()
-            .find(|s| s.name_b == stack[left_index])
-            .map(|s| s.value)
-            .unwrap_or(0);
-
-        score += seatings[name]
-            .iter()
-            .find(|s| s.name_b == stack[right_index])
-            .map(|s| s.value)
-            .unwrap_or(0);
-    }
-    return score;
+    return graph;
 }
 
-fn part1(seatings: &Seatings) {
-    let mut out = Vec::new();
-    make_permutations(&[], &seatings, &mut out);
+// the guests going around the table, and the total change in happiness.
+fn find_best_seating(seatings: &Seatings) -> (Vec<&String>, i64) {
+    let (names, costs) = make_costs(seatings);
+    // anyone can sit next to anyone, so there is always a seating.
+    let tour = tsp::solve(&costs, Shape::Cycle, Goal::Longest).unwrap();
+    let order = tour.order.iter().map(|i| names[*i]).collect();
+    return (order, tour.cost);
+}
 
-    let mut scores: Vec<i64> = out.iter().map(|s| get_score(s, &seatings)).collect();
-    scores.sort();
+fn format_seating(order: &[&String]) -> String {
+    return order
+        .iter()
+        .map(|n| n.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+}
 
-    println!("Day 13A: {:?}", scores.last().unwrap());
+fn part1(seatings: &Seatings) {
+    let (order, score) = find_best_seating(seatings);
+    println!("Day 13A: {} ({})", score, format_seating(&order));
 }
 
 fn part2(seatings: &Seatings) {
-    let mut out = Vec::new();
-    make_permutations(&[], &seatings, &mut out);
+    let (order, score) = find_best_seating(seatings);
+    println!("Day 13B: {} ({})", score, format_seating(&order));
+}
 
-    let mut scores: Vec<i64> = out.iter().map(|s| get_score(s, &seatings)).collect();
-    scores.sort();
+mod tests {
+    use crate::day13::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+Alice would gain 54 happiness units by sitting next to Bob.
+Alice would lose 79 happiness units by sitting next to Carol.
+Alice would lose 2 happiness units by sitting next to David.
+Bob would gain 83 happiness units by sitting next to Alice.
+Bob would lose 7 happiness units by sitting next to Carol.
+Bob would lose 63 happiness units by sitting next to David.
+Carol would lose 62 happiness units by sitting next to Alice.
+Carol would gain 60 happiness units by sitting next to Bob.
+Carol would gain 55 happiness units by sitting next to David.
+David would gain 46 happiness units by sitting next to Alice.
+David would lose 7 happiness units by sitting next to Bob.
+David would gain 41 happiness units by sitting next to Carol.
+"#;
+
+    #[test]
+    fn seat_the_example() {
+        let seatings = parse_seatings(EXAMPLE_INPUT).unwrap();
+
+        let (order, score) = find_best_seating(&seatings);
+
+        assert_eq!(330, score);
+        assert_eq!("Alice, David, Carol, Bob", format_seating(&order));
+    }
 
-    println!("Day 13B: {:?}", scores.last().unwrap());
+    #[test]
+    fn draw_the_example() {
+        let seatings = parse_seatings(EXAMPLE_INPUT).unwrap();
+
+        assert_eq!(
+            include_str!("day13_example.dot"),
+            seatings_to_graph(&seatings).to_dot()
+        );
+    }
 }
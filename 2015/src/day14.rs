@@ -1,8 +1,5 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum State {
-    Moving(i64),
-    Resting(i64),
-}
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 
 #[derive(Debug, Clone, Copy)]
 struct Reindeer<'a> {
@@ -10,81 +7,246 @@ struct Reindeer<'a> {
     speed: i64,
     can_move_seconds: i64,
     must_rest_seconds: i64,
-    state: State,
-    distance_traveled: i64,
-    score: i64,
 }
 
+impl Reindeer<'_> {
+    // every reindeer repeats the same flight and rest, so whole cycles can
+    // be counted at once and only the last one needs a closer look.
+    fn distance_at(&self, seconds: i64) -> i64 {
+        let cycle = self.can_move_seconds + self.must_rest_seconds;
+        let full_cycles = seconds / cycle;
+        let moving_in_last = std::cmp::min(seconds % cycle, self.can_move_seconds);
+
+        return (full_cycles * self.can_move_seconds + moving_in_last) * self.speed;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Standing<'a> {
+    name: &'a str,
+    distance: i64,
+    points: i64,
+}
+
+// hands out a point every second to whoever is in the lead. when several
+// reindeer share the lead, each of them gets a point.
+struct Race<'a> {
+    deer: &'a [Reindeer<'a>],
+    points: Vec<i64>,
+    seconds: i64,
+}
+
+impl<'a> Race<'a> {
+    fn new(deer: &'a [Reindeer<'a>]) -> Self {
+        return Self {
+            deer,
+            points: vec![0; deer.len()],
+            seconds: 0,
+        };
+    }
+
+    fn advance_to(&mut self, seconds: i64) {
+        assert!(seconds >= self.seconds, "The race cannot go back in time.");
+
+        while self.seconds < seconds {
+            self.seconds += 1;
+
+            let distances: Vec<i64> = self
+                .deer
+                .iter()
+                .map(|d| d.distance_at(self.seconds))
+                .collect();
+            let lead = distances.iter().max().copied().unwrap_or(0);
+
+            for (i, distance) in distances.iter().enumerate() {
+                if *distance == lead {
+                    self.points[i] += 1;
+                }
+            }
+        }
+    }
+
+    // the most points first, then the furthest along, then by name.
+    fn leaderboard(&self) -> Vec<Standing<'a>> {
+        let mut out: Vec<Standing> = self
+            .deer
+            .iter()
+            .zip(self.points.iter())
+            .map(|(d, points)| Standing {
+                name: d.name,
+                distance: d.distance_at(self.seconds),
+                points: *points,
+            })
+            .collect();
+
+        out.sort_by(|a, b| {
+            return b
+                .points
+                .cmp(&a.points)
+                .then(b.distance.cmp(&a.distance))
+                .then(a.name.cmp(b.name));
+        });
+
+        return out;
+    }
+}
+
+const RACE_SECONDS: i64 = 2503;
+
 pub fn run() {
     let input = std::fs::read_to_string("src/day14_input.txt").unwrap();
-    let deer = parse_deer(&input);
+    let deer = match parse_deer(&input) {
+        Ok(deer) => deer,
+        Err(err) => {
+            println!("Day 14: {}", err);
+            return;
+        }
+    };
     part1(&deer);
+    part2(&deer);
 }
 
-fn parse_deer(input: &str) -> Vec<Reindeer> {
+fn parse_deer(input: &str) -> Result<Vec<Reindeer>, ParseError> {
     let mut deer = Vec::new();
 
-    for line in input.trim().lines() {
-        let chunks: Vec<&str> = line.trim().split_whitespace().collect();
+    for (n, line) in numbered_lines(input) {
+        let (name, speed, can_move_seconds, must_rest_seconds) = scan!(
+            (n, line),
+            "{} can fly {} km/s for {} seconds, but then must rest for {} seconds.",
+            &str,
+            i64,
+            i64,
+            i64
+        )?;
         let d = Reindeer {
-            name: chunks[0],
-            speed: chunks[3].parse().unwrap(),
-            can_move_seconds: chunks[6].parse().unwrap(),
-            must_rest_seconds: chunks[chunks.len() - 2].parse().unwrap(),
-            state: State::Moving(0),
-            distance_traveled: 0,
-            score: 0,
+            name,
+            speed,
+            can_move_seconds,
+            must_rest_seconds,
         };
         deer.push(d);
     }
 
-    return deer;
+    return Ok(deer);
+}
+
+// every reindeer that shares the best value, so a tie shows up as one.
+fn winners(standings: &[Standing], value: fn(&Standing) -> i64) -> (String, i64) {
+    let best = standings.iter().map(value).max().unwrap();
+    let names: Vec<&str> = standings
+        .iter()
+        .filter(|s| value(s) == best)
+        .map(|s| s.name)
+        .collect();
+    return (names.join(", "), best);
 }
 
 fn part1(deer: &[Reindeer]) {
-    let mut deer: Vec<Reindeer> = deer.to_vec();
-
-    for _ in 1..=2503 {
-        for d in deer.iter_mut() {
-            match d.state {
-                State::Moving(s) => {
-                    if s == d.can_move_seconds {
-                        d.state = State::Resting(1);
-                    } else {
-                        d.state = State::Moving(s + 1);
-                        d.distance_traveled += d.speed;
-                    }
-                }
-                State::Resting(s) => {
-                    if s == d.must_rest_seconds {
-                        d.state = State::Moving(1);
-                        d.distance_traveled += d.speed;
-                    } else {
-                        d.state = State::Resting(s + 1);
-                    }
+    let mut race = Race::new(deer);
+    race.advance_to(RACE_SECONDS);
+    let (names, distance) = winners(&race.leaderboard(), |s| s.distance);
+
+    println!("Day 14A: {} = {}", names, distance);
+}
+
+fn part2(deer: &[Reindeer]) {
+    let mut race = Race::new(deer);
+    race.advance_to(RACE_SECONDS);
+    let (names, points) = winners(&race.leaderboard(), |s| s.points);
+
+    println!("Day 14B: {} = {}", names, points);
+}
+
+mod tests {
+    use crate::day14::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
+Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds.
+"#;
+
+    #[test]
+    fn match_a_second_by_second_flight() {
+        let deer = parse_deer(EXAMPLE_INPUT).unwrap();
+
+        for d in &deer {
+            let mut distance = 0;
+            for second in 1..=1000 {
+                let cycle_second = (second - 1) % (d.can_move_seconds + d.must_rest_seconds);
+                if cycle_second < d.can_move_seconds {
+                    distance += d.speed;
                 }
+                assert_eq!(distance, d.distance_at(second), "{} at {}", d.name, second);
             }
         }
+        assert_eq!(0, deer[0].distance_at(0));
+    }
 
-        let winner_distance = deer.iter().map(|d| d.distance_traveled).max().unwrap();
-        deer.iter_mut()
-            .filter(|d| d.distance_traveled == winner_distance)
-            .for_each(|d| d.score += 1);
+    #[test]
+    fn race_the_example() {
+        let deer = parse_deer(EXAMPLE_INPUT).unwrap();
+        let mut race = Race::new(&deer);
+
+        race.advance_to(1);
+        assert_eq!(
+            vec![
+                Standing {
+                    name: "Dancer",
+                    distance: 16,
+                    points: 1
+                },
+                Standing {
+                    name: "Comet",
+                    distance: 14,
+                    points: 0
+                },
+            ],
+            race.leaderboard()
+        );
+
+        race.advance_to(140);
+        assert_eq!(
+            ("Dancer", 139),
+            (race.leaderboard()[0].name, race.leaderboard()[0].points)
+        );
+
+        race.advance_to(1000);
+        let board = race.leaderboard();
+        assert_eq!(("Dancer".to_string(), 689), winners(&board, |s| s.points));
+        assert_eq!(("Comet".to_string(), 1120), winners(&board, |s| s.distance));
+        assert_eq!(
+            Standing {
+                name: "Comet",
+                distance: 1120,
+                points: 312
+            },
+            board[1]
+        );
     }
 
-    let winner_by_distance = deer
-        .iter()
-        .max_by(|a, b| a.distance_traveled.cmp(&b.distance_traveled))
-        .unwrap();
-
-    let winner_by_score = deer.iter().max_by(|a, b| a.score.cmp(&b.score)).unwrap();
-
-    println!(
-        "Day 14A: {} = {}",
-        winner_by_distance.name, winner_by_distance.distance_traveled
-    );
-    println!(
-        "Day 14B: {} = {}",
-        winner_by_score.name, winner_by_score.score
-    );
+    #[test]
+    fn share_points_on_a_tie() {
+        let input = format!(
+            "{}{}",
+            EXAMPLE_INPUT,
+            EXAMPLE_INPUT.replace("Comet", "Cupid")
+        );
+        let deer = parse_deer(&input).unwrap();
+        let mut race = Race::new(&deer);
+        race.advance_to(1000);
+        let board = race.leaderboard();
+
+        assert_eq!(
+            ("Comet, Cupid".to_string(), 1120),
+            winners(&board, |s| s.distance)
+        );
+        assert_eq!(
+            vec![312, 312],
+            board
+                .iter()
+                .filter(|s| s.distance == 1120)
+                .map(|s| s.points)
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,9 +1,18 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+
 pub fn run() {
     let input = std::fs::read_to_string("src/day15_input.txt").unwrap();
-    let ingredients = parse(&input);
+    let ingredients = match parse(&input) {
+        Ok(ingredients) => ingredients,
+        Err(err) => {
+            println!("Day 15: {}", err);
+            return;
+        }
+    };
 
-    println!("Day 15A: {}", solve_the_thing(&ingredients, None));
-    println!("Day 15B: {}", solve_the_thing(&ingredients, Some(500)));
+    part("A", &ingredients, None);
+    part("B", &ingredients, Some(500));
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -16,87 +25,193 @@ struct Ingredient {
     calories: i64,
 }
 
-fn parse(input: &str) -> Vec<Ingredient> {
+fn parse(input: &str) -> Result<Vec<Ingredient>, ParseError> {
     let mut out = Vec::new();
 
-    for line in input.trim().lines() {
-        let chunks: Vec<&str> = line
-            .trim()
-            .split(&[':', ' ', ','][..])
-            .filter(|s| !s.is_empty())
-            .collect();
+    for (n, line) in numbered_lines(input) {
+        let (name, capacity, durability, flavor, texture, calories) = scan!(
+            (n, line),
+            "{}: capacity {}, durability {}, flavor {}, texture {}, calories {}",
+            String,
+            i64,
+            i64,
+            i64,
+            i64,
+            i64
+        )?;
         let ingr = Ingredient {
-            name: chunks[0].to_string(),
-            capacity: chunks[2].parse().unwrap(),
-            durability: chunks[4].parse().unwrap(),
-            flavor: chunks[6].parse().unwrap(),
-            texture: chunks[8].parse().unwrap(),
-            calories: chunks[10].parse().unwrap(),
+            name,
+            capacity,
+            durability,
+            flavor,
+            texture,
+            calories,
         };
         out.push(ingr);
     }
-    return out;
+    return Ok(out);
 }
 
-fn solve_the_thing(ingredients: &[Ingredient], with_calories: Option<i64>) -> i64 {
-    let mut best_cookie = 0;
+impl Ingredient {
+    fn properties(&self) -> [i64; PROPERTIES] {
+        return [self.capacity, self.durability, self.flavor, self.texture];
+    }
+}
 
-    for a in 0..=100 {
-        for b in 0..=100 {
-            for c in 0..=100 {
-                for d in 0..=100 {
-                    if a + b + c + d != 100 {
-                        continue;
-                    }
-                    let teaspoons = [a, b, c, d];
-                    let capacity: i64 = ingredients
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ingr)| ingr.capacity * teaspoons[i])
-                        .sum();
-                    let durability: i64 = ingredients
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ingr)| ingr.durability * teaspoons[i])
-                        .sum();
-                    let flavor: i64 = ingredients
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ingr)| ingr.flavor * teaspoons[i])
-                        .sum();
-                    let texture: i64 = ingredients
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ingr)| ingr.texture * teaspoons[i])
-                        .sum();
-                    let calories: i64 = ingredients
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ingr)| ingr.calories * teaspoons[i])
-                        .sum();
+// the properties that make up the score, so not the calories.
+const PROPERTIES: usize = 4;
+const TEASPOONS: i64 = 100;
 
-                    let score = [capacity, durability, flavor, texture]
-                        .map(|s| std::cmp::max(0, s))
-                        .iter()
-                        .product();
+#[derive(Debug, Clone, PartialEq)]
+struct Recipe {
+    teaspoons: Vec<i64>,
+    score: i64,
+}
 
-                    let has_correct_calorie_count =
-                        with_calories == None || with_calories == Some(calories);
+// the state of the search, with the ingredients picked so far.
+struct Search<'a> {
+    ingredients: &'a [Ingredient],
+    calories: Option<i64>,
+    // the highest property and calories still reachable from ingredient i on.
+    max_from: Vec<[i64; PROPERTIES]>,
+    calories_from: Vec<(i64, i64)>,
+    teaspoons: Vec<i64>,
+    best: Option<Recipe>,
+}
 
-                    if score > best_cookie && has_correct_calorie_count {
-                        best_cookie = score;
-                    }
-                }
+impl<'a> Search<'a> {
+    fn new(ingredients: &'a [Ingredient], calories: Option<i64>) -> Self {
+        let mut max_from = vec![[i64::MIN; PROPERTIES]; ingredients.len() + 1];
+        let mut calories_from = vec![(i64::MAX, i64::MIN); ingredients.len() + 1];
+        for i in (0..ingredients.len()).rev() {
+            let props = ingredients[i].properties();
+            for p in 0..PROPERTIES {
+                max_from[i][p] = std::cmp::max(max_from[i + 1][p], props[p]);
+            }
+            let (low, high) = calories_from[i + 1];
+            let cal = ingredients[i].calories;
+            calories_from[i] = (std::cmp::min(low, cal), std::cmp::max(high, cal));
+        }
+
+        return Self {
+            ingredients,
+            calories,
+            max_from,
+            calories_from,
+            teaspoons: Vec::with_capacity(ingredients.len()),
+            best: None,
+        };
+    }
+
+    fn visit(&mut self, left: i64, totals: [i64; PROPERTIES], calories: i64) {
+        let i = self.teaspoons.len();
+
+        // every property gets at most the best remaining ingredient for
+        // all the teaspoons that are left. when that is still not above
+        // zero, or not above the best score so far, this branch is done.
+        let mut bound = 1;
+        for p in 0..PROPERTIES {
+            let most = totals[p] + left * self.max_from[i][p];
+            if most <= 0 {
+                return;
+            }
+            bound *= most;
+        }
+        if self.best.as_ref().map_or(false, |b| bound <= b.score) {
+            return;
+        }
+
+        if let Some(target) = self.calories {
+            let (low, high) = self.calories_from[i];
+            if target < calories + left * low || target > calories + left * high {
+                return;
+            }
+        }
+
+        // with every ingredient picked nothing is left, so the bound is
+        // the score and the calories have to match exactly.
+        if i == self.ingredients.len() {
+            self.best = Some(Recipe {
+                teaspoons: self.teaspoons.clone(),
+                score: bound,
+            });
+            return;
+        }
+
+        // the last ingredient takes whatever is left.
+        let first = if i + 1 == self.ingredients.len() {
+            left
+        } else {
+            0
+        };
+        let props = self.ingredients[i].properties();
+        for amount in first..=left {
+            let mut next = totals;
+            for p in 0..PROPERTIES {
+                next[p] += props[p] * amount;
             }
+            self.teaspoons.push(amount);
+            self.visit(
+                left - amount,
+                next,
+                calories + self.ingredients[i].calories * amount,
+            );
+            self.teaspoons.pop();
         }
     }
+}
+
+// the best recipe with exactly "teaspoons" in total, and optionally an
+// exact number of calories. None when no recipe scores above zero.
+fn best_recipe(
+    ingredients: &[Ingredient],
+    teaspoons: i64,
+    calories: Option<i64>,
+) -> Option<Recipe> {
+    if ingredients.is_empty() {
+        return None;
+    }
+    let mut search = Search::new(ingredients, calories);
+    search.visit(teaspoons, [0; PROPERTIES], 0);
+    return search.best;
+}
 
-    return best_cookie;
+fn format_recipe(ingredients: &[Ingredient], recipe: &Recipe) -> String {
+    return ingredients
+        .iter()
+        .zip(&recipe.teaspoons)
+        .map(|(ingr, amount)| format!("{} {}", ingr.name, amount))
+        .collect::<Vec<_>>()
+        .join(", ");
+}
+
+fn part(name: &str, ingredients: &[Ingredient], calories: Option<i64>) {
+    match best_recipe(ingredients, TEASPOONS, calories) {
+        Some(recipe) => println!(
+            "Day 15{}: {} ({})",
+            name,
+            recipe.score,
+            format_recipe(ingredients, &recipe)
+        ),
+        None => println!("Day 15{}: no recipe scores above zero", name),
+    }
 }
 
 mod tests {
+    use crate::combinatorics::compositions;
     use crate::day15::*;
 
+    fn score_of(ingredients: &[Ingredient], recipe: &Recipe) -> i64 {
+        return (0..PROPERTIES)
+            .map(|p| {
+                let total: i64 = (0..ingredients.len())
+                    .map(|i| ingredients[i].properties()[p] * recipe.teaspoons[i])
+                    .sum();
+                return std::cmp::max(0, total);
+            })
+            .product();
+    }
+
     const TEST_INPUT: &'static str = r#"
     Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
     Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3        
@@ -104,7 +219,7 @@ mod tests {
 
     #[test]
     fn parse_the_thing() {
-        let parsed = parse(TEST_INPUT);
+        let parsed = parse(TEST_INPUT).unwrap();
         assert_eq!(2, parsed.len());
 
         assert_eq!(-1, parsed[0].capacity);
@@ -116,8 +231,63 @@ mod tests {
 
     #[test]
     fn solves_correctly() {
-        let parsed = parse(TEST_INPUT);
-        let best = solve_the_thing(&parsed, None);
-        assert_eq!(62_842_880, best);
+        let parsed = parse(TEST_INPUT).unwrap();
+
+        let best = best_recipe(&parsed, 100, None).unwrap();
+        assert_eq!(62_842_880, best.score);
+        assert_eq!(vec![44, 56], best.teaspoons);
+        assert_eq!(
+            "Butterscotch 44, Cinnamon 56",
+            format_recipe(&parsed, &best)
+        );
+
+        let light = best_recipe(&parsed, 100, Some(500)).unwrap();
+        assert_eq!(57_600_000, light.score);
+        assert_eq!(vec![40, 60], light.teaspoons);
+    }
+
+    #[test]
+    fn match_every_composition() {
+        let input = std::fs::read_to_string("src/day15_input.txt").unwrap();
+        let parsed = parse(&input).unwrap();
+
+        for teaspoons in [0, 1, 7, 20, 31] {
+            for calories in [None, Some(teaspoons as i64 * 5)] {
+                let mut expected = 0;
+                for amounts in compositions(teaspoons, parsed.len()) {
+                    let recipe = Recipe {
+                        teaspoons: amounts.iter().map(|a| *a as i64).collect(),
+                        score: 0,
+                    };
+                    let cal: i64 = (0..parsed.len())
+                        .map(|i| parsed[i].calories * recipe.teaspoons[i])
+                        .sum();
+                    if calories.map_or(true, |c| c == cal) {
+                        expected = std::cmp::max(expected, score_of(&parsed, &recipe));
+                    }
+                }
+
+                let found = best_recipe(&parsed, teaspoons as i64, calories);
+                assert_eq!(
+                    expected,
+                    found.as_ref().map_or(0, |r| r.score),
+                    "{} {:?}",
+                    teaspoons,
+                    calories
+                );
+                if let Some(recipe) = found {
+                    assert_eq!(recipe.score, score_of(&parsed, &recipe));
+                    assert_eq!(teaspoons as i64, recipe.teaspoons.iter().sum::<i64>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handle_recipes_without_a_score() {
+        let parsed = parse(TEST_INPUT).unwrap();
+        assert_eq!(None, best_recipe(&parsed, 100, Some(1)));
+        assert_eq!(None, best_recipe(&parsed[..1], 100, None));
+        assert_eq!(None, best_recipe(&[], 100, None));
     }
 }
@@ -1,135 +1,320 @@
-use std::collections::HashMap;
-use std::fs;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Compound {
-    Children,
-    Cats,
-    Samoyeds,
-    Pomeranians,
-    Akitas,
-    Vizslas,
-    Goldfish,
-    Trees,
-    Cars,
-    Perfumes,
+use crate::parse::{numbered_lines, parse_token, ParseError};
+use crate::scan;
+use std::fmt;
+
+pub fn run() {
+    let input = std::fs::read_to_string("src/day16_input.txt").unwrap();
+    let rules = std::fs::read_to_string("src/day16_rules.txt").unwrap();
+
+    let (sues, rules) = match (parse_sues(&input), parse_rules(&rules)) {
+        (Ok(sues), Ok(rules)) => (sues, rules),
+        (Err(err), _) | (_, Err(err)) => {
+            println!("Day 16: {}", err);
+            return;
+        }
+    };
+
+    // the first part reads the tape as if every value was exact.
+    let exact: Vec<Rule> = rules.iter().map(Rule::as_exact).collect();
+
+    part("A", &sues, &exact);
+    part("B", &sues, &rules);
 }
 
-#[derive(Debug)]
-struct Gift {
-    sue_number: i64,
-    compounds: HashMap<Compound, i64>,
+// what the tape says about a compound. the ranges are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Exactly(i64),
+    MoreThan(i64),
+    FewerThan(i64),
+    Between(i64, i64),
 }
 
-fn parse_gift(line: &str) -> Gift {
-    let mut index_of_first_colon = 0;
-    let mut i = 0;
+impl Comparison {
+    fn matches(&self, value: i64) -> bool {
+        return match *self {
+            Comparison::Exactly(n) => value == n,
+            Comparison::MoreThan(n) => value > n,
+            Comparison::FewerThan(n) => value < n,
+            Comparison::Between(low, high) => low <= value && value <= high,
+        };
+    }
+}
 
-    for ch in line.chars() {
-        if ch == ':' {
-            index_of_first_colon = i;
-            break;
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparison::Exactly(n) => write!(f, "{}", n),
+            Comparison::MoreThan(n) => write!(f, ">{}", n),
+            Comparison::FewerThan(n) => write!(f, "<{}", n),
+            Comparison::Between(low, high) => write!(f, "{}..{}", low, high),
         }
-        i += 1;
     }
+}
 
-    let (_, sue_num_str) = &line[0..(index_of_first_colon)].split_once(' ').unwrap();
-    let sue_num = sue_num_str.parse::<i64>().unwrap();
-    let actual_line = &line[(index_of_first_colon + 1)..];
-    let mut compounds = HashMap::new();
-
-    for chunk in actual_line.split(',') {
-        let (key, value) = chunk.split_once(':').unwrap();
-
-        let compound = match key.trim() {
-            "children" => Compound::Children,
-            "cats" => Compound::Cats,
-            "samoyeds" => Compound::Samoyeds,
-            "pomeranians" => Compound::Pomeranians,
-            "akitas" => Compound::Akitas,
-            "vizslas" => Compound::Vizslas,
-            "goldfish" => Compound::Goldfish,
-            "trees" => Compound::Trees,
-            "cars" => Compound::Cars,
-            "perfumes" => Compound::Perfumes,
-            _ => panic!(),
-        };
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    compound: String,
+    comparison: Comparison,
+}
 
-        compounds.insert(compound, value.trim().parse::<i64>().unwrap());
+impl Rule {
+    // the reading without the retroencabulator's corrections. a range
+    // has no single reading, so it stays as it is.
+    fn as_exact(&self) -> Rule {
+        let comparison = match self.comparison {
+            Comparison::MoreThan(n) | Comparison::FewerThan(n) => Comparison::Exactly(n),
+            other => other,
+        };
+        return Rule {
+            compound: self.compound.clone(),
+            comparison,
+        };
     }
+}
 
-    return Gift {
-        sue_number: sue_num,
-        compounds,
-    };
+// what we remember about one aunt. any compound name is fine.
+#[derive(Debug, Clone, PartialEq)]
+struct Sue {
+    number: i64,
+    compounds: Vec<(String, i64)>,
 }
 
-fn get_gift_likeness(gift: &Gift) -> i64 {
-    let cmp = &gift.compounds;
+// one line is "name: value", where the value is "3", ">7", "<3" or "2..5".
+fn parse_rules(input: &str) -> Result<Vec<Rule>, ParseError> {
+    let mut out: Vec<Rule> = Vec::new();
 
-    let cats = cmp.get(&Compound::Cats);
-    let trees = cmp.get(&Compound::Trees);
-    let pomer = cmp.get(&Compound::Pomeranians);
-    let fish = cmp.get(&Compound::Goldfish);
-    let mut score = 0;
+    for (n, line) in numbered_lines(input) {
+        let (compound, value) = scan!((n, line), "{}: {}", &str, &str)?;
 
-    if let Some(&num) = cats {
-        if num <= 7 {
-            return 0;
-        }
-        score += 1;
-    }
+        let comparison = if let Some(rest) = value.strip_prefix('>') {
+            Comparison::MoreThan(parse_token(n, line, rest, "a number")?)
+        } else if let Some(rest) = value.strip_prefix('<') {
+            Comparison::FewerThan(parse_token(n, line, rest, "a number")?)
+        } else if let Some((low, high)) = value.split_once("..") {
+            let low = parse_token(n, line, low, "a number")?;
+            let high = parse_token(n, line, high, "a number")?;
+            if low > high {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    value,
+                    "a range that is not empty",
+                ));
+            }
+            Comparison::Between(low, high)
+        } else {
+            Comparison::Exactly(parse_token(n, line, value, "a number")?)
+        };
 
-    if let Some(&num) = trees {
-        if num <= 3 {
-            return 0;
+        if out.iter().any(|r| r.compound == compound) {
+            return Err(ParseError::at_token(
+                n,
+                line,
+                compound,
+                "each compound only once",
+            ));
         }
-        score += 1;
+        out.push(Rule {
+            compound: compound.to_string(),
+            comparison,
+        });
     }
 
-    if let Some(&num) = pomer {
-        if num >= 3 {
-            return 0;
-        }
-        score += 1;
-    }
+    return Ok(out);
+}
 
-    if let Some(&num) = fish {
-        if num >= 5 {
-            return 0;
-        }
-        score += 1;
-    }
+fn parse_sues(input: &str) -> Result<Vec<Sue>, ParseError> {
+    let mut out = Vec::new();
 
-    let exact_compounds = [
-        (Compound::Children, 3),
-        (Compound::Samoyeds, 2),
-        (Compound::Cars, 2),
-        (Compound::Perfumes, 1),
-    ];
-
-    for (c, expected_num) in exact_compounds {
-        if let Some(&actual_num) = cmp.get(&c) {
-            if actual_num != expected_num {
-                return 0;
+    for (n, line) in numbered_lines(input) {
+        let (number, list) = scan!((n, line), "Sue {}: {}", i64, &str)?;
+        let mut compounds = Vec::new();
+
+        for chunk in list.split(',') {
+            let (name, value) = match chunk.split_once(':') {
+                Some((name, value)) => (name.trim(), value.trim()),
+                None => return Err(ParseError::at_token(n, line, chunk.trim(), "'name: value'")),
+            };
+            if name.is_empty() {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    chunk.trim(),
+                    "a compound name",
+                ));
             }
-            score += 1;
+            compounds.push((name.to_string(), parse_token(n, line, value, "a number")?));
         }
+
+        out.push(Sue { number, compounds });
     }
 
-    return score;
+    return Ok(out);
 }
 
-pub fn run() {
-    let input = fs::read_to_string("./src/day16_input.txt").unwrap();
-    let mut gifts = input.lines().map(parse_gift).collect::<Vec<Gift>>();
+#[derive(Debug, Clone, PartialEq)]
+struct Violation<'a> {
+    compound: &'a str,
+    found: i64,
+    expected: Comparison,
+}
 
-    gifts.sort_by(|a, b| {
-        let al = get_gift_likeness(&a);
-        let bl = get_gift_likeness(&b);
+#[derive(Debug, Clone, PartialEq)]
+struct Candidate<'a> {
+    sue: &'a Sue,
+    matched: usize,
+    violations: Vec<Violation<'a>>,
+}
+
+// every sue, best first: the fewest violated rules, then the most matched
+// ones. compounds that the tape says nothing about do not count either way.
+fn rank<'a>(sues: &'a [Sue], rules: &'a [Rule]) -> Vec<Candidate<'a>> {
+    let mut out: Vec<Candidate> = sues
+        .iter()
+        .map(|sue| {
+            let mut candidate = Candidate {
+                sue,
+                matched: 0,
+                violations: Vec::new(),
+            };
+            for (name, found) in &sue.compounds {
+                let rule = match rules.iter().find(|r| &r.compound == name) {
+                    Some(rule) => rule,
+                    None => continue,
+                };
+                if rule.comparison.matches(*found) {
+                    candidate.matched += 1;
+                } else {
+                    candidate.violations.push(Violation {
+                        compound: &rule.compound,
+                        found: *found,
+                        expected: rule.comparison,
+                    });
+                }
+            }
+            return candidate;
+        })
+        .collect();
 
-        return bl.cmp(&al);
+    out.sort_by(|a, b| {
+        return a
+            .violations
+            .len()
+            .cmp(&b.violations.len())
+            .then(b.matched.cmp(&a.matched))
+            .then(a.sue.number.cmp(&b.sue.number));
     });
+    return out;
+}
+
+fn format_candidate(candidate: &Candidate) -> String {
+    let mut out = format!(
+        "Sue {} ({} matched",
+        candidate.sue.number, candidate.matched
+    );
+    for v in &candidate.violations {
+        out += &format!(", {}: {} is not {}", v.compound, v.found, v.expected);
+    }
+    out += ")";
+    return out;
+}
 
-    println!("Day 16B: {:?}", gifts[0]);
+fn part(name: &str, sues: &[Sue], rules: &[Rule]) {
+    match rank(sues, rules).first() {
+        Some(best) => println!("Day 16{}: {}", name, format_candidate(best)),
+        None => println!("Day 16{}: there are no aunts", name),
+    }
+}
+
+mod tests {
+    use crate::day16::*;
+
+    const EXAMPLE_RULES: &'static str = r#"
+children: 3
+cats: >7
+goldfish: <5
+cars: 1..2
+"#;
+
+    const EXAMPLE_SUES: &'static str = r#"
+Sue 1: children: 3, cats: 7, cars: 2
+Sue 2: children: 3, cats: 8, hamsters: 4
+Sue 3: goldfish: 6, cars: 3, cats: 9
+Sue 4: children: 3, goldfish: 4, cars: 1
+"#;
+
+    #[test]
+    fn parse_rules_and_sues() {
+        let rules = parse_rules(EXAMPLE_RULES).unwrap();
+        assert_eq!(
+            vec![
+                Comparison::Exactly(3),
+                Comparison::MoreThan(7),
+                Comparison::FewerThan(5),
+                Comparison::Between(1, 2)
+            ],
+            rules.iter().map(|r| r.comparison).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "3 >7 <5 1..2",
+            rules
+                .iter()
+                .map(|r| r.comparison.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+
+        let sues = parse_sues(EXAMPLE_SUES).unwrap();
+        assert_eq!(4, sues.len());
+        assert_eq!(("hamsters".to_string(), 4), sues[1].compounds[2]);
+
+        let check = |err: ParseError, column: usize, expected: &str| {
+            assert_eq!((column, expected.to_string()), (err.column, err.expected));
+        };
+        check(parse_rules("cats: >x").unwrap_err(), 8, "a number");
+        check(
+            parse_rules("cats: 5..2").unwrap_err(),
+            7,
+            "a range that is not empty",
+        );
+        check(
+            parse_rules("cats: 1\ncats: 2").unwrap_err(),
+            1,
+            "each compound only once",
+        );
+        check(parse_sues("Sue 1: cats 3").unwrap_err(), 8, "'name: value'");
+        check(
+            parse_sues("Sue 1: cats: 3, dogs: x").unwrap_err(),
+            23,
+            "a number",
+        );
+    }
+
+    #[test]
+    fn rank_the_sues() {
+        let rules = parse_rules(EXAMPLE_RULES).unwrap();
+        let sues = parse_sues(EXAMPLE_SUES).unwrap();
+
+        let ranked = rank(&sues, &rules);
+        assert_eq!(
+            vec![4, 2, 1, 3],
+            ranked.iter().map(|c| c.sue.number).collect::<Vec<_>>()
+        );
+        assert_eq!("Sue 4 (3 matched)", format_candidate(&ranked[0]));
+        assert_eq!("Sue 2 (2 matched)", format_candidate(&ranked[1]));
+        assert_eq!(
+            "Sue 1 (2 matched, cats: 7 is not >7)",
+            format_candidate(&ranked[2])
+        );
+        assert_eq!(
+            "Sue 3 (1 matched, goldfish: 6 is not <5, cars: 3 is not 1..2)",
+            format_candidate(&ranked[3])
+        );
+
+        // read as exact values, only a sue with seven cats fits.
+        let exact: Vec<Rule> = rules.iter().map(Rule::as_exact).collect();
+        assert_eq!(Comparison::Between(1, 2), exact[3].comparison);
+        assert_eq!(1, rank(&sues, &exact)[0].sue.number);
+    }
 }
@@ -1,79 +1,211 @@
-use std::collections::HashSet;
+use crate::parse::{numbered_lines, parse_token, ParseError};
 
-const CONTAINERS: [i64; 20] = [
-    33, 14, 18, 20, 45, 35, 16, 35, 1, 13, 18, 13, 50, 44, 48, 6, 24, 41, 30, 42,
-];
+const TARGET: usize = 150;
 
-// the example input...
-// const CONTAINERS: [i64; 5] = [20, 15, 10, 5, 5];
+pub fn run() {
+    let input = std::fs::read_to_string("src/day17_input.txt").unwrap();
+    let containers = match parse_containers(&input) {
+        Ok(containers) => containers,
+        Err(err) => {
+            println!("Day 17: {}", err);
+            return;
+        }
+    };
 
-#[derive(Debug, Clone, Copy, Hash)]
-struct Container {
-    capacity: i64,
-    index: usize,
+    let counts = count_fillings(&containers, TARGET);
+    println!("Day 17A: {}", counts.total);
+    match counts.fewest {
+        Some(fewest) => println!("Day 17B: {} ({} containers)", counts.with_fewest, fewest),
+        None => println!("Day 17B: 0"),
+    }
 }
 
-impl PartialEq for Container {
-    fn eq(&self, other: &Self) -> bool {
-        return self.index == other.index;
-    }
+fn parse_containers(input: &str) -> Result<Vec<usize>, ParseError> {
+    return numbered_lines(input)
+        .map(|(n, line)| parse_token(n, line, line.trim(), "a container size"))
+        .collect();
 }
 
-impl Eq for Container {}
+#[derive(Debug, Clone, PartialEq)]
+struct Counts {
+    // every set of containers that holds exactly the target.
+    total: u64,
+    // the smallest of those sets, and how many there are of that size.
+    fewest: Option<usize>,
+    with_fewest: u64,
+}
 
-fn make_combos(
-    target: i64,
-    carry: &[Container],
-    containers: &[Container],
-    out: &mut Vec<HashSet<Container>>,
-) {
-    let sum: i64 = carry.iter().map(|c| c.capacity).sum();
+// counts the sets without listing them. ways[k][s] is the number of sets
+// of k containers, out of the ones seen so far, that hold s liters.
+fn count_fillings(containers: &[usize], target: usize) -> Counts {
+    let n = containers.len();
+    let mut ways = vec![vec![0u64; target + 1]; n + 1];
+    ways[0][0] = 1;
 
-    if sum > target {
-        return;
-    }
-    if sum == target {
-        let mut result = HashSet::<Container>::new();
-        result.extend(carry);
-
-        if !out.contains(&result) {
-            out.push(result);
-            // println!("OK {:?}", out.len());
+    for (i, size) in containers.iter().enumerate() {
+        if *size > target {
+            continue;
+        }
+        // going down keeps each container from being used twice.
+        for k in (0..=i).rev() {
+            for s in 0..=(target - size) {
+                ways[k + 1][s + size] += ways[k][s];
+            }
         }
-        return;
     }
 
-    for c in containers {
-        if carry.contains(c) {
-            continue;
+    let total = (0..=n).map(|k| ways[k][target]).sum();
+    let fewest = (0..=n).find(|k| ways[*k][target] > 0);
+    let with_fewest = fewest.map_or(0, |k| ways[k][target]);
+
+    return Counts {
+        total,
+        fewest,
+        with_fewest,
+    };
+}
+
+// lists the sets as indices into "containers". reachable[i][s] tells
+// whether the containers from i on can hold s liters, so the search never
+// goes down a branch without an answer.
+fn list_fillings(containers: &[usize], target: usize) -> Vec<Vec<usize>> {
+    let n = containers.len();
+    let mut reachable = vec![vec![false; target + 1]; n + 1];
+    reachable[n][0] = true;
+
+    for i in (0..n).rev() {
+        for s in 0..=target {
+            reachable[i][s] =
+                reachable[i + 1][s] || (s >= containers[i] && reachable[i + 1][s - containers[i]]);
         }
+    }
 
-        let next_carry = &[carry, &[*c]].concat();
-        make_combos(target, next_carry, containers, out);
+    let mut out = Vec::new();
+    let mut picked = Vec::new();
+    if reachable[0][target] {
+        visit(containers, &reachable, 0, target, &mut picked, &mut out);
     }
+    return out;
 }
 
-pub fn run() {
-    let containers: Vec<Container> = CONTAINERS
-        .iter()
-        .enumerate()
-        .map(|item| {
-            return Container {
-                capacity: *item.1,
-                index: item.0,
-            };
-        })
-        .collect();
+fn visit(
+    containers: &[usize],
+    reachable: &[Vec<bool>],
+    i: usize,
+    left: usize,
+    picked: &mut Vec<usize>,
+    out: &mut Vec<Vec<usize>>,
+) {
+    if i == containers.len() {
+        out.push(picked.clone());
+        return;
+    }
 
-    println!("{:?}", containers);
+    if left >= containers[i] && reachable[i + 1][left - containers[i]] {
+        picked.push(i);
+        visit(
+            containers,
+            reachable,
+            i + 1,
+            left - containers[i],
+            picked,
+            out,
+        );
+        picked.pop();
+    }
+    if reachable[i + 1][left] {
+        visit(containers, reachable, i + 1, left, picked, out);
+    }
+}
 
-    let mut out = Vec::new();
-    make_combos(150, &[], &containers, &mut out);
+mod tests {
+    use crate::combinatorics::power_set;
+    use crate::day17::*;
+
+    const EXAMPLE: [usize; 5] = [20, 15, 10, 5, 5];
+
+    #[test]
+    fn fill_the_example() {
+        assert_eq!(
+            Counts {
+                total: 4,
+                fewest: Some(2),
+                with_fewest: 3
+            },
+            count_fillings(&EXAMPLE, 25)
+        );
+
+        assert_eq!(
+            vec![vec![0, 3], vec![0, 4], vec![1, 2], vec![1, 3, 4]],
+            list_fillings(&EXAMPLE, 25)
+        );
+    }
 
-    println!("Day 17A: {:?}", out.len());
+    #[test]
+    fn handle_edge_cases() {
+        let none = Counts {
+            total: 0,
+            fewest: None,
+            with_fewest: 0,
+        };
+        assert_eq!(none, count_fillings(&EXAMPLE, 100));
+        assert!(list_fillings(&EXAMPLE, 100).is_empty());
+
+        // only the empty set holds nothing.
+        let empty = Counts {
+            total: 1,
+            fewest: Some(0),
+            with_fewest: 1,
+        };
+        assert_eq!(empty, count_fillings(&EXAMPLE, 0));
+        assert_eq!(vec![Vec::<usize>::new()], list_fillings(&[], 0));
+
+        // sixty ones can be picked in more ways than a u32 holds.
+        assert_eq!(
+            Counts {
+                total: 118264581564861424,
+                fewest: Some(30),
+                with_fewest: 118264581564861424
+            },
+            count_fillings(&[1; 60], 30)
+        );
+    }
 
-    let min_containers = out.iter().map(|c| c.len()).min().unwrap_or(0);
-    let combos_of_min_size = out.iter().filter(|c| c.len() == min_containers);
+    #[test]
+    fn match_the_power_set() {
+        let input = std::fs::read_to_string("src/day17_input.txt").unwrap();
+        let containers = parse_containers(&input).unwrap();
+        let containers = &containers[..14];
+
+        for target in [0, 1, 33, 60, 100, 150] {
+            let mut expected: Vec<Vec<usize>> = power_set(containers.len())
+                .map(|picked| picked.as_slice().to_vec())
+                .filter(|picked| picked.iter().map(|i| containers[*i]).sum::<usize>() == target)
+                .collect();
+            expected.sort();
+
+            let mut listed = list_fillings(containers, target);
+            listed.sort();
+            assert_eq!(expected, listed);
+
+            let counts = count_fillings(containers, target);
+            assert_eq!(expected.len() as u64, counts.total);
+            let fewest = expected.iter().map(|p| p.len()).min();
+            assert_eq!(fewest, counts.fewest);
+            assert_eq!(
+                expected.iter().filter(|p| Some(p.len()) == fewest).count() as u64,
+                counts.with_fewest
+            );
+        }
+    }
 
-    println!("Day 17B: {:?}", combos_of_min_size.count());
+    #[test]
+    fn parse_the_containers() {
+        assert_eq!(
+            vec![20, 15, 10],
+            parse_containers("20\n15\n\n10\n").unwrap()
+        );
+        let err = parse_containers("20\n-5").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+    }
 }
@@ -1,17 +1,23 @@
+use crate::automaton::{Automaton, Rule, Topology};
+use crate::image;
+use crate::parse::{numbered_lines, ParseError};
 use std::fs;
 
 pub fn run() {
     let input = fs::read_to_string("./src/day18_input.txt").unwrap();
+    let lights = match parse_lights(&input, SIZE) {
+        Ok(lights) => lights,
+        Err(err) => {
+            println!("Day 18: {}", err);
+            return;
+        }
+    };
 
-    do_the_thing(&input, Part::One);
-    do_the_thing(&input, Part::Two);
+    do_the_thing(lights.clone(), Part::One);
+    do_the_thing(lights, Part::Two);
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum LightState {
-    Off,
-    On,
-}
+const SIZE: usize = 100;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Part {
@@ -19,118 +25,122 @@ enum Part {
     Two,
 }
 
-type Lights<const N: usize> = [[LightState; N]; N];
-
-fn parse_lights<const N: usize>(input: &str) -> Lights<N> {
-    let mut lights = [[LightState::Off; N]; N];
+// the lights follow conway's game of life, and nothing is lit past the edges.
+// the grid has to be exactly "size" lights wide and high.
+fn parse_lights(input: &str, size: usize) -> Result<Automaton<2>, ParseError> {
+    let rule = Rule::parse("B3/S23").unwrap();
+    let mut lights = Automaton::new(rule, Topology::Bounded([size as i64; 2]));
     let mut i = 0;
+    let mut last = (1, "");
 
-    for line in input.lines() {
-        let mut k = 0;
-
-        for ch in line.chars() {
-            lights[i][k] = if ch == '#' {
-                LightState::On
-            } else {
-                LightState::Off
-            };
-            k += 1;
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        if i == size {
+            let expected = format!("{} rows", size);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+        if let Some(bad) = text.find(|ch| ch != '#' && ch != '.') {
+            return Err(ParseError::at_token(n, line, &text[bad..], "'#' or '.'"));
+        }
+        if text.len() != size {
+            let expected = format!("{} lights", size);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+        for (k, ch) in text.chars().enumerate() {
+            lights.set([k as i64, i as i64], ch == '#');
         }
-        assert_eq!(N, k);
         i += 1;
+        last = (n, line);
     }
-    assert_eq!(N, i);
-
-    return lights;
-}
 
-fn is_corner(coord: (i64, i64), size: usize) -> bool {
-    let edge_value = (size - 1) as i64;
-    return (coord.0 == 0 && coord.1 == 0)
-        || (coord.0 == 0 && coord.1 == edge_value)
-        || (coord.0 == edge_value && coord.1 == 0)
-        || (coord.0 == edge_value && coord.1 == edge_value);
+    if i != size {
+        let expected = format!("{} rows", size);
+        return Err(ParseError::at_end(last.0, last.1, &expected));
+    }
+    return Ok(lights);
 }
 
-fn step<const N: usize>(prev: &Lights<N>, part: Part) -> Lights<N> {
-    let mut next = [[LightState::Off; N]; N];
-
-    for i in 0..(N as i64) {
-        for k in 0..(N as i64) {
-            let neighbours: [(i64, i64); 8] = [
-                (i - 1, k - 1),
-                (i - 1, k),
-                (i - 1, k + 1),
-                (i, k - 1),
-                (i, k + 1),
-                (i + 1, k - 1),
-                (i + 1, k),
-                (i + 1, k + 1),
-            ];
-            let enabled_neighbours = neighbours
-                .map(|coord| {
-                    let state = prev
-                        .get(coord.0 as usize)
-                        .map(|arr| arr.get(coord.1 as usize))
-                        .map(|b| b.map(|c| *c).unwrap_or(LightState::Off))
-                        .unwrap_or(LightState::Off);
-
-                    return state;
-                })
-                .into_iter()
-                .filter(|s| *s == LightState::On)
-                .count();
-            let state = prev[i as usize][k as usize];
-            let next_state = match state {
-                LightState::On => {
-                    if enabled_neighbours == 2
-                        || enabled_neighbours == 3
-                        || (part == Part::Two && is_corner((i, k), N))
-                    {
-                        LightState::On
-                    } else {
-                        LightState::Off
-                    }
-                }
-                LightState::Off => {
-                    if enabled_neighbours == 3 || (part == Part::Two && is_corner((i, k), N)) {
-                        LightState::On
-                    } else {
-                        LightState::Off
-                    }
-                }
-            };
-            next[i as usize][k as usize] = next_state;
-        }
+// in part two the four corners are stuck on.
+fn pin_corners(lights: &mut Automaton<2>, size: usize) {
+    let edge = size as i64 - 1;
+    for corner in [[0, 0], [0, edge], [edge, 0], [edge, edge]] {
+        lights.pin(corner, true);
     }
-
-    return next;
 }
 
-fn do_the_thing(input: &str, part: Part) {
-    let mut lights = parse_lights::<100>(input);
-
+fn do_the_thing(mut lights: Automaton<2>, part: Part) {
     if part == Part::Two {
-        lights[0][0] = LightState::On;
-        lights[0][99] = LightState::On;
-        lights[99][0] = LightState::On;
-        lights[99][99] = LightState::On;
+        pin_corners(&mut lights, SIZE);
     }
 
+    let mut animation = image::Animation::new(5);
+
     for _ in 0..100 {
-        let next = step(&lights, part);
-        lights = next;
+        if image::is_enabled() {
+            animation.push_frame(&lights_to_image(&lights, SIZE));
+        }
+        lights.step();
     }
 
-    let enabled_count = lights
-        .iter()
-        .flatten()
-        .filter(|l| **l == LightState::On)
-        .count();
-
     println!(
         "Day 18{}: {}",
         if part == Part::One { "A" } else { "B" },
-        enabled_count
+        lights.population()
     );
+
+    if image::is_enabled() {
+        animation.push_frame(&lights_to_image(&lights, SIZE));
+        let name = format!("day18_part{}.gif", if part == Part::One { 1 } else { 2 });
+        image::save(&name, &animation.to_gif());
+    }
+}
+
+const LIGHT_PALETTE: [image::Rgb; 2] = [[20, 20, 30], [120, 255, 120]];
+
+fn lights_to_image(lights: &Automaton<2>, size: usize) -> image::Image {
+    let img = image::Image::from_fn(size, size, &LIGHT_PALETTE, |x, y| {
+        return u8::from(lights.is_alive([x as i64, y as i64]));
+    });
+    return img.scaled(4);
+}
+
+mod tests {
+    use crate::day18::*;
+
+    const EXAMPLE_INPUT: &'static str = ".#.#.#
+...##.
+#....#
+..#...
+#.#..#
+####..";
+
+    #[test]
+    fn animate_the_example() {
+        let mut lights = parse_lights(EXAMPLE_INPUT, 6).unwrap();
+        lights.run(4);
+        assert_eq!(4, lights.population());
+
+        let mut lights = parse_lights(EXAMPLE_INPUT, 6).unwrap();
+        pin_corners(&mut lights, 6);
+        lights.run(5);
+        assert_eq!(17, lights.population());
+    }
+
+    #[test]
+    fn report_bad_grids() {
+        let check = |input: &str, line: usize, column: usize, expected: &str| {
+            let err = parse_lights(input, 3).unwrap_err();
+            assert_eq!(
+                (line, column, expected.to_string()),
+                (err.line, err.column, err.expected),
+                "{}",
+                input
+            );
+        };
+
+        check(".#.\n.#\n...", 2, 1, "3 lights");
+        check(".#.\n.x.\n...", 2, 2, "'#' or '.'");
+        check(".#.\n...", 2, 4, "3 rows");
+        check(".#.\n...\n...\n...", 4, 1, "3 rows");
+    }
 }
@@ -1,135 +1,208 @@
-use std::collections::HashMap;
+use crate::grammar::{Grammar, Tree};
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::collections::HashSet;
 
-type StringPair = (&'static str, &'static str);
-
-const REPLACEMENTS: [StringPair; 43] = [
-    ("Al", "ThF"),
-    ("Al", "ThRnFAr"),
-    ("B", "BCa"),
-    ("B", "TiB"),
-    ("B", "TiRnFAr"),
-    ("Ca", "CaCa"),
-    ("Ca", "PB"),
-    ("Ca", "PRnFAr"),
-    ("Ca", "SiRnFYFAr"),
-    ("Ca", "SiRnMgAr"),
-    ("Ca", "SiTh"),
-    ("F", "CaF"),
-    ("F", "PMg"),
-    ("F", "SiAl"),
-    ("H", "CRnAlAr"),
-    ("H", "CRnFYFYFAr"),
-    ("H", "CRnFYMgAr"),
-    ("H", "CRnMgYFAr"),
-    ("H", "HCa"),
-    ("H", "NRnFYFAr"),
-    ("H", "NRnMgAr"),
-    ("H", "NTh"),
-    ("H", "OB"),
-    ("H", "ORnFAr"),
-    ("Mg", "BF"),
-    ("Mg", "TiMg"),
-    ("N", "CRnFAr"),
-    ("N", "HSi"),
-    ("O", "CRnFYFAr"),
-    ("O", "CRnMgAr"),
-    ("O", "HP"),
-    ("O", "NRnFAr"),
-    ("O", "OTi"),
-    ("P", "CaP"),
-    ("P", "PTi"),
-    ("P", "SiRnFAr"),
-    ("Si", "CaSi"),
-    ("Th", "ThCa"),
-    ("Ti", "BP"),
-    ("Ti", "TiTi"),
-    ("e", "HF"),
-    ("e", "NAl"),
-    ("e", "OMg"),
-];
-
-const MOLECULE: &'static str = "ORnPBPMgArCaCaCaSiThCaCaSiThCaCaPBSiRnFArRnFArCaCaSiThCaCaSiThCaCaCaCaCaCaSiRnFYFArSiRnMgArCaSiRnPTiTiBFYPBFArSiRnCaSiRnTiRnFArSiAlArPTiBPTiRnCaSiAlArCaPTiTiBPMgYFArPTiRnFArSiRnCaCaFArRnCaFArCaSiRnSiRnMgArFYCaSiRnMgArCaCaSiThPRnFArPBCaSiRnMgArCaCaSiThCaSiRnTiMgArFArSiThSiThCaCaSiRnMgArCaCaSiRnFArTiBPTiRnCaSiAlArCaPTiRnFArPBPBCaCaSiThCaPBSiThPRnFArSiThCaSiThCaSiThCaPTiBSiRnFYFArCaCaPRnFArPBCaCaPBSiRnTiRnFArCaPRnFArSiRnCaCaCaSiThCaRnCaFArYCaSiRnFArBCaCaCaSiThFArPBFArCaSiRnFArRnCaCaCaFArSiRnFArTiRnPMgArF";
-
-// NRnBSiRnCaRnFArYFArFArF
-
-// Example input.
-// const REPLACEMENTS: [Replacement; 3] = [("H", "HO"), ("H", "OH"), ("O", "HH")];
-// const MOLECULE: &'static str = "HOH";
-
 pub fn run() {
-    part1();
-    part2();
+    let input = std::fs::read_to_string("src/day19_input.txt").unwrap();
+    let machine = match parse_machine(&input) {
+        Ok(machine) => machine,
+        Err(err) => {
+            println!("Day 19: {}", err);
+            return;
+        }
+    };
+
+    part1(&machine);
+    part2(&machine);
 }
 
-fn expand_once(input: &str, replacements: &[StringPair]) -> HashSet<String> {
-    let mut result = HashSet::new();
+#[derive(Debug)]
+struct Replacement {
+    from: String,
+    to: Vec<String>,
+}
 
-    for i in 0..input.len() {
-        for (from, to) in replacements {
-            let end = std::cmp::min(i + from.len(), input.len());
-            let chunk = &input[i..end];
+#[derive(Debug)]
+struct Machine {
+    replacements: Vec<Replacement>,
+    molecule: Vec<String>,
+}
 
-            if chunk == *from {
-                let str = format!("{}{}{}", &input[0..i], to, &input[end..]);
-                result.insert(str);
+// an element is a capital letter and any lowercase letters after it. the
+// electron is written as a lone "e".
+fn tokenize(line_number: usize, line: &str) -> Result<Vec<String>, ParseError> {
+    let mut out = Vec::new();
+    let mut start = 0;
+
+    while start < line.len() {
+        let first = line.as_bytes()[start];
+        let len = match first {
+            b'e' => 1,
+            b'A'..=b'Z' => {
+                1 + line[(start + 1)..]
+                    .bytes()
+                    .take_while(|b| b.is_ascii_lowercase())
+                    .count()
             }
-        }
+            _ => {
+                return Err(ParseError::new(line_number, line, start + 1, "an element"));
+            }
+        };
+        out.push(line[start..(start + len)].to_string());
+        start += len;
     }
 
-    return result;
+    return Ok(out);
 }
 
-fn part1() {
-    let result = expand_once(MOLECULE, &REPLACEMENTS);
-    println!("Day 19A: {}", result.len());
-}
+// the replacements come first, and the molecule is the line without an arrow.
+fn parse_machine(input: &str) -> Result<Machine, ParseError> {
+    let mut replacements = Vec::new();
+    let mut molecule = None;
 
-fn find_fewest_reductions_possible(
-    molecule: &str,
-    target: &str,
-    replacements: &[StringPair],
-) -> i64 {
-    let mut seen = HashSet::<String>::new();
-    let mut stack: Vec<(String, i64)> = vec![(molecule.to_string(), 0)];
-
-    while !stack.is_empty() {
-        stack.sort_by(|a, b| {
-            return b.0.len().cmp(&a.0.len());
+    for (n, line) in numbered_lines(input) {
+        if !line.contains("=>") {
+            molecule = Some(tokenize(n, line.trim())?);
+            continue;
+        }
+        let (from, to) = scan!((n, line), "{} => {}", &str, &str)?;
+        let from_tokens = tokenize(n, from)?;
+        if from_tokens.len() != 1 {
+            return Err(ParseError::at_token(n, line, from, "a single element"));
+        }
+        replacements.push(Replacement {
+            from: from.to_string(),
+            to: tokenize(n, to)?,
         });
+    }
 
-        let (item, count) = stack.pop().unwrap();
+    return match molecule {
+        Some(molecule) => Ok(Machine {
+            replacements,
+            molecule,
+        }),
+        None => Err(ParseError::at_end(
+            input.lines().count().max(1),
+            input.lines().last().unwrap_or(""),
+            "a molecule",
+        )),
+    };
+}
 
-        for &(from, to) in replacements {
-            let expanded = expand_once(&item, &[(to, from)]);
+fn expand_once(molecule: &[String], replacements: &[Replacement]) -> HashSet<String> {
+    let mut result = HashSet::new();
 
-            for res in expanded {
-                if seen.contains(&res) {
-                    continue;
-                }
-                if res == target {
-                    return count + 1;
-                }
-                seen.insert(res.clone());
-                stack.push((res.clone(), count + 1));
-            }
+    for (i, element) in molecule.iter().enumerate() {
+        for r in replacements.iter().filter(|r| r.from == *element) {
+            let str = format!(
+                "{}{}{}",
+                molecule[..i].concat(),
+                r.to.concat(),
+                molecule[(i + 1)..].concat()
+            );
+            result.insert(str);
         }
     }
 
-    panic!("Bruh! Did not find a solution.");
+    return result;
 }
 
-fn part2() {
-    let res = find_fewest_reductions_possible(MOLECULE, "e", &REPLACEMENTS);
-    println!("Day 19B: {}", res);
+fn part1(machine: &Machine) {
+    let result = expand_once(&machine.molecule, &machine.replacements);
+    println!("Day 19A: {}", result.len());
+}
+
+// the replacements are the rules of a grammar, and the molecule has to be
+// derived from the electron. the fewest rules used is the fewest steps.
+fn make_grammar(replacements: &[Replacement]) -> Grammar {
+    let mut grammar = Grammar::new();
+    for r in replacements {
+        let to: Vec<&str> = r.to.iter().map(|s| s.as_str()).collect();
+        grammar.add_rule(&r.from, &to);
+    }
+    return grammar;
+}
+
+fn find_fabrication(machine: &Machine, start: &str) -> Option<Tree> {
+    let grammar = make_grammar(&machine.replacements);
+    let tokens: Vec<&str> = machine.molecule.iter().map(|s| s.as_str()).collect();
+    return grammar.parse(start, &tokens);
+}
+
+fn part2(machine: &Machine) {
+    match find_fabrication(machine, "e") {
+        Some(tree) => println!("Day 19B: {}", tree.steps()),
+        None => println!("Day 19B: the molecule cannot be made"),
+    }
 }
 
 mod tests {
     use crate::day19::*;
 
+    const EXAMPLE_INPUT: &'static str = r#"
+e => H
+e => O
+H => HO
+H => OH
+O => HH
+
+HOH
+"#;
+
+    #[test]
+    fn expand_the_example() {
+        let machine = parse_machine(EXAMPLE_INPUT).unwrap();
+        assert_eq!(
+            4,
+            expand_once(&machine.molecule, &machine.replacements).len()
+        );
+
+        let machine = parse_machine(&EXAMPLE_INPUT.replace("HOH", "HOHOHO")).unwrap();
+        assert_eq!(
+            7,
+            expand_once(&machine.molecule, &machine.replacements).len()
+        );
+    }
+
+    #[test]
+    fn fabricate_the_example() {
+        let machine = parse_machine(EXAMPLE_INPUT).unwrap();
+        let tree = find_fabrication(&machine, "e").unwrap();
+        assert_eq!(3, tree.steps());
+        assert_eq!("e(O(H H(O H)))", tree.to_string());
+
+        let machine = parse_machine(&EXAMPLE_INPUT.replace("HOH", "HOHOHO")).unwrap();
+        assert_eq!(6, find_fabrication(&machine, "e").unwrap().steps());
+    }
+
     #[test]
     fn should_work_on_non_coincidental_stuff() {
-        let res = find_fewest_reductions_possible("ABCA", "E", &[("D", "A"), ("E", "ABCD")]);
-        assert_eq!(2, res);
+        let machine = parse_machine("D => A\nE => ABCD\nABCA").unwrap();
+        let tree = find_fabrication(&machine, "E").unwrap();
+
+        assert_eq!(2, tree.steps());
+        assert_eq!("E(A B C D(A))", tree.to_string());
+    }
+
+    #[test]
+    fn split_into_elements() {
+        assert_eq!(
+            vec!["Ca", "Si", "Rn", "F", "Y"],
+            tokenize(1, "CaSiRnFY").unwrap()
+        );
+        assert_eq!(vec!["e"], tokenize(1, "e").unwrap());
+
+        let err = tokenize(3, "CaSi2").unwrap_err();
+        assert_eq!(
+            (3, 5, "an element".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse_machine("Ca => CaCa\nCa => Si").unwrap_err();
+        assert_eq!(
+            (2, 9, "a molecule".to_string()),
+            (err.line, err.column, err.expected)
+        );
     }
 }
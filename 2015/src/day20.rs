@@ -0,0 +1,75 @@
+pub fn run() {
+    let path = "src/day20_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 20: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let target: u64 = match input.trim().parse() {
+        Ok(target) => target,
+        Err(_) => {
+            println!("Day 20: expected a number, got {:?}", input.trim());
+            return;
+        }
+    };
+
+    println!("Day 20A: {}", first_house(target, 10, None));
+    println!("Day 20B: {}", first_house(target, 11, Some(50)));
+}
+
+// the presents at houses 0..=limit. elf n brings "per_house * n" presents
+// to every n-th house, and stops after "max_houses" of them when there is
+// a limit. this is a sieve over the divisors, so it costs n log n.
+fn presents(limit: usize, per_house: u64, max_houses: Option<usize>) -> Vec<u64> {
+    let mut out = vec![0; limit + 1];
+    for elf in 1..=limit {
+        let last = match max_houses {
+            Some(max) => std::cmp::min(limit, elf.saturating_mul(max)),
+            None => limit,
+        };
+        for house in (elf..=last).step_by(elf) {
+            out[house] += per_house * elf as u64;
+        }
+    }
+    return out;
+}
+
+fn first_house(target: u64, per_house: u64, max_houses: Option<usize>) -> usize {
+    // house n always gets at least what elf n brings, so no house past
+    // this one has to be checked.
+    let limit = std::cmp::max(1, (target + per_house - 1) / per_house) as usize;
+    return presents(limit, per_house, max_houses)
+        .iter()
+        .enumerate()
+        .skip(1)
+        .find(|(_, p)| **p >= target)
+        .map(|(house, _)| house)
+        .unwrap();
+}
+
+mod tests {
+    use crate::day20::*;
+
+    #[test]
+    fn deliver_the_example() {
+        assert_eq!(
+            vec![0, 10, 30, 40, 70, 60, 120, 80, 150, 130],
+            presents(9, 10, None)
+        );
+        assert_eq!(6, first_house(100, 10, None));
+        assert_eq!(8, first_house(150, 10, None));
+        assert_eq!(1, first_house(1, 10, None));
+    }
+
+    #[test]
+    fn let_elves_stop() {
+        // elf 1 only visits houses 1 and 2.
+        assert_eq!(
+            vec![0, 11, 33, 33, 66, 55, 99, 77, 132, 99],
+            presents(9, 11, Some(2))
+        );
+        assert_eq!(presents(100, 11, None), presents(100, 11, Some(100)));
+    }
+}
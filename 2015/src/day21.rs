@@ -0,0 +1,240 @@
+use crate::combinatorics::combinations;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+
+const PLAYER_HIT_POINTS: i64 = 100;
+
+pub fn run() {
+    let path = "src/day21_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 21: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let boss = match parse_stats(&input) {
+        Ok(boss) => boss,
+        Err(err) => {
+            println!("Day 21: {}", err);
+            return;
+        }
+    };
+
+    let outcomes = every_outcome(&boss);
+    let cheapest_win = outcomes
+        .iter()
+        .filter(|(_, won)| *won)
+        .min_by_key(|(l, _)| l.cost);
+    let dearest_loss = outcomes
+        .iter()
+        .filter(|(_, won)| !*won)
+        .max_by_key(|(l, _)| l.cost);
+
+    match cheapest_win {
+        Some((l, _)) => println!("Day 21A: {} ({})", l.cost, format_loadout(l)),
+        None => println!("Day 21A: the boss cannot be beaten"),
+    }
+    match dearest_loss {
+        Some((l, _)) => println!("Day 21B: {} ({})", l.cost, format_loadout(l)),
+        None => println!("Day 21B: the boss cannot win"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stats {
+    hit_points: i64,
+    damage: i64,
+    armor: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Item {
+    name: &'static str,
+    cost: i64,
+    damage: i64,
+    armor: i64,
+}
+
+const fn item(name: &'static str, cost: i64, damage: i64, armor: i64) -> Item {
+    return Item {
+        name,
+        cost,
+        damage,
+        armor,
+    };
+}
+
+const WEAPONS: [Item; 5] = [
+    item("Dagger", 8, 4, 0),
+    item("Shortsword", 10, 5, 0),
+    item("Warhammer", 25, 6, 0),
+    item("Longsword", 40, 7, 0),
+    item("Greataxe", 74, 8, 0),
+];
+
+const ARMOR: [Item; 5] = [
+    item("Leather", 13, 0, 1),
+    item("Chainmail", 31, 0, 2),
+    item("Splintmail", 53, 0, 3),
+    item("Bandedmail", 75, 0, 4),
+    item("Platemail", 102, 0, 5),
+];
+
+const RINGS: [Item; 6] = [
+    item("Damage +1", 25, 1, 0),
+    item("Damage +2", 50, 2, 0),
+    item("Damage +3", 100, 3, 0),
+    item("Defense +1", 20, 0, 1),
+    item("Defense +2", 40, 0, 2),
+    item("Defense +3", 80, 0, 3),
+];
+
+fn parse_stats(input: &str) -> Result<Stats, ParseError> {
+    let mut stats = Stats {
+        hit_points: 0,
+        damage: 0,
+        armor: 0,
+    };
+
+    for (n, line) in numbered_lines(input) {
+        let (name, value) = scan!((n, line), "{}: {}", &str, i64)?;
+        match name {
+            "Hit Points" => stats.hit_points = value,
+            "Damage" => stats.damage = value,
+            "Armor" => stats.armor = value,
+            _ => {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    name,
+                    "'Hit Points', 'Damage' or 'Armor'",
+                ))
+            }
+        }
+    }
+
+    return Ok(stats);
+}
+
+// the player hits first, so they win when the boss falls in as many
+// rounds as the player does, or fewer.
+fn player_wins(player: &Stats, boss: &Stats) -> bool {
+    let rounds_to_fall = |defender: &Stats, attacker: &Stats| -> i64 {
+        let hit = std::cmp::max(1, attacker.damage - defender.armor);
+        return (defender.hit_points + hit - 1) / hit;
+    };
+    return rounds_to_fall(boss, player) <= rounds_to_fall(player, boss);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Loadout {
+    items: Vec<Item>,
+    cost: i64,
+}
+
+// one weapon, at most one armor and at most two different rings.
+fn loadouts() -> Vec<Loadout> {
+    let mut out = Vec::new();
+
+    for weapon in WEAPONS {
+        for armor in (0..=ARMOR.len()).map(|i| ARMOR.get(i)) {
+            for ring_count in 0..=2 {
+                for rings in combinations(RINGS.len(), ring_count) {
+                    let mut items = vec![weapon];
+                    items.extend(armor);
+                    items.extend(rings.iter().map(|i| RINGS[*i]));
+
+                    let cost = items.iter().map(|i| i.cost).sum();
+                    out.push(Loadout { items, cost });
+                }
+            }
+        }
+    }
+
+    return out;
+}
+
+fn format_loadout(loadout: &Loadout) -> String {
+    return loadout
+        .items
+        .iter()
+        .map(|i| i.name)
+        .collect::<Vec<_>>()
+        .join(", ");
+}
+
+fn every_outcome(boss: &Stats) -> Vec<(Loadout, bool)> {
+    return loadouts()
+        .into_iter()
+        .map(|loadout| {
+            let player = Stats {
+                hit_points: PLAYER_HIT_POINTS,
+                damage: loadout.items.iter().map(|i| i.damage).sum(),
+                armor: loadout.items.iter().map(|i| i.armor).sum(),
+            };
+            let won = player_wins(&player, boss);
+            return (loadout, won);
+        })
+        .collect();
+}
+
+mod tests {
+    use crate::day21::*;
+
+    #[test]
+    fn fight_the_example() {
+        let player = Stats {
+            hit_points: 8,
+            damage: 5,
+            armor: 5,
+        };
+        let boss = Stats {
+            hit_points: 12,
+            damage: 7,
+            armor: 2,
+        };
+        assert!(player_wins(&player, &boss));
+
+        // one more hit point is one more round for the boss.
+        let boss = Stats {
+            hit_points: 13,
+            ..boss
+        };
+        assert!(!player_wins(&player, &boss));
+    }
+
+    #[test]
+    fn go_shopping() {
+        // 5 weapons * 6 armor choices * (1 + 6 + 15) ring choices.
+        let all = loadouts();
+        assert_eq!(5 * 6 * 22, all.len());
+        assert_eq!(8, all.iter().map(|l| l.cost).min().unwrap());
+        assert_eq!(
+            74 + 102 + 100 + 80,
+            all.iter().map(|l| l.cost).max().unwrap()
+        );
+
+        let boss = parse_stats("Hit Points: 12\nDamage: 7\nArmor: 2\n").unwrap();
+        assert_eq!(
+            Stats {
+                hit_points: 12,
+                damage: 7,
+                armor: 2
+            },
+            boss
+        );
+        let outcomes = every_outcome(&boss);
+        assert!(outcomes.iter().all(|(_, won)| *won));
+        let picked = vec![WEAPONS[0], ARMOR[0], RINGS[0], RINGS[5]];
+        let loadout = all.iter().find(|l| l.items == picked).unwrap();
+        assert_eq!(8 + 13 + 25 + 80, loadout.cost);
+        assert_eq!(
+            "Dagger, Leather, Damage +1, Defense +3",
+            format_loadout(loadout)
+        );
+
+        let err = parse_stats("Hit Points: 12\nSpeed: 3").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+    }
+}
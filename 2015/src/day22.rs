@@ -0,0 +1,355 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+pub fn run() {
+    let path = "src/day22_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 22: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let duel = match parse_boss(&input) {
+        Ok((hit_points, damage)) => Duel::new(50, 500, hit_points, damage),
+        Err(err) => {
+            println!("Day 22: {}", err);
+            return;
+        }
+    };
+
+    part("A", duel, Difficulty::Normal);
+    part("B", duel, Difficulty::Hard);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Spell {
+    MagicMissile,
+    Drain,
+    Shield,
+    Poison,
+    Recharge,
+}
+
+const SPELLS: [Spell; 5] = [
+    Spell::MagicMissile,
+    Spell::Drain,
+    Spell::Shield,
+    Spell::Poison,
+    Spell::Recharge,
+];
+
+impl Spell {
+    fn cost(self) -> i64 {
+        return match self {
+            Spell::MagicMissile => 53,
+            Spell::Drain => 73,
+            Spell::Shield => 113,
+            Spell::Poison => 173,
+            Spell::Recharge => 229,
+        };
+    }
+
+    fn name(self) -> &'static str {
+        return match self {
+            Spell::MagicMissile => "Magic Missile",
+            Spell::Drain => "Drain",
+            Spell::Shield => "Shield",
+            Spell::Poison => "Poison",
+            Spell::Recharge => "Recharge",
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Difficulty {
+    Normal,
+    // the player loses a hit point at the start of each of their turns.
+    Hard,
+}
+
+// everything that can change during a fight. the effects are the number
+// of turns they still last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Duel {
+    player_hit_points: i64,
+    mana: i64,
+    boss_hit_points: i64,
+    boss_damage: i64,
+    shield: u8,
+    poison: u8,
+    recharge: u8,
+}
+
+impl Duel {
+    fn new(player_hit_points: i64, mana: i64, boss_hit_points: i64, boss_damage: i64) -> Self {
+        return Self {
+            player_hit_points,
+            mana,
+            boss_hit_points,
+            boss_damage,
+            shield: 0,
+            poison: 0,
+            recharge: 0,
+        };
+    }
+
+    fn is_won(&self) -> bool {
+        return self.boss_hit_points <= 0;
+    }
+
+    // runs the effects at the start of a turn and returns the player's armor.
+    fn apply_effects(&mut self) -> i64 {
+        let mut armor = 0;
+        if self.shield > 0 {
+            armor = 7;
+            self.shield -= 1;
+        }
+        if self.poison > 0 {
+            self.boss_hit_points -= 3;
+            self.poison -= 1;
+        }
+        if self.recharge > 0 {
+            self.mana += 101;
+            self.recharge -= 1;
+        }
+        return armor;
+    }
+
+    // one turn for the player and one for the boss, and whether the spell
+    // was cast at all, as the effects can win the fight before it is. None
+    // when the spell cannot be cast or the player does not survive the round.
+    fn play_round(&self, spell: Spell, difficulty: Difficulty) -> Option<(Duel, bool)> {
+        let mut next = *self;
+
+        if difficulty == Difficulty::Hard {
+            next.player_hit_points -= 1;
+            if next.player_hit_points <= 0 {
+                return None;
+            }
+        }
+        next.apply_effects();
+        if next.is_won() {
+            return Some((next, false));
+        }
+
+        if spell.cost() > next.mana {
+            return None;
+        }
+        next.mana -= spell.cost();
+        match spell {
+            Spell::MagicMissile => next.boss_hit_points -= 4,
+            Spell::Drain => {
+                next.boss_hit_points -= 2;
+                next.player_hit_points += 2;
+            }
+            Spell::Shield if next.shield == 0 => next.shield = 6,
+            Spell::Poison if next.poison == 0 => next.poison = 6,
+            Spell::Recharge if next.recharge == 0 => next.recharge = 5,
+            // an effect cannot be cast while it is still running.
+            _ => return None,
+        }
+        if next.is_won() {
+            return Some((next, true));
+        }
+
+        let armor = next.apply_effects();
+        if next.is_won() {
+            return Some((next, true));
+        }
+        next.player_hit_points -= std::cmp::max(1, next.boss_damage - armor);
+        if next.player_hit_points <= 0 {
+            return None;
+        }
+
+        return Some((next, true));
+    }
+}
+
+// dijkstra over the states of the fight, with the mana spent as the
+// distance. returns the mana and the spells, in the order they were cast.
+fn least_mana_to_win(start: Duel, difficulty: Difficulty) -> Option<(i64, Vec<Spell>)> {
+    let mut queue = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    queue.push(Reverse((0, start, Vec::new())));
+
+    while let Some(Reverse((spent, duel, spells))) = queue.pop() {
+        if duel.is_won() {
+            return Some((spent, spells));
+        }
+        if !seen.insert(duel) {
+            continue;
+        }
+
+        for spell in SPELLS {
+            match duel.play_round(spell, difficulty) {
+                Some((next, true)) => {
+                    let mut spells = spells.clone();
+                    spells.push(spell);
+                    queue.push(Reverse((spent + spell.cost(), next, spells)));
+                }
+                // nothing was spent, so any spell leads to the same place.
+                Some((next, false)) => {
+                    queue.push(Reverse((spent, next, spells.clone())));
+                    break;
+                }
+                None => (),
+            }
+        }
+    }
+
+    return None;
+}
+
+// both the hit points and the damage have to be there.
+fn parse_boss(input: &str) -> Result<(i64, i64), ParseError> {
+    let mut hit_points = None;
+    let mut damage = None;
+    let mut last = (1, "");
+
+    for (n, line) in numbered_lines(input) {
+        let (name, value) = scan!((n, line), "{}: {}", &str, i64)?;
+        last = (n, line);
+        match name {
+            "Hit Points" => hit_points = Some(value),
+            "Damage" => damage = Some(value),
+            _ => {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    name,
+                    "'Hit Points' or 'Damage'",
+                ))
+            }
+        }
+    }
+
+    return match (hit_points, damage) {
+        (Some(hit_points), Some(damage)) => Ok((hit_points, damage)),
+        (None, _) => Err(ParseError::at_end(last.0, last.1, "'Hit Points'")),
+        (_, None) => Err(ParseError::at_end(last.0, last.1, "'Damage'")),
+    };
+}
+
+fn format_spells(spells: &[Spell]) -> String {
+    return spells
+        .iter()
+        .map(|s| s.name())
+        .collect::<Vec<_>>()
+        .join(", ");
+}
+
+fn part(name: &str, duel: Duel, difficulty: Difficulty) {
+    match least_mana_to_win(duel, difficulty) {
+        Some((mana, spells)) => println!("Day 22{}: {} ({})", name, mana, format_spells(&spells)),
+        None => println!("Day 22{}: the boss cannot be beaten", name),
+    }
+}
+
+mod tests {
+    use crate::day22::*;
+
+    #[test]
+    fn fight_the_examples() {
+        let first = Duel::new(10, 250, 13, 8);
+        assert_eq!(
+            Some((226, vec![Spell::Poison, Spell::MagicMissile])),
+            least_mana_to_win(first, Difficulty::Normal)
+        );
+
+        let second = Duel::new(10, 250, 14, 8);
+        let (mana, spells) = least_mana_to_win(second, Difficulty::Normal).unwrap();
+        assert_eq!(641, mana);
+        assert_eq!(
+            "Recharge, Shield, Drain, Poison, Magic Missile",
+            format_spells(&spells)
+        );
+    }
+
+    #[test]
+    fn play_the_second_example_round_by_round() {
+        let mut duel = Duel::new(10, 250, 14, 8);
+        let spells = [
+            Spell::Recharge,
+            Spell::Shield,
+            Spell::Drain,
+            Spell::Poison,
+            Spell::MagicMissile,
+        ];
+        let after: Vec<(i64, i64, i64)> = spells
+            .iter()
+            .map(|spell| {
+                let (next, cast) = duel.play_round(*spell, Difficulty::Normal).unwrap();
+                assert!(cast);
+                duel = next;
+                return (duel.player_hit_points, duel.mana, duel.boss_hit_points);
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                (2, 122, 14),
+                (1, 211, 14),
+                (2, 340, 12),
+                (1, 167, 9),
+                (1, 114, -1)
+            ],
+            after
+        );
+    }
+
+    #[test]
+    fn follow_the_rules() {
+        let duel = Duel::new(10, 250, 13, 8);
+
+        // poison is still running on the next turn.
+        let (next, _) = duel.play_round(Spell::Poison, Difficulty::Normal).unwrap();
+        assert_eq!(None, next.play_round(Spell::Poison, Difficulty::Normal));
+        assert_eq!(
+            None,
+            Duel::new(10, 100, 13, 8).play_round(Spell::Poison, Difficulty::Normal)
+        );
+
+        // on hard, the player is down to zero before they can cast anything.
+        assert_eq!(
+            None,
+            Duel::new(1, 250, 13, 8).play_round(Spell::Shield, Difficulty::Hard)
+        );
+        assert_eq!(
+            None,
+            least_mana_to_win(Duel::new(10, 250, 13, 8), Difficulty::Hard)
+        );
+
+        // poison finishes the boss before the next spell has to be paid for.
+        let (next, cast) = Duel::new(50, 500, 4, 8)
+            .play_round(Spell::Poison, Difficulty::Normal)
+            .unwrap();
+        assert!(cast);
+        let (_, cast) = next
+            .play_round(Spell::Recharge, Difficulty::Normal)
+            .unwrap();
+        assert!(!cast);
+        // the last tick of poison wins on the fourth turn, with nothing cast.
+        assert_eq!(
+            Some((
+                279,
+                vec![Spell::Poison, Spell::MagicMissile, Spell::MagicMissile]
+            )),
+            least_mana_to_win(Duel::new(50, 500, 24, 8), Difficulty::Normal)
+        );
+
+        assert_eq!((58, 9), parse_boss("Hit Points: 58\nDamage: 9\n").unwrap());
+        let err = parse_boss("Hit Points: 58\n").unwrap_err();
+        assert_eq!(
+            (1, 15, "'Damage'".to_string()),
+            (err.line, err.column, err.expected)
+        );
+        assert_eq!("'Hit Points'", parse_boss("").unwrap_err().expected);
+        assert_eq!(
+            3,
+            parse_boss("Hit Points: 58\n  Armor: 9").unwrap_err().column
+        );
+    }
+}
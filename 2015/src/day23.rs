@@ -0,0 +1,183 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
+
+pub fn run() {
+    let path = "src/day23_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 23: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let program = match parse_program(&input) {
+        Ok(program) => program,
+        Err(err) => {
+            println!("Day 23: {}", err);
+            return;
+        }
+    };
+
+    println!("Day 23A: {}", execute(&program, [0, 0])[1]);
+    println!("Day 23B: {}", execute(&program, [1, 0])[1]);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instruction {
+    Half(usize),
+    Triple(usize),
+    Increment(usize),
+    Jump(i64),
+    JumpIfEven(usize, i64),
+    JumpIfOne(usize, i64),
+}
+
+fn parse_program(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut out = Vec::new();
+
+    for (n, line) in numbered_lines(input) {
+        let line = line.trim();
+        let (op, args) = line.split_once(' ').unwrap_or((line, ""));
+        let args: Vec<&str> = args.split(',').map(|a| a.trim()).collect();
+
+        let register = |arg: &str| -> Result<usize, ParseError> {
+            return match arg {
+                "a" => Ok(0),
+                "b" => Ok(1),
+                _ => Err(ParseError::at_token(n, line, arg, "'a' or 'b'")),
+            };
+        };
+        // the offsets are written like "+2" or "-7", and i64 reads both.
+        let offset = |arg: &str| parse_token::<i64>(n, line, arg, "an offset");
+        let arg_count = |count: usize| -> Result<(), ParseError> {
+            if args.len() != count {
+                let expected = format!("{} argument(s)", count);
+                return Err(ParseError::at_end(n, line, &expected));
+            }
+            return Ok(());
+        };
+
+        let instruction = match op {
+            "hlf" | "tpl" | "inc" => {
+                arg_count(1)?;
+                let r = register(args[0])?;
+                match op {
+                    "hlf" => Instruction::Half(r),
+                    "tpl" => Instruction::Triple(r),
+                    _ => Instruction::Increment(r),
+                }
+            }
+            "jmp" => {
+                arg_count(1)?;
+                Instruction::Jump(offset(args[0])?)
+            }
+            "jie" | "jio" => {
+                arg_count(2)?;
+                let r = register(args[0])?;
+                let o = offset(args[1])?;
+                match op {
+                    "jie" => Instruction::JumpIfEven(r, o),
+                    _ => Instruction::JumpIfOne(r, o),
+                }
+            }
+            _ => {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    op,
+                    "hlf, tpl, inc, jmp, jie or jio",
+                ))
+            }
+        };
+        out.push(instruction);
+    }
+
+    return Ok(out);
+}
+
+// runs until the program counter leaves the program, and returns the
+// registers a and b.
+fn execute(program: &[Instruction], mut registers: [u64; 2]) -> [u64; 2] {
+    let mut pc: i64 = 0;
+
+    while pc >= 0 && (pc as usize) < program.len() {
+        pc += match program[pc as usize] {
+            Instruction::Half(r) => {
+                registers[r] /= 2;
+                1
+            }
+            Instruction::Triple(r) => {
+                registers[r] *= 3;
+                1
+            }
+            Instruction::Increment(r) => {
+                registers[r] += 1;
+                1
+            }
+            Instruction::Jump(offset) => offset,
+            Instruction::JumpIfEven(r, offset) if registers[r] % 2 == 0 => offset,
+            Instruction::JumpIfOne(r, offset) if registers[r] == 1 => offset,
+            Instruction::JumpIfEven(..) | Instruction::JumpIfOne(..) => 1,
+        };
+    }
+
+    return registers;
+}
+
+mod tests {
+    use crate::day23::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+inc a
+jio a, +2
+tpl a
+inc a
+"#;
+
+    #[test]
+    fn run_the_example() {
+        let program = parse_program(EXAMPLE_INPUT).unwrap();
+        assert_eq!(
+            vec![
+                Instruction::Increment(0),
+                Instruction::JumpIfOne(0, 2),
+                Instruction::Triple(0),
+                Instruction::Increment(0)
+            ],
+            program
+        );
+        assert_eq!([2, 0], execute(&program, [0, 0]));
+        assert_eq!([7, 0], execute(&program, [1, 0]));
+    }
+
+    #[test]
+    fn count_collatz_steps() {
+        // the puzzle programs count the steps for a to reach 1.
+        let program = parse_program(
+            r#"
+jio a, +8
+inc b
+jie a, +4
+tpl a
+inc a
+jmp +2
+hlf a
+jmp -7
+"#,
+        )
+        .unwrap();
+        assert_eq!([1, 111], execute(&program, [27, 0]));
+        assert_eq!([1, 0], execute(&program, [1, 0]));
+    }
+
+    #[test]
+    fn point_at_bad_instructions() {
+        let check = |line: &str, column: usize, expected: &str| {
+            let err = parse_program(line).unwrap_err();
+            assert_eq!((column, expected.to_string()), (err.column, err.expected));
+        };
+        check("inc c", 5, "'a' or 'b'");
+        check("jmp two", 5, "an offset");
+        check("jio a", 6, "2 argument(s)");
+        check("nop", 1, "hlf, tpl, inc, jmp, jie or jio");
+    }
+}
@@ -0,0 +1,142 @@
+use crate::combinatorics::{combinations, MAX_ITEMS};
+use crate::parse::{numbered_lines, parse_token, ParseError};
+
+pub fn run() {
+    let path = "src/day24_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 24: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let weights = match parse_weights(&input) {
+        Ok(weights) => weights,
+        Err(err) => {
+            println!("Day 24: {}", err);
+            return;
+        }
+    };
+
+    for (name, groups) in [("A", 3), ("B", 4)] {
+        match best_entanglement(&weights, groups) {
+            Some(qe) => println!("Day 24{}: {}", name, qe),
+            None => println!("Day 24{}: the packages cannot be balanced", name),
+        }
+    }
+}
+
+fn parse_weights(input: &str) -> Result<Vec<u64>, ParseError> {
+    let weights: Vec<u64> = numbered_lines(input)
+        .map(|(n, line)| parse_token(n, line, line.trim(), "a package weight"))
+        .collect::<Result<_, _>>()?;
+
+    if weights.len() > MAX_ITEMS {
+        let (n, line) = numbered_lines(input).nth(MAX_ITEMS).unwrap();
+        let expected = format!("at most {} packages", MAX_ITEMS);
+        return Err(ParseError::at_token(n, line, line.trim(), &expected));
+    }
+    return Ok(weights);
+}
+
+// the quantum entanglement of the passenger compartment. it gets as few
+// packages as possible, and the lowest product of weights among those,
+// as long as the rest still splits into equal groups.
+fn best_entanglement(weights: &[u64], groups: usize) -> Option<u64> {
+    let total: u64 = weights.iter().sum();
+    if groups == 0 || total % groups as u64 != 0 {
+        return None;
+    }
+    let target = total / groups as u64;
+
+    for size in 1..=weights.len() {
+        let mut candidates: Vec<(u64, Vec<usize>)> = combinations(weights.len(), size)
+            .filter(|picked| picked.iter().map(|i| weights[*i]).sum::<u64>() == target)
+            .map(|picked| {
+                let qe = picked.iter().map(|i| weights[*i]).product();
+                return (qe, picked.to_vec());
+            })
+            .collect();
+        candidates.sort();
+
+        for (qe, picked) in candidates {
+            let rest: Vec<u64> = (0..weights.len())
+                .filter(|i| !picked.contains(i))
+                .map(|i| weights[i])
+                .collect();
+            if can_split(&rest, groups - 1, target) {
+                return Some(qe);
+            }
+        }
+    }
+
+    return None;
+}
+
+// whether "weights" fit exactly into "groups" bins that each hold "target".
+fn can_split(weights: &[u64], groups: usize, target: u64) -> bool {
+    if groups <= 1 {
+        return weights.iter().sum::<u64>() == target * groups as u64;
+    }
+
+    let mut sorted = weights.to_vec();
+    sorted.sort_by(|a, b| b.cmp(a));
+    let mut bins = vec![0; groups];
+    return fill_bins(&sorted, &mut bins, target);
+}
+
+fn fill_bins(weights: &[u64], bins: &mut [u64], target: u64) -> bool {
+    let (weight, rest) = match weights.split_first() {
+        Some(split) => split,
+        None => return bins.iter().all(|b| *b == target),
+    };
+
+    for i in 0..bins.len() {
+        // empty bins are all the same, so only try the first of them.
+        if bins[i] + weight > target || (bins[i] == 0 && bins[..i].contains(&0)) {
+            continue;
+        }
+        bins[i] += weight;
+        if fill_bins(rest, bins, target) {
+            return true;
+        }
+        bins[i] -= weight;
+    }
+    return false;
+}
+
+mod tests {
+    use crate::day24::*;
+
+    const EXAMPLE: [u64; 10] = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+
+    #[test]
+    fn balance_the_example() {
+        assert_eq!(Some(99), best_entanglement(&EXAMPLE, 3));
+        assert_eq!(Some(44), best_entanglement(&EXAMPLE, 4));
+        assert_eq!(None, best_entanglement(&EXAMPLE, 7));
+    }
+
+    #[test]
+    fn split_into_bins() {
+        assert!(can_split(&[1, 2, 3, 4, 5], 3, 5));
+        assert!(!can_split(&[2, 2, 2, 4], 2, 5));
+        assert!(!can_split(&[6, 1, 1, 1, 1], 2, 5));
+        assert!(can_split(&[], 1, 0));
+
+        // the 10 is a group on its own, but 9, 9 and 2 do not split.
+        assert_eq!(None, best_entanglement(&[10, 9, 9, 2], 3));
+    }
+
+    #[test]
+    fn parse_the_weights() {
+        assert_eq!(vec![1, 2, 11], parse_weights("1\n2\n\n11").unwrap());
+        let err = parse_weights("1\nten").unwrap_err();
+        assert_eq!(
+            (2, "a package weight".to_string()),
+            (err.line, err.expected)
+        );
+        let many = "1\n".repeat(MAX_ITEMS + 1);
+        assert_eq!(MAX_ITEMS + 1, parse_weights(&many).unwrap_err().line);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::parse::ParseError;
+use crate::scan;
+
+const FIRST_CODE: u64 = 20151125;
+const MULTIPLIER: u64 = 252533;
+const MODULUS: u64 = 33554393;
+
+pub fn run() {
+    let path = "src/day25_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 25: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    match parse_position(&input) {
+        Ok((row, column)) => println!("Day 25: {}", code_at(row, column)),
+        Err(err) => println!("Day 25: {}", err),
+    }
+}
+
+fn parse_position(input: &str) -> Result<(u64, u64), ParseError> {
+    let (row, column) = scan!(
+        input.trim(),
+        "To continue, please consult the code grid in the manual.  Enter the code at row {}, column {}.",
+        u64,
+        u64
+    )?;
+    if row == 0 || column == 0 {
+        return Err(ParseError::at_end(
+            1,
+            input.trim(),
+            "a row and column from 1",
+        ));
+    }
+    return Ok((row, column));
+}
+
+// the codes are filled in along the diagonals, from the bottom left to
+// the top right. this is where (row, column) comes in that order, from 1.
+fn order_of(row: u64, column: u64) -> u64 {
+    let diagonal = row + column - 1;
+    return diagonal * (diagonal - 1) / 2 + column;
+}
+
+fn mod_pow(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut out = 1;
+    base %= modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            out = out * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    return out;
+}
+
+// each code is the one before it times the multiplier, so there is no
+// need to go through all of them.
+fn code_at(row: u64, column: u64) -> u64 {
+    let steps = order_of(row, column) - 1;
+    return FIRST_CODE * mod_pow(MULTIPLIER, steps, MODULUS) % MODULUS;
+}
+
+mod tests {
+    use crate::day25::*;
+
+    #[test]
+    fn number_the_grid() {
+        let orders: Vec<Vec<u64>> = (1..=4)
+            .map(|row| (1..=4).map(|column| order_of(row, column)).collect())
+            .collect();
+        assert_eq!(
+            vec![
+                vec![1, 3, 6, 10],
+                vec![2, 5, 9, 14],
+                vec![4, 8, 13, 19],
+                vec![7, 12, 18, 25]
+            ],
+            orders
+        );
+    }
+
+    #[test]
+    fn fill_in_the_codes() {
+        let expected = [
+            [20151125, 18749137, 17289845, 30943339, 10071777, 33511524],
+            [31916031, 21629792, 16929656, 7726640, 15514188, 4041754],
+            [16080970, 8057251, 1601130, 7981243, 11661866, 16474243],
+            [24592653, 32451966, 21345942, 9380097, 10600672, 31527494],
+            [77061, 17552253, 28094349, 6899651, 9250759, 31663883],
+            [33071741, 6796745, 25397450, 24659492, 1534922, 27995004],
+        ];
+        for (row, codes) in expected.iter().enumerate() {
+            for (column, code) in codes.iter().enumerate() {
+                assert_eq!(*code, code_at(row as u64 + 1, column as u64 + 1));
+            }
+        }
+
+        // the same as going one code at a time.
+        let mut code = FIRST_CODE;
+        for _ in 1..order_of(30, 40) {
+            code = code * MULTIPLIER % MODULUS;
+        }
+        assert_eq!(code, code_at(30, 40));
+    }
+
+    #[test]
+    fn parse_the_position() {
+        let text = "To continue, please consult the code grid in the manual.  Enter the code at row 4, column 2.\n";
+        assert_eq!((4, 2), parse_position(text).unwrap());
+        assert!(parse_position("row 4, column 2").is_err());
+    }
+}
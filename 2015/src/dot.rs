@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+// a graphviz graph, written in the order nodes and edges were added so
+// that the output is stable. render with "dot -Tsvg graphs/x.dot".
+#[derive(Debug, Clone)]
+pub struct Graph {
+    name: String,
+    directed: bool,
+    attrs: Vec<(String, String)>,
+    nodes: Vec<(String, Vec<(String, String)>)>,
+    edges: Vec<(String, String, Vec<(String, String)>)>,
+}
+
+impl Graph {
+    pub fn directed(name: &str) -> Self {
+        return Self::new(name, true);
+    }
+
+    pub fn undirected(name: &str) -> Self {
+        return Self::new(name, false);
+    }
+
+    fn new(name: &str, directed: bool) -> Self {
+        return Self {
+            name: name.to_string(),
+            directed,
+            attrs: Vec::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+    }
+
+    // sets an attribute of the whole graph, like "rankdir".
+    pub fn attr(&mut self, key: &str, value: &str) {
+        self.attrs.push((key.to_string(), value.to_string()));
+    }
+
+    pub fn node(&mut self, id: &str, attrs: &[(&str, &str)]) {
+        self.nodes.push((id.to_string(), to_owned_attrs(attrs)));
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attrs: &[(&str, &str)]) {
+        self.edges
+            .push((from.to_string(), to.to_string(), to_owned_attrs(attrs)));
+    }
+
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let mut out = String::new();
+
+        writeln!(out, "{} {} {{", keyword, quote(&self.name)).unwrap();
+
+        for (key, value) in &self.attrs {
+            writeln!(out, "    {}={};", key, quote(value)).unwrap();
+        }
+        for (id, attrs) in &self.nodes {
+            writeln!(out, "    {}{};", quote(id), format_attrs(attrs)).unwrap();
+        }
+        for (from, to, attrs) in &self.edges {
+            writeln!(
+                out,
+                "    {} {} {}{};",
+                quote(from),
+                arrow,
+                quote(to),
+                format_attrs(attrs)
+            )
+            .unwrap();
+        }
+
+        out.push_str("}\n");
+
+        return out;
+    }
+}
+
+fn to_owned_attrs(attrs: &[(&str, &str)]) -> Vec<(String, String)> {
+    return attrs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+}
+
+fn format_attrs(attrs: &[(String, String)]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let parts: Vec<String> = attrs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, quote(v)))
+        .collect();
+    return format!(" [{}]", parts.join(", "));
+}
+
+// every id is quoted, so any name is valid.
+fn quote(value: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+    return out;
+}
+
+pub fn is_enabled() -> bool {
+    return std::env::args().any(|arg| arg == "--dot");
+}
+
+pub fn save(name: &str, graph: &Graph) {
+    let dir = std::path::Path::new("graphs");
+    std::fs::create_dir_all(dir).unwrap();
+
+    let path = dir.join(name);
+    std::fs::write(&path, graph.to_dot()).unwrap();
+
+    println!("Wrote {}", path.display());
+}
+
+mod tests {
+    use crate::dot::*;
+
+    #[test]
+    fn write_a_small_graph() {
+        let mut graph = Graph::directed("example");
+        graph.attr("rankdir", "LR");
+        graph.node("a", &[("label", "say \"hi\"")]);
+        graph.node("b", &[]);
+        graph.edge("a", "b", &[("label", "1")]);
+
+        let expected = r#"digraph "example" {
+    rankdir="LR";
+    "a" [label="say \"hi\""];
+    "b";
+    "a" -> "b" [label="1"];
+}
+"#;
+        assert_eq!(expected, graph.to_dot());
+    }
+
+    #[test]
+    fn write_an_undirected_graph() {
+        let mut graph = Graph::undirected("g");
+        graph.edge("x\\y", "z", &[]);
+
+        assert_eq!(
+            "graph \"g\" {\n    \"x\\\\y\" -- \"z\";\n}\n",
+            graph.to_dot()
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::fmt;
+
+const UNREACHABLE: u32 = u32::MAX;
+
+// a context-free grammar where every rule costs one step. symbols are
+// plain names, and a token matches the symbol with the same name.
+#[derive(Debug, Clone, Default)]
+pub struct Grammar {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    // rules with more than two symbols on the right are split into a chain
+    // of binary ones through helper symbols, which cost nothing.
+    helper: Vec<bool>,
+    helpers: HashMap<Vec<usize>, usize>,
+    binary: Vec<Binary>,
+    by_left: Vec<Vec<usize>>,
+    units: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Binary {
+    lhs: usize,
+    left: usize,
+    right: usize,
+    cost: u32,
+}
+
+impl Grammar {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    fn symbol(&mut self, name: &str) -> usize {
+        if let Some(i) = self.index.get(name) {
+            return *i;
+        }
+        return self.add_symbol(name.to_string(), false);
+    }
+
+    fn add_symbol(&mut self, name: String, helper: bool) -> usize {
+        let i = self.names.len();
+        self.index.insert(name.clone(), i);
+        self.names.push(name);
+        self.helper.push(helper);
+        self.by_left.push(Vec::new());
+        return i;
+    }
+
+    pub fn add_rule(&mut self, lhs: &str, rhs: &[&str]) {
+        assert!(!rhs.is_empty(), "Rules cannot be empty.");
+
+        let lhs = self.symbol(lhs);
+        let rhs: Vec<usize> = rhs.iter().map(|s| self.symbol(s)).collect();
+
+        if rhs.len() == 1 {
+            self.units.push((lhs, rhs[0]));
+            return;
+        }
+
+        let right = self.chain(&rhs[1..]);
+        self.add_binary(lhs, rhs[0], right, 1);
+    }
+
+    // a symbol that stands for the whole of "rhs", shared between rules.
+    fn chain(&mut self, rhs: &[usize]) -> usize {
+        if rhs.len() == 1 {
+            return rhs[0];
+        }
+        if let Some(i) = self.helpers.get(rhs) {
+            return *i;
+        }
+
+        let right = self.chain(&rhs[1..]);
+        let name = format!(
+            "<{}>",
+            rhs.iter()
+                .map(|s| self.names[*s].as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let helper = self.add_symbol(name, true);
+        self.helpers.insert(rhs.to_vec(), helper);
+        self.add_binary(helper, rhs[0], right, 0);
+
+        return helper;
+    }
+
+    fn add_binary(&mut self, lhs: usize, left: usize, right: usize, cost: u32) {
+        self.by_left[left].push(self.binary.len());
+        self.binary.push(Binary {
+            lhs,
+            left,
+            right,
+            cost,
+        });
+    }
+
+    // finds the derivation of the tokens from "start" that uses the fewest
+    // rules, with a weighted cyk chart parser. every span of the input gets
+    // the lowest cost of deriving it from each symbol, built from the
+    // costs of the two shorter spans it splits into.
+    pub fn parse(&self, start: &str, tokens: &[&str]) -> Option<Tree> {
+        let start = *self.index.get(start)?;
+        let tokens: Vec<usize> = tokens
+            .iter()
+            .map(|t| self.index.get(*t).copied())
+            .collect::<Option<_>>()?;
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let chart = Chart::fill(self, &tokens);
+        if chart.cost(0, tokens.len(), start) == UNREACHABLE {
+            return None;
+        }
+
+        let mut trees = chart.build(self, 0, tokens.len(), start);
+        return trees.pop();
+    }
+}
+
+struct Chart {
+    // costs[start][len - 1][symbol].
+    costs: Vec<Vec<Vec<u32>>>,
+    // the symbols that can derive each span, so empty ones are not looked at.
+    present: Vec<Vec<Vec<usize>>>,
+    tokens: Vec<usize>,
+}
+
+impl Chart {
+    fn fill(grammar: &Grammar, tokens: &[usize]) -> Self {
+        let n = tokens.len();
+        let symbols = grammar.names.len();
+        let mut chart = Chart {
+            costs: (0..n).map(|start| Vec::with_capacity(n - start)).collect(),
+            present: (0..n).map(|start| Vec::with_capacity(n - start)).collect(),
+            tokens: tokens.to_vec(),
+        };
+
+        for len in 1..=n {
+            for start in 0..=(n - len) {
+                let mut cell = vec![UNREACHABLE; symbols];
+
+                if len == 1 {
+                    cell[tokens[start]] = 0;
+                }
+
+                for split in 1..len {
+                    let left = &chart.costs[start][split - 1];
+                    let right = &chart.costs[start + split][len - split - 1];
+
+                    for symbol in &chart.present[start][split - 1] {
+                        let left_cost = left[*symbol];
+                        for r in &grammar.by_left[*symbol] {
+                            let rule = grammar.binary[*r];
+                            let right_cost = right[rule.right];
+                            if right_cost == UNREACHABLE {
+                                continue;
+                            }
+                            let cost = left_cost + right_cost + rule.cost;
+                            if cost < cell[rule.lhs] {
+                                cell[rule.lhs] = cost;
+                            }
+                        }
+                    }
+                }
+
+                // unit rules can chain, so keep going until nothing improves.
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for (lhs, rhs) in &grammar.units {
+                        if cell[*rhs] != UNREACHABLE && cell[*rhs] + 1 < cell[*lhs] {
+                            cell[*lhs] = cell[*rhs] + 1;
+                            changed = true;
+                        }
+                    }
+                }
+
+                let present = (0..symbols).filter(|s| cell[*s] != UNREACHABLE).collect();
+                chart.present[start].push(present);
+                chart.costs[start].push(cell);
+            }
+        }
+
+        return chart;
+    }
+
+    fn cost(&self, start: usize, len: usize, symbol: usize) -> u32 {
+        return self.costs[start][len - 1][symbol];
+    }
+
+    // walks back down the chart, looking for whichever rule gave each cost.
+    // helper symbols are left out, so their children end up in the rule
+    // that they were split out of.
+    fn build(&self, grammar: &Grammar, start: usize, len: usize, symbol: usize) -> Vec<Tree> {
+        let cost = self.cost(start, len, symbol);
+        let node = |children: Vec<Tree>| -> Vec<Tree> {
+            if grammar.helper[symbol] {
+                return children;
+            }
+            return vec![Tree {
+                symbol: grammar.names[symbol].clone(),
+                children,
+            }];
+        };
+
+        if len == 1 && cost == 0 && self.tokens[start] == symbol {
+            return node(Vec::new());
+        }
+
+        for (lhs, rhs) in &grammar.units {
+            if *lhs == symbol
+                && self.cost(start, len, *rhs) != UNREACHABLE
+                && self.cost(start, len, *rhs) + 1 == cost
+            {
+                return node(self.build(grammar, start, len, *rhs));
+            }
+        }
+
+        for split in 1..len {
+            for rule in grammar.binary.iter().filter(|r| r.lhs == symbol) {
+                let left = self.cost(start, split, rule.left);
+                let right = self.cost(start + split, len - split, rule.right);
+                if left == UNREACHABLE || right == UNREACHABLE || left + right + rule.cost != cost {
+                    continue;
+                }
+
+                let mut children = self.build(grammar, start, split, rule.left);
+                children.extend(self.build(grammar, start + split, len - split, rule.right));
+                return node(children);
+            }
+        }
+
+        unreachable!("Every cost in the chart comes from some rule.");
+    }
+}
+
+// a leaf is a token, and every other node is one rule being applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tree {
+    pub symbol: String,
+    pub children: Vec<Tree>,
+}
+
+impl Tree {
+    pub fn steps(&self) -> usize {
+        if self.children.is_empty() {
+            return 0;
+        }
+        return 1 + self.children.iter().map(|c| c.steps()).sum::<usize>();
+    }
+}
+
+// like "e(H(H O) F)".
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if self.children.is_empty() {
+            return Ok(());
+        }
+        write!(f, "(")?;
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", child)?;
+        }
+        write!(f, ")")
+    }
+}
+
+mod tests {
+    use crate::grammar::*;
+
+    #[test]
+    fn derive_with_the_fewest_rules() {
+        let mut grammar = Grammar::new();
+        grammar.add_rule("S", &["A", "B", "C"]);
+        grammar.add_rule("S", &["X", "C"]);
+        grammar.add_rule("X", &["Y"]);
+        grammar.add_rule("Y", &["A", "B"]);
+
+        let tree = grammar.parse("S", &["A", "B", "C"]).unwrap();
+        assert_eq!("S(A B C)", tree.to_string());
+        assert_eq!(1, tree.steps());
+
+        let tree = grammar.parse("X", &["A", "B"]).unwrap();
+        assert_eq!("X(Y(A B))", tree.to_string());
+        assert_eq!(2, tree.steps());
+    }
+
+    #[test]
+    fn handle_recursion_and_failures() {
+        let mut grammar = Grammar::new();
+        grammar.add_rule("L", &["L", "L"]);
+        grammar.add_rule("L", &["a"]);
+
+        let tree = grammar.parse("L", &["a", "a", "a"]).unwrap();
+        assert_eq!(5, tree.steps());
+        assert_eq!(3, tree.to_string().matches("L(a)").count());
+
+        assert_eq!(None, grammar.parse("L", &["a", "b"]));
+        assert_eq!(None, grammar.parse("Q", &["a"]));
+        assert_eq!(None, grammar.parse("L", &[]));
+
+        // a token is its own symbol, without applying any rule.
+        assert_eq!(0, grammar.parse("a", &["a"]).unwrap().steps());
+    }
+}
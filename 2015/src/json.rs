@@ -0,0 +1,688 @@
+use crate::parse::ParseError;
+use std::fmt;
+use std::fmt::Write;
+
+// object members keep the order they were read in, so that a document
+// is written back the way it came.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        return match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        };
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        return match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        };
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        return match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        };
+    }
+
+    // true for an object where one of the members is "value".
+    pub fn has_member_value(&self, value: &Value) -> bool {
+        return match self {
+            Value::Object(members) => members.iter().any(|(_, v)| v == value),
+            _ => false,
+        };
+    }
+
+    pub fn to_pretty(&self) -> String {
+        let mut out = String::new();
+        write_pretty(&mut out, self, 0);
+        return out;
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        return Value::String(s.to_string());
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        return Value::Number(n);
+    }
+}
+
+// the compact form, without any whitespace.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_char(']')
+            }
+            Value::Object(members) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}:{}", Quoted(key), value)?;
+                }
+                f.write_char('}')
+            }
+            scalar => write_scalar(f, scalar),
+        }
+    }
+}
+
+fn write_scalar<W: Write>(out: &mut W, value: &Value) -> fmt::Result {
+    return match value {
+        Value::Null => out.write_str("null"),
+        Value::Bool(b) => write!(out, "{}", b),
+        // json has no way to write these.
+        Value::Number(n) if !n.is_finite() => out.write_str("null"),
+        Value::Number(n) => write!(out, "{}", n),
+        Value::String(s) => write!(out, "{}", Quoted(s)),
+        Value::Array(_) | Value::Object(_) => unreachable!(),
+    };
+}
+
+fn write_pretty(out: &mut String, value: &Value, depth: usize) {
+    let indent = "  ".repeat(depth + 1);
+
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(&indent);
+                write_pretty(out, item, depth + 1);
+                out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&indent[2..]);
+            out.push(']');
+        }
+        Value::Object(members) if !members.is_empty() => {
+            out.push_str("{\n");
+            for (i, (key, item)) in members.iter().enumerate() {
+                write!(out, "{}{}: ", indent, Quoted(key)).unwrap();
+                write_pretty(out, item, depth + 1);
+                out.push_str(if i + 1 < members.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&indent[2..]);
+            out.push('}');
+        }
+        Value::Array(_) => out.push_str("[]"),
+        Value::Object(_) => out.push_str("{}"),
+        scalar => write_scalar(out, scalar).unwrap(),
+    }
+}
+
+struct Quoted<'a>(&'a str);
+
+impl fmt::Display for Quoted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for ch in self.0.chars() {
+            match ch {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                '\u{8}' => f.write_str("\\b")?,
+                '\u{c}' => f.write_str("\\f")?,
+                ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser { input, pos: 0 };
+
+    let value = parser.read_value()?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.error("the end of the input"));
+    }
+
+    return Ok(value);
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        return self.input.as_bytes().get(self.pos).copied();
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    // points at the current position, which can be anywhere in the input.
+    fn error(&self, expected: &str) -> ParseError {
+        let line_start = self.input[..self.pos]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let line_end = self.input[line_start..]
+            .find('\n')
+            .map(|i| line_start + i)
+            .unwrap_or(self.input.len());
+        let line = self.input[..line_start].matches('\n').count() + 1;
+        let column = self.input[line_start..self.pos].chars().count() + 1;
+        let source_line = self.input[line_start..line_end].trim_end_matches('\r');
+
+        return ParseError::new(line, source_line, column, expected);
+    }
+
+    fn expect(&mut self, byte: u8, expected: &str) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.error(expected));
+        }
+        self.pos += 1;
+        return Ok(());
+    }
+
+    fn read_value(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+
+        return match self.peek() {
+            Some(b'{') => self.read_object(),
+            Some(b'[') => self.read_array(),
+            Some(b'"') => Ok(Value::String(self.read_string()?)),
+            Some(b'-' | b'0'..=b'9') => self.read_number(),
+            Some(b't') => self.read_literal("true", Value::Bool(true)),
+            Some(b'f') => self.read_literal("false", Value::Bool(false)),
+            Some(b'n') => self.read_literal("null", Value::Null),
+            _ => Err(self.error("a value")),
+        };
+    }
+
+    fn read_literal(&mut self, word: &str, value: Value) -> Result<Value, ParseError> {
+        if !self.input[self.pos..].starts_with(word) {
+            return Err(self.error(word));
+        }
+        self.pos += word.len();
+        return Ok(value);
+    }
+
+    fn read_object(&mut self) -> Result<Value, ParseError> {
+        self.pos += 1;
+        let mut members: Vec<(String, Value)> = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("a string key"));
+            }
+            let key_start = self.pos;
+            let key = self.read_string()?;
+            // a key given twice would lose one of the values.
+            if members.iter().any(|(k, _)| *k == key) {
+                self.pos = key_start;
+                return Err(self.error("a key that is not in the object yet"));
+            }
+            self.expect(b':', "':'")?;
+            let value = self.read_value()?;
+            members.push((key, value));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.error("',' or '}'")),
+            }
+        }
+    }
+
+    fn read_array(&mut self) -> Result<Value, ParseError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.read_value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("',' or ']'")),
+            }
+        }
+    }
+
+    // follows the json grammar, so "01", "1." and ".5" are all rejected.
+    fn read_number(&mut self) -> Result<Value, ParseError> {
+        let start = self.pos;
+
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.error("a digit")),
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.read_digits()?;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            self.read_digits()?;
+        }
+
+        return Ok(Value::Number(self.input[start..self.pos].parse().unwrap()));
+    }
+
+    fn read_digits(&mut self) -> Result<(), ParseError> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(self.error("a digit"));
+        }
+        self.skip_digits();
+        return Ok(());
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, ParseError> {
+        self.pos += 1;
+        let mut out = String::new();
+
+        loop {
+            // everything up to the next quote, backslash or control character
+            // is copied as it is. those are all ascii, so the cut is on a char.
+            let start = self.pos;
+            while let Some(b) = self.peek() {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            out.push_str(&self.input[start..self.pos]);
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    out.push(self.read_escape()?);
+                }
+                Some(_) => return Err(self.error("an escaped control character")),
+                None => return Err(self.error("'\"'")),
+            }
+        }
+    }
+
+    fn read_escape(&mut self) -> Result<char, ParseError> {
+        let ch = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                return self.read_unicode_escape();
+            }
+            _ => return Err(self.error("an escape sequence")),
+        };
+        self.pos += 1;
+        return Ok(ch);
+    }
+
+    // characters outside the basic plane are written as a surrogate pair,
+    // like "\ud83d\ude00".
+    fn read_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let start = self.pos - 2;
+        let high = self.read_hex4()?;
+
+        let code = match high {
+            0xd800..=0xdbff => {
+                if !self.input[self.pos..].starts_with("\\u") {
+                    return Err(self.error("a low surrogate"));
+                }
+                self.pos += 2;
+                let low = self.read_hex4()?;
+                if !(0xdc00..=0xdfff).contains(&low) {
+                    self.pos -= 6;
+                    return Err(self.error("a low surrogate"));
+                }
+                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+            }
+            0xdc00..=0xdfff => {
+                self.pos = start;
+                return Err(self.error("a high surrogate before a low one"));
+            }
+            code => code,
+        };
+
+        return Ok(char::from_u32(code).unwrap());
+    }
+
+    fn read_hex4(&mut self) -> Result<u32, ParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = match self.peek().map(|b| (b as char).to_digit(16)) {
+                Some(Some(d)) => d,
+                _ => return Err(self.error("a hex digit")),
+            };
+            code = code * 16 + digit;
+            self.pos += 1;
+        }
+        return Ok(code);
+    }
+}
+
+// a path like "a.b.0.*" picks values out of a document. each step is an
+// object key, an array index, "*" for every child or "**" for the value
+// itself and everything under it.
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    Children,
+    Descendants,
+}
+
+pub struct Query {
+    steps: Vec<Step>,
+    prune: Vec<Box<dyn Fn(&Value) -> bool>>,
+}
+
+impl Query {
+    // an empty path selects the document itself.
+    pub fn new(path: &str) -> Result<Self, ParseError> {
+        let mut steps = Vec::new();
+
+        if !path.is_empty() {
+            for part in path.split('.') {
+                steps.push(match part {
+                    "" => {
+                        let column = part.as_ptr() as usize - path.as_ptr() as usize + 1;
+                        return Err(ParseError::new(1, path, column, "a key, an index, * or **"));
+                    }
+                    "*" => Step::Children,
+                    "**" => Step::Descendants,
+                    _ => match part.parse() {
+                        Ok(i) => Step::Index(i),
+                        Err(_) => Step::Key(part.to_string()),
+                    },
+                });
+            }
+        }
+
+        return Ok(Self {
+            steps,
+            prune: Vec::new(),
+        });
+    }
+
+    // values where "f" is true are skipped, along with everything under them.
+    pub fn prune<F: Fn(&Value) -> bool + 'static>(mut self, f: F) -> Self {
+        self.prune.push(Box::new(f));
+        return self;
+    }
+
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut out = Vec::new();
+        self.walk(root, &self.steps, &mut out);
+        return out;
+    }
+
+    // adds up every selected number, and ignores everything else.
+    pub fn sum(&self, root: &Value) -> f64 {
+        return self.select(root).iter().filter_map(|v| v.as_f64()).sum();
+    }
+
+    fn walk<'a>(&self, value: &'a Value, steps: &[Step], out: &mut Vec<&'a Value>) {
+        if self.prune.iter().any(|f| f(value)) {
+            return;
+        }
+
+        let (step, rest) = match steps.split_first() {
+            Some(split) => split,
+            None => {
+                out.push(value);
+                return;
+            }
+        };
+
+        match (step, value) {
+            (Step::Key(key), _) => {
+                if let Some(child) = value.get(key) {
+                    self.walk(child, rest, out);
+                }
+            }
+            (Step::Index(i), Value::Array(items)) => {
+                if let Some(child) = items.get(*i) {
+                    self.walk(child, rest, out);
+                }
+            }
+            (Step::Index(_), _) => {}
+            (Step::Children, _) => {
+                for child in children(value) {
+                    self.walk(child, rest, out);
+                }
+            }
+            (Step::Descendants, _) => {
+                self.walk(value, rest, out);
+                for child in children(value) {
+                    self.walk(child, steps, out);
+                }
+            }
+        }
+    }
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    return match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(members) => members.iter().map(|(_, v)| v).collect(),
+        _ => Vec::new(),
+    };
+}
+
+mod tests {
+    use crate::json::*;
+
+    #[test]
+    fn parse_every_kind_of_value() {
+        let doc =
+            parse(r#" {"a": [1, -2.5, 3e2], "b": {"c": true, "d": null}, "e": false, "f": "x"} "#)
+                .unwrap();
+
+        assert_eq!(
+            Value::Object(vec![
+                (
+                    "a".to_string(),
+                    Value::Array(vec![1.0.into(), (-2.5).into(), 300.0.into()])
+                ),
+                (
+                    "b".to_string(),
+                    Value::Object(vec![
+                        ("c".to_string(), Value::Bool(true)),
+                        ("d".to_string(), Value::Null)
+                    ])
+                ),
+                ("e".to_string(), Value::Bool(false)),
+                ("f".to_string(), "x".into()),
+            ]),
+            doc
+        );
+        assert_eq!(Some(&Value::Null), doc.get("b").unwrap().get("d"));
+        assert_eq!(None, doc.get("z"));
+    }
+
+    #[test]
+    fn parse_string_escapes() {
+        let doc = parse(r#""quote \" slash \\ \/ \b\f\n\r\t é \u00e9 \ud83d\ude00""#).unwrap();
+
+        assert_eq!(
+            Some("quote \" slash \\ / \u{8}\u{c}\n\r\t é é 😀"),
+            doc.as_str()
+        );
+    }
+
+    #[test]
+    fn report_error_positions() {
+        let check = |input: &str, line: usize, column: usize, expected: &str| {
+            let err = parse(input).unwrap_err();
+            assert_eq!(
+                (line, column, expected.to_string()),
+                (err.line, err.column, err.expected),
+                "{}",
+                input
+            );
+        };
+
+        check("[1, 2,]", 1, 7, "a value");
+        check("{\n  \"a\": 1\n  \"b\": 2\n}", 3, 3, "',' or '}'");
+        check("{\"a\" 1}", 1, 6, "':'");
+        check("{1: 2}", 1, 2, "a string key");
+        check(
+            "{\"a\": 1, \"b\": {\"a\": 2}, \"a\": 3}",
+            1,
+            25,
+            "a key that is not in the object yet",
+        );
+        check("[01]", 1, 3, "',' or ']'");
+        check("[1.]", 1, 4, "a digit");
+        check("[-]", 1, 3, "a digit");
+        check("\"abc", 1, 5, "'\"'");
+        check("\"a\\x\"", 1, 4, "an escape sequence");
+        check("\"a\nb\"", 1, 3, "an escaped control character");
+        check("\"\\u12g4\"", 1, 6, "a hex digit");
+        check("\"\\ud83d\"", 1, 8, "a low surrogate");
+        check("\"\\ud83d\\u0041\"", 1, 8, "a low surrogate");
+        check("\"ü\\ude00\"", 1, 3, "a high surrogate before a low one");
+        check("[tru]", 1, 2, "true");
+        check("{} {}", 1, 4, "the end of the input");
+        check("", 1, 1, "a value");
+    }
+
+    #[test]
+    fn write_compact_and_pretty() {
+        let input = r#"{"name":"a \"b\"\n\u0001","list":[1,2.5,-0.125,{}],"empty":[],"nested":{"ok":true,"n":null}}"#;
+        let doc = parse(input).unwrap();
+
+        assert_eq!(input, doc.to_string());
+        assert_eq!(doc, parse(&doc.to_pretty()).unwrap());
+
+        let expected = r#"{
+  "name": "a \"b\"\n\u0001",
+  "list": [
+    1,
+    2.5,
+    -0.125,
+    {}
+  ],
+  "empty": [],
+  "nested": {
+    "ok": true,
+    "n": null
+  }
+}"#;
+        assert_eq!(expected, doc.to_pretty());
+        assert_eq!("null", Value::Number(f64::NAN).to_string());
+    }
+
+    #[test]
+    fn select_values_by_path() {
+        let doc = parse(r#"{"a": {"b": [10, 20, {"c": 30}]}, "d": 4}"#).unwrap();
+        let select = |path: &str| -> Vec<String> {
+            return Query::new(path)
+                .unwrap()
+                .select(&doc)
+                .iter()
+                .map(|v| v.to_string())
+                .collect();
+        };
+
+        assert_eq!(vec![doc.to_string()], select(""));
+        assert_eq!(vec!["20"], select("a.b.1"));
+        assert_eq!(vec!["30"], select("a.b.2.c"));
+        assert_eq!(vec!["10", "20", r#"{"c":30}"#], select("a.b.*"));
+        assert_eq!(vec!["30"], select("**.c"));
+        assert_eq!(Vec::<String>::new(), select("a.x.1"));
+        assert_eq!(Vec::<String>::new(), select("d.0"));
+
+        assert_eq!(64.0, Query::new("**").unwrap().sum(&doc));
+        assert_eq!(60.0, Query::new("a.**").unwrap().sum(&doc));
+
+        let err = Query::new("a..b").err().unwrap();
+        assert_eq!((1, 3), (err.line, err.column));
+    }
+
+    #[test]
+    fn prune_objects_with_a_value() {
+        let doc = parse(
+            r#"[1, {"c": "red", "b": 2}, 3, ["red", 5], {"d": {"e": "red", "f": 9}, "g": 4}]"#,
+        )
+        .unwrap();
+        let red = Value::from("red");
+        let query = Query::new("**")
+            .unwrap()
+            .prune(move |v| v.has_member_value(&red));
+
+        assert_eq!(13.0, query.sum(&doc));
+        assert_eq!(0.0, query.sum(&parse(r#"{"a": 1, "b": "red"}"#).unwrap()));
+    }
+}
@@ -0,0 +1,20 @@
+// a small linear congruential generator, for tests that need a lot of
+// numbers but still have to be repeatable.
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        return Lcg { state: seed };
+    }
+
+    // a number from 0 up to, but not including, "limit".
+    pub fn below(&mut self, limit: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        return (self.state >> 33) % limit;
+    }
+}
@@ -0,0 +1,308 @@
+// a grid of lights that only changes in rectangles. the edges of all the
+// rectangles cut the grid into blocks that are each lit the same way, so
+// the work depends on the number of instructions and not on the size of
+// the grid.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    TurnOn,
+    TurnOff,
+    Toggle,
+}
+
+// both corners are included, like "0,0 through 999,999".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x0: u64,
+    pub y0: u64,
+    pub x1: u64,
+    pub y1: u64,
+}
+
+impl Rect {
+    pub fn new(x0: u64, y0: u64, x1: u64, y1: u64) -> Self {
+        assert!(
+            x0 <= x1 && y0 <= y1,
+            "The corners are the wrong way around."
+        );
+        assert!(
+            x1 < u64::MAX && y1 < u64::MAX,
+            "The rectangle is too large."
+        );
+        return Self { x0, y0, x1, y1 };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instruction {
+    pub rect: Rect,
+    pub action: Action,
+}
+
+// what an action does to a single light. a light that was never touched
+// is the default one, which has to count as zero.
+pub trait Light: Copy + Default {
+    fn apply(self, action: Action) -> Self;
+    fn value(self) -> u64;
+}
+
+impl Light for bool {
+    fn apply(self, action: Action) -> Self {
+        return match action {
+            Action::TurnOn => true,
+            Action::TurnOff => false,
+            Action::Toggle => !self,
+        };
+    }
+
+    fn value(self) -> u64 {
+        return u64::from(self);
+    }
+}
+
+// the ancient nordic elvish reading of the instructions.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Brightness(pub u64);
+
+impl Light for Brightness {
+    fn apply(self, action: Action) -> Self {
+        return Brightness(match action {
+            Action::TurnOn => self.0 + 1,
+            Action::TurnOff => self.0.saturating_sub(1),
+            Action::Toggle => self.0 + 2,
+        });
+    }
+
+    fn value(self) -> u64 {
+        return self.0;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LightGrid<L> {
+    // block (col, row) covers xs[col]..xs[col + 1] and ys[row]..ys[row + 1],
+    // without the end.
+    xs: Vec<u64>,
+    ys: Vec<u64>,
+    blocks: Vec<L>,
+}
+
+impl<L: Light> LightGrid<L> {
+    pub fn new(instructions: &[Instruction]) -> Self {
+        let mut xs: Vec<u64> = instructions
+            .iter()
+            .flat_map(|i| [i.rect.x0, i.rect.x1 + 1])
+            .collect();
+        let mut ys: Vec<u64> = instructions
+            .iter()
+            .flat_map(|i| [i.rect.y0, i.rect.y1 + 1])
+            .collect();
+        xs.sort();
+        xs.dedup();
+        ys.sort();
+        ys.dedup();
+
+        let cols = xs.len().saturating_sub(1);
+        let rows = ys.len().saturating_sub(1);
+        let mut grid = Self {
+            xs,
+            ys,
+            blocks: vec![L::default(); cols * rows],
+        };
+
+        for instruction in instructions {
+            let rect = instruction.rect;
+            // the edges are all in the lists, so these always find them.
+            let col0 = grid.xs.binary_search(&rect.x0).unwrap();
+            let col1 = grid.xs.binary_search(&(rect.x1 + 1)).unwrap();
+            let row0 = grid.ys.binary_search(&rect.y0).unwrap();
+            let row1 = grid.ys.binary_search(&(rect.y1 + 1)).unwrap();
+
+            for row in row0..row1 {
+                for block in &mut grid.blocks[(row * cols + col0)..(row * cols + col1)] {
+                    *block = block.apply(instruction.action);
+                }
+            }
+        }
+
+        return grid;
+    }
+
+    fn cols(&self) -> usize {
+        return self.xs.len().saturating_sub(1);
+    }
+
+    pub fn get(&self, x: u64, y: u64) -> L {
+        let col = self.xs.partition_point(|e| *e <= x);
+        let row = self.ys.partition_point(|e| *e <= y);
+        if col == 0 || col == self.xs.len() || row == 0 || row == self.ys.len() {
+            return L::default();
+        }
+        return self.blocks[(row - 1) * self.cols() + (col - 1)];
+    }
+
+    // the sum of every light in the grid, which is the number of lights
+    // that are on for plain lights.
+    pub fn total(&self) -> u64 {
+        let cols = self.cols();
+        return self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let (col, row) = (i % cols, i / cols);
+                let width = self.xs[col + 1] - self.xs[col];
+                let height = self.ys[row + 1] - self.ys[row];
+                return block.value() * width * height;
+            })
+            .sum();
+    }
+
+    // the same, but only for the lights inside "rect".
+    pub fn total_in(&self, rect: Rect) -> u64 {
+        let cols = self.cols();
+        let col0 = self.xs.partition_point(|e| *e <= rect.x0).saturating_sub(1);
+        let row0 = self.ys.partition_point(|e| *e <= rect.y0).saturating_sub(1);
+
+        let mut total = 0;
+        for row in row0..self.ys.len().saturating_sub(1) {
+            let top = std::cmp::max(self.ys[row], rect.y0);
+            let bottom = std::cmp::min(self.ys[row + 1], rect.y1 + 1);
+            if top > rect.y1 {
+                break;
+            }
+            if top >= bottom {
+                continue;
+            }
+
+            for col in col0..cols {
+                let left = std::cmp::max(self.xs[col], rect.x0);
+                let right = std::cmp::min(self.xs[col + 1], rect.x1 + 1);
+                if left > rect.x1 {
+                    break;
+                }
+                if left >= right {
+                    continue;
+                }
+                total += self.blocks[row * cols + col].value() * (right - left) * (bottom - top);
+            }
+        }
+        return total;
+    }
+}
+
+mod tests {
+    use crate::lcg::Lcg;
+    use crate::lights::*;
+
+    fn instruction(x0: u64, y0: u64, x1: u64, y1: u64, action: Action) -> Instruction {
+        return Instruction {
+            rect: Rect::new(x0, y0, x1, y1),
+            action,
+        };
+    }
+
+    #[test]
+    fn follow_the_examples() {
+        let instructions = [
+            instruction(0, 0, 999, 999, Action::TurnOn),
+            instruction(0, 0, 999, 0, Action::Toggle),
+            instruction(499, 499, 500, 500, Action::TurnOff),
+        ];
+        let grid: LightGrid<bool> = LightGrid::new(&instructions);
+        assert_eq!(1_000_000 - 1000 - 4, grid.total());
+        assert!(!grid.get(500, 500));
+        assert!(grid.get(501, 500));
+        assert!(!grid.get(1000, 5));
+
+        let instructions = [
+            instruction(0, 0, 0, 0, Action::TurnOn),
+            instruction(0, 0, 999, 999, Action::Toggle),
+        ];
+        let grid: LightGrid<Brightness> = LightGrid::new(&instructions);
+        assert_eq!(2_000_001, grid.total());
+        assert_eq!(Brightness(3), grid.get(0, 0));
+    }
+
+    #[test]
+    fn count_lights_in_a_rectangle() {
+        let instructions = [
+            instruction(10, 10, 19, 19, Action::TurnOn),
+            instruction(15, 0, 15, 100, Action::Toggle),
+        ];
+        let grid: LightGrid<bool> = LightGrid::new(&instructions);
+
+        assert_eq!(100 - 10 + 91, grid.total());
+        assert_eq!(100 - 10, grid.total_in(Rect::new(10, 10, 19, 19)));
+        assert_eq!(4, grid.total_in(Rect::new(0, 0, 11, 11)));
+        assert_eq!(0, grid.total_in(Rect::new(500, 500, 600, 600)));
+        assert_eq!(grid.total(), grid.total_in(Rect::new(0, 0, 1000, 1000)));
+        assert_eq!(
+            0,
+            LightGrid::<bool>::new(&[]).total_in(Rect::new(0, 0, 9, 9))
+        );
+    }
+
+    #[test]
+    fn handle_a_huge_grid() {
+        let size = 1_000_000_000;
+        let instructions = [
+            instruction(0, 0, size - 1, size - 1, Action::TurnOn),
+            instruction(1, 1, size - 2, size - 2, Action::Toggle),
+            instruction(0, 0, size - 1, size - 1, Action::Toggle),
+        ];
+        let grid: LightGrid<Brightness> = LightGrid::new(&instructions);
+        assert_eq!(3 * size * size + 2 * (size - 2) * (size - 2), grid.total());
+        assert_eq!(4 * 3 + 3 + 3 * 5, grid.total_in(Rect::new(0, 0, 3, 1)));
+    }
+
+    #[test]
+    fn match_a_plain_grid() {
+        let mut lcg = Lcg::new(7);
+
+        let size = 40;
+        let mut instructions = Vec::new();
+        for _ in 0..60 {
+            let (a, b, c, d) = (
+                lcg.below(size),
+                lcg.below(size),
+                lcg.below(size),
+                lcg.below(size),
+            );
+            let action = [Action::TurnOn, Action::TurnOff, Action::Toggle][lcg.below(3) as usize];
+            let rect = Rect::new(a.min(b), c.min(d), a.max(b), c.max(d));
+            instructions.push(Instruction { rect, action });
+        }
+
+        let mut plain = vec![vec![Brightness(0); size as usize]; size as usize];
+        for i in &instructions {
+            for y in i.rect.y0..=i.rect.y1 {
+                for x in i.rect.x0..=i.rect.x1 {
+                    let light = &mut plain[y as usize][x as usize];
+                    *light = light.apply(i.action);
+                }
+            }
+        }
+
+        let grid: LightGrid<Brightness> = LightGrid::new(&instructions);
+        for _ in 0..50 {
+            let (a, b, c, d) = (
+                lcg.below(size),
+                lcg.below(size),
+                lcg.below(size),
+                lcg.below(size),
+            );
+            let rect = Rect::new(a.min(b), c.min(d), a.max(b), c.max(d));
+            let expected: u64 = (rect.y0..=rect.y1)
+                .flat_map(|y| (rect.x0..=rect.x1).map(move |x| (x, y)))
+                .map(|(x, y)| plain[y as usize][x as usize].0)
+                .sum();
+            assert_eq!(expected, grid.total_in(rect));
+        }
+        for y in 0..size {
+            for x in 0..size {
+                assert_eq!(plain[y as usize][x as usize], grid.get(x, y));
+            }
+        }
+    }
+}
@@ -1,3 +1,7 @@
+mod automaton;
+mod bignum;
+mod circuit;
+mod combinatorics;
 mod day01;
 mod day02;
 mod day03;
@@ -17,7 +21,24 @@ mod day16;
 mod day17;
 mod day18;
 mod day19;
-// mod day20;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
+mod dot;
+mod grammar;
+mod image;
+mod json;
+mod lcg;
+mod lights;
+mod md5;
+mod memo;
+mod mining;
+mod parse;
+mod password;
+mod tsp;
 
 fn main() {
     // day01::run();
@@ -40,4 +61,9 @@ fn main() {
     // day18::run();
     day19::run();
     // day20::run();
+    // day21::run();
+    // day22::run();
+    // day23::run();
+    // day24::run();
+    // day25::run();
 }
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+// anything that can remember values by key.
+pub trait Cache<K, V> {
+    fn lookup(&self, key: &K) -> Option<&V>;
+    fn store(&mut self, key: K, value: V);
+    fn entries(&self) -> usize;
+}
+
+impl<K: Hash + Eq, V> Cache<K, V> for HashMap<K, V> {
+    fn lookup(&self, key: &K) -> Option<&V> {
+        return self.get(key);
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+    fn entries(&self) -> usize {
+        return self.len();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} hits, {} misses", self.hits, self.misses)
+    }
+}
+
+// wraps a cache for a recursive function. the function gets the memo
+// handed back to it, so it can recurse through the same cache.
+pub struct Memo<K, V, C = HashMap<K, V>> {
+    cache: C,
+    stats: Stats,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        return Self::with_cache(HashMap::new());
+    }
+}
+
+impl<K, V: Clone, C: Cache<K, V>> Memo<K, V, C> {
+    pub fn with_cache(cache: C) -> Self {
+        return Self {
+            cache,
+            stats: Stats::default(),
+            _marker: PhantomData,
+        };
+    }
+
+    pub fn get_or_compute<F: FnOnce(&mut Self) -> V>(&mut self, key: K, compute: F) -> V {
+        if let Some(value) = self.cache.lookup(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.store(key, value.clone());
+
+        return value;
+    }
+
+    // seeds a value, which is neither a hit nor a miss.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.cache.store(key, value);
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        return self.cache.lookup(key);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.cache.entries();
+    }
+
+    pub fn stats(&self) -> Stats {
+        return self.stats;
+    }
+}
+
+mod tests {
+    use crate::memo::*;
+
+    fn count_paths(x: i64, y: i64, memo: &mut Memo<(i64, i64), u64>) -> u64 {
+        if x == 0 || y == 0 {
+            return 1;
+        }
+        return memo.get_or_compute((x, y), |memo| {
+            return count_paths(x - 1, y, memo) + count_paths(x, y - 1, memo);
+        });
+    }
+
+    #[test]
+    fn memoize_a_recursive_function() {
+        let mut memo = Memo::new();
+
+        assert_eq!(184_756, count_paths(10, 10, &mut memo));
+        assert_eq!(100, memo.len());
+        assert_eq!(
+            Stats {
+                hits: 81,
+                misses: 100
+            },
+            memo.stats()
+        );
+
+        assert_eq!(184_756, count_paths(10, 10, &mut memo));
+        assert_eq!(
+            Stats {
+                hits: 82,
+                misses: 100
+            },
+            memo.stats()
+        );
+    }
+
+    #[test]
+    fn seeded_values_are_used_as_is() {
+        let mut memo: Memo<&str, i32> = Memo::new();
+        memo.insert("a", 5);
+
+        assert_eq!(
+            5,
+            memo.get_or_compute("a", |_| panic!("Should not be computed."))
+        );
+        assert_eq!(7, memo.get_or_compute("b", |_| 7));
+        assert_eq!(Some(&7), memo.get(&"b"));
+        assert_eq!("1 hits, 1 misses", memo.stats().to_string());
+    }
+}
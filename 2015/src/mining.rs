@@ -0,0 +1,138 @@
+use crate::md5;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+// nonces are handed out to the workers in blocks of this many, in order.
+const BLOCK_SIZE: u64 = 4096;
+
+// finds the lowest positive nonce where md5(secret + nonce) starts with
+// "difficulty" zero hex digits. "threads" of zero uses every core.
+pub fn find_first(secret: &str, difficulty: usize, threads: usize) -> Option<u64> {
+    assert!(difficulty <= 32, "An md5 digest only has 32 hex digits.");
+
+    let threads = match threads {
+        0 => thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        n => n,
+    };
+    let next_block = AtomicU64::new(1);
+    let best = AtomicU64::new(u64::MAX);
+
+    let mut prefix = md5::Context::new();
+    prefix.update(secret.as_bytes());
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| search_blocks(&prefix, difficulty, &next_block, &best));
+        }
+    });
+
+    return match best.load(Ordering::SeqCst) {
+        u64::MAX => None,
+        nonce => Some(nonce),
+    };
+}
+
+// blocks are taken in increasing order, so once a nonce is found every
+// block below it has already been taken by some worker. the workers only
+// stop taking blocks past the best nonce, which makes it the lowest one.
+fn search_blocks(
+    prefix: &md5::Context,
+    difficulty: usize,
+    next_block: &AtomicU64,
+    best: &AtomicU64,
+) {
+    let mut digits = [0u8; 20];
+
+    loop {
+        let start = next_block.fetch_add(BLOCK_SIZE, Ordering::SeqCst);
+        let end = start.saturating_add(BLOCK_SIZE);
+
+        if start >= best.load(Ordering::SeqCst) {
+            return;
+        }
+
+        for nonce in start..end {
+            let mut ctx = *prefix;
+            ctx.update(format_nonce(nonce, &mut digits));
+
+            if has_leading_zeros(&ctx.finish(), difficulty) {
+                best.fetch_min(nonce, Ordering::SeqCst);
+                break;
+            }
+        }
+
+        if end == u64::MAX {
+            return;
+        }
+    }
+}
+
+fn format_nonce(mut nonce: u64, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut i = buffer.len();
+    loop {
+        i -= 1;
+        buffer[i] = b'0' + (nonce % 10) as u8;
+        nonce /= 10;
+        if nonce == 0 {
+            return &buffer[i..];
+        }
+    }
+}
+
+// compares the digest bytes, two hex digits per byte.
+pub fn has_leading_zeros(digest: &[u8], hex_digits: usize) -> bool {
+    let full_bytes = hex_digits / 2;
+
+    if digest[..full_bytes].iter().any(|b| *b != 0) {
+        return false;
+    }
+
+    return hex_digits % 2 == 0 || digest[full_bytes] >> 4 == 0;
+}
+
+mod tests {
+    use crate::mining::*;
+
+    #[test]
+    fn format_nonces() {
+        let mut buffer = [0u8; 20];
+
+        assert_eq!(b"0", format_nonce(0, &mut buffer));
+        assert_eq!(b"609043", format_nonce(609043, &mut buffer));
+        assert_eq!(b"18446744073709551615", format_nonce(u64::MAX, &mut buffer));
+    }
+
+    #[test]
+    fn count_leading_zeros() {
+        let digest = [0x00, 0x00, 0x0f, 0xff];
+
+        assert!(has_leading_zeros(&digest, 0));
+        assert!(has_leading_zeros(&digest, 4));
+        assert!(has_leading_zeros(&digest, 5));
+        assert!(!has_leading_zeros(&digest, 6));
+    }
+
+    #[test]
+    fn find_the_lowest_nonce() {
+        // nonces are spread over the workers, but the answer should not change.
+        for difficulty in 1..=3 {
+            let expected = (1..)
+                .find(|n| {
+                    let hex = md5::Hex(md5::compute(format!("abcdef{}", n).as_bytes())).to_string();
+                    return hex.starts_with(&"0".repeat(difficulty));
+                })
+                .unwrap();
+
+            assert_eq!(Some(expected), find_first("abcdef", difficulty, 1));
+            assert_eq!(Some(expected), find_first("abcdef", difficulty, 4));
+        }
+    }
+
+    #[test]
+    fn mine_the_examples() {
+        assert_eq!(Some(609043), find_first("abcdef", 5, 0));
+        assert_eq!(Some(1048970), find_first("pqrstuv", 5, 0));
+    }
+}
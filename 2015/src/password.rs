@@ -0,0 +1,229 @@
+// passwords are lowercase letters, counted like numbers in base 26.
+pub trait Rule {
+    fn check(&self, password: &[u8]) -> bool;
+
+    // a character that can never appear. the search skips every
+    // password with one of these without looking at it.
+    fn forbids(&self, _ch: u8) -> bool {
+        return false;
+    }
+}
+
+// any closure over the password is a rule too.
+impl<F: Fn(&[u8]) -> bool> Rule for F {
+    fn check(&self, password: &[u8]) -> bool {
+        return self(password);
+    }
+}
+
+// a run of this many increasing letters, like "abc".
+pub struct Straight(pub usize);
+
+impl Rule for Straight {
+    fn check(&self, password: &[u8]) -> bool {
+        if self.0 <= 1 {
+            return password.len() >= self.0;
+        }
+
+        let mut run = 1;
+        for pair in password.windows(2) {
+            run = if pair[1] == pair[0] + 1 { run + 1 } else { 1 };
+            if run >= self.0 {
+                return true;
+            }
+        }
+        return false;
+    }
+}
+
+pub struct Forbidden(pub &'static str);
+
+impl Rule for Forbidden {
+    fn check(&self, password: &[u8]) -> bool {
+        return !password.iter().any(|ch| self.forbids(*ch));
+    }
+
+    fn forbids(&self, ch: u8) -> bool {
+        return self.0.as_bytes().contains(&ch);
+    }
+}
+
+// at least this many different letters that appear twice in a row, like "aa".
+pub struct Pairs(pub usize);
+
+impl Rule for Pairs {
+    fn check(&self, password: &[u8]) -> bool {
+        let mut seen = [false; 26];
+        for pair in password.windows(2) {
+            if pair[0] == pair[1] {
+                seen[(pair[0] - b'a') as usize] = true;
+            }
+        }
+        return seen.iter().filter(|s| **s).count() >= self.0;
+    }
+}
+
+pub struct Length(pub usize);
+
+impl Rule for Length {
+    fn check(&self, password: &[u8]) -> bool {
+        return password.len() == self.0;
+    }
+}
+
+#[derive(Default)]
+pub struct Policy {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Policy {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn rule<R: Rule + 'static>(mut self, rule: R) -> Self {
+        self.rules.push(Box::new(rule));
+        return self;
+    }
+
+    pub fn is_valid(&self, password: &str) -> bool {
+        return self.rules.iter().all(|r| r.check(password.as_bytes()));
+    }
+
+    // every valid password that comes after "start" and has the same length,
+    // in order. nothing is computed until it is asked for.
+    pub fn after(&self, start: &str) -> Passwords<'_> {
+        assert!(
+            start.bytes().all(|ch| ch.is_ascii_lowercase()),
+            "Passwords can only have lowercase letters."
+        );
+
+        let mut current = start.as_bytes().to_vec();
+
+        // everything up to the next allowed letter at the first forbidden one
+        // has it too. maxing out what follows makes the first step jump there.
+        if let Some(i) = current.iter().position(|ch| self.forbids(*ch)) {
+            for ch in &mut current[(i + 1)..] {
+                *ch = b'z';
+            }
+        }
+
+        return Passwords {
+            policy: self,
+            current: Some(current),
+        };
+    }
+
+    fn forbids(&self, ch: u8) -> bool {
+        return self.rules.iter().any(|r| r.forbids(ch));
+    }
+
+    fn next_allowed(&self, from: u8) -> Option<u8> {
+        return (from..=b'z').find(|ch| !self.forbids(*ch));
+    }
+
+    // steps to the next password without forbidden letters. false after the last one.
+    fn increment(&self, password: &mut [u8]) -> bool {
+        let lowest = match self.next_allowed(b'a') {
+            Some(ch) => ch,
+            None => return false,
+        };
+
+        for i in (0..password.len()).rev() {
+            match self.next_allowed(password[i] + 1) {
+                Some(ch) => {
+                    password[i] = ch;
+                    return true;
+                }
+                None => password[i] = lowest,
+            }
+        }
+
+        return false;
+    }
+}
+
+pub struct Passwords<'a> {
+    policy: &'a Policy,
+    current: Option<Vec<u8>>,
+}
+
+impl Iterator for Passwords<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let current = self.current.as_mut()?;
+
+        loop {
+            if !self.policy.increment(current) {
+                self.current = None;
+                return None;
+            }
+            if self.policy.rules.iter().all(|r| r.check(current)) {
+                return Some(String::from_utf8(current.clone()).unwrap());
+            }
+        }
+    }
+}
+
+mod tests {
+    use crate::password::*;
+
+    #[test]
+    fn check_each_rule() {
+        assert!(Straight(3).check(b"xabcx"));
+        assert!(!Straight(3).check(b"abdbce"));
+        assert!(Straight(4).check(b"wxyz"));
+        assert!(!Straight(3).check(b"xy"));
+
+        assert!(Forbidden("iol").check(b"abc"));
+        assert!(!Forbidden("iol").check(b"abo"));
+
+        assert!(Pairs(2).check(b"aabcc"));
+        assert!(!Pairs(2).check(b"aaaa"));
+        assert!(Pairs(1).check(b"aaa"));
+
+        assert!(Length(3).check(b"abc"));
+        assert!(!Length(3).check(b"abcd"));
+    }
+
+    #[test]
+    fn skip_forbidden_letters() {
+        let policy = Policy::new().rule(Forbidden("b"));
+        let next: Vec<String> = policy.after("aa").take(3).collect();
+        assert_eq!(vec!["ac", "ad", "ae"], next);
+
+        // the start itself is not a valid password.
+        assert_eq!(Some("ca".to_string()), policy.after("bq").next());
+        assert_eq!(Some("ca".to_string()), policy.after("az").next());
+
+        // nothing but "z" is left after the forbidden letters.
+        let policy = Policy::new().rule(Forbidden("abcdefghijklmnopqrstuvwxy"));
+        assert_eq!(vec!["zz"], policy.after("ab").collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stop_after_the_last_password() {
+        let policy = Policy::new();
+
+        assert_eq!(vec!["y", "z"], policy.after("x").collect::<Vec<_>>());
+        assert_eq!(None, policy.after("zz").next());
+        assert_eq!(26 * 26 - 1, policy.after("aa").count());
+    }
+
+    #[test]
+    fn use_a_closure_as_a_rule() {
+        let policy = Policy::new()
+            .rule(|p: &[u8]| p.first() == p.last())
+            .rule(Forbidden("aeiou"));
+        let next: Vec<String> = policy.after("bb").take(3).collect();
+
+        assert_eq!(
+            vec!["bcb", "bdb", "bfb"],
+            policy.after("bbb").take(3).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["cc", "dd", "ff"], next);
+        assert!(policy.is_valid("xyx"));
+        assert!(!policy.is_valid("aba"));
+    }
+}
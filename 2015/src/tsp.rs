@@ -0,0 +1,241 @@
+// travelling salesman by dynamic programming over subsets (held-karp).
+// the table has 2^n * n entries, so this is for inputs of about 20 nodes.
+pub const MAX_NODES: usize = 20;
+// the cost between two nodes that have no way between them.
+pub const UNREACHABLE: i64 = i64::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    // visits every node once, starting and ending anywhere.
+    Path,
+    // visits every node once and then returns to the first one.
+    Cycle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Goal {
+    Shortest,
+    Longest,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tour {
+    pub order: Vec<usize>,
+    pub cost: i64,
+}
+
+// "costs[a][b]" is the cost of going from a to b, which does not have to
+// be the same as going back. a cycle always starts at node 0. when no tour
+// visits every node, there is nothing to return.
+pub fn solve(costs: &[Vec<i64>], shape: Shape, goal: Goal) -> Option<Tour> {
+    let n = costs.len();
+    assert!(n <= MAX_NODES, "At most {} nodes are supported.", MAX_NODES);
+
+    if n == 0 {
+        return Some(Tour {
+            order: Vec::new(),
+            cost: 0,
+        });
+    }
+
+    let unset = match goal {
+        Goal::Shortest => i64::MAX,
+        Goal::Longest => i64::MIN,
+    };
+    let is_better = |a: i64, b: i64| -> bool {
+        return match goal {
+            Goal::Shortest => a < b,
+            Goal::Longest => a > b,
+        };
+    };
+
+    // best[mask * n + last] is the best cost of visiting the nodes in "mask",
+    // ending at "last". prev remembers the node before "last" on that route.
+    let full = (1usize << n) - 1;
+    let mut best = vec![unset; (full + 1) * n];
+    let mut prev = vec![u8::MAX; (full + 1) * n];
+
+    match shape {
+        Shape::Path => {
+            for i in 0..n {
+                best[(1 << i) * n + i] = 0;
+            }
+        }
+        Shape::Cycle => best[n] = 0,
+    }
+
+    for mask in 1..=full {
+        for last in 0..n {
+            let cost = best[mask * n + last];
+            if cost == unset {
+                continue;
+            }
+
+            for next in 0..n {
+                if mask & (1 << next) != 0 || costs[last][next] == UNREACHABLE {
+                    continue;
+                }
+                let k = (mask | (1 << next)) * n + next;
+                let c = cost + costs[last][next];
+                if is_better(c, best[k]) {
+                    best[k] = c;
+                    prev[k] = last as u8;
+                }
+            }
+        }
+    }
+
+    let mut end = 0;
+    let mut total = unset;
+    for last in 0..n {
+        let cost = best[full * n + last];
+        if cost == unset {
+            continue;
+        }
+        let cost = match shape {
+            Shape::Path => cost,
+            Shape::Cycle if costs[last][0] == UNREACHABLE => continue,
+            Shape::Cycle => cost + costs[last][0],
+        };
+        if is_better(cost, total) {
+            total = cost;
+            end = last;
+        }
+    }
+
+    if total == unset {
+        return None;
+    }
+
+    let mut order = Vec::with_capacity(n);
+    let mut mask = full;
+    let mut last = end;
+    loop {
+        order.push(last);
+        let p = prev[mask * n + last];
+        mask &= !(1 << last);
+        if p == u8::MAX {
+            break;
+        }
+        last = p as usize;
+    }
+    order.reverse();
+
+    return Some(Tour { order, cost: total });
+}
+
+mod tests {
+    use crate::combinatorics::permutations;
+    use crate::lcg::Lcg;
+    use crate::tsp::*;
+
+    fn tour_cost(costs: &[Vec<i64>], order: &[usize], shape: Shape) -> i64 {
+        let mut cost: i64 = order.windows(2).map(|w| costs[w[0]][w[1]]).sum();
+        if shape == Shape::Cycle {
+            cost += costs[order[order.len() - 1]][order[0]];
+        }
+        return cost;
+    }
+
+    fn brute_force(costs: &[Vec<i64>], shape: Shape, goal: Goal) -> i64 {
+        let all = permutations(costs.len()).map(|order| tour_cost(costs, &order, shape));
+        return match goal {
+            Goal::Shortest => all.min().unwrap(),
+            Goal::Longest => all.max().unwrap(),
+        };
+    }
+
+    fn make_costs(n: usize, seed: u64) -> Vec<Vec<i64>> {
+        let mut lcg = Lcg::new(seed);
+        return (0..n)
+            .map(|a| {
+                return (0..n)
+                    .map(|b| if a == b { 0 } else { lcg.below(100) as i64 })
+                    .collect();
+            })
+            .collect();
+    }
+
+    #[test]
+    fn solve_the_route_example() {
+        // london, dublin, belfast from day 9.
+        let costs = vec![vec![0, 464, 518], vec![464, 0, 141], vec![518, 141, 0]];
+
+        let shortest = solve(&costs, Shape::Path, Goal::Shortest).unwrap();
+        assert_eq!(605, shortest.cost);
+        assert!(shortest.order == vec![0, 1, 2] || shortest.order == vec![2, 1, 0]);
+
+        let longest = solve(&costs, Shape::Path, Goal::Longest).unwrap();
+        assert_eq!(982, longest.cost);
+        assert!(longest.order == vec![1, 0, 2] || longest.order == vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn match_brute_force() {
+        for n in 1..=7 {
+            let costs = make_costs(n, n as u64);
+
+            for shape in [Shape::Path, Shape::Cycle] {
+                for goal in [Goal::Shortest, Goal::Longest] {
+                    let tour = solve(&costs, shape, goal).unwrap();
+
+                    let mut visited = tour.order.clone();
+                    visited.sort();
+                    assert_eq!((0..n).collect::<Vec<_>>(), visited);
+                    assert_eq!(tour.cost, tour_cost(&costs, &tour.order, shape));
+                    assert_eq!(brute_force(&costs, shape, goal), tour.cost);
+
+                    if shape == Shape::Cycle {
+                        assert_eq!(0, tour.order[0]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn solve_empty_and_large_inputs() {
+        assert_eq!(
+            Some(Tour {
+                order: Vec::new(),
+                cost: 0
+            }),
+            solve(&[], Shape::Cycle, Goal::Shortest)
+        );
+
+        // a line of towns one apart, listed out of order.
+        let positions: Vec<i64> = vec![5, 12, 0, 3, 15, 8, 1, 14, 6, 10, 2, 13, 4, 9, 11, 7];
+        let costs: Vec<Vec<i64>> = positions
+            .iter()
+            .map(|a| positions.iter().map(|b| (a - b).abs()).collect())
+            .collect();
+
+        assert_eq!(15, solve(&costs, Shape::Path, Goal::Shortest).unwrap().cost);
+        assert_eq!(
+            30,
+            solve(&costs, Shape::Cycle, Goal::Shortest).unwrap().cost
+        );
+    }
+
+    #[test]
+    fn solve_without_a_tour() {
+        // 0 - 1 - 2, with no way from 2 back to 0.
+        let x = UNREACHABLE;
+        let costs = vec![vec![0, 1, x], vec![1, 0, 2], vec![x, 2, 0]];
+
+        let tour = solve(&costs, Shape::Path, Goal::Longest).unwrap();
+        assert_eq!(3, tour.cost);
+        assert!(tour.order == vec![0, 1, 2] || tour.order == vec![2, 1, 0]);
+        assert_eq!(None, solve(&costs, Shape::Cycle, Goal::Shortest));
+
+        // two pairs with nothing between them.
+        let costs = vec![
+            vec![0, 1, x, x],
+            vec![1, 0, x, x],
+            vec![x, x, 0, 1],
+            vec![x, x, 1, 0],
+        ];
+        assert_eq!(None, solve(&costs, Shape::Path, Goal::Shortest));
+        assert_eq!(None, solve(&costs, Shape::Path, Goal::Longest));
+    }
+}
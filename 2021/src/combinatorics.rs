@@ -0,0 +1,317 @@
+use core::fmt;
+use core::ops::Deref;
+
+// everything but the tests only uses core, so the generators work without std.
+pub const MAX_ITEMS: usize = 32;
+
+// a fixed-capacity list of small numbers. the generators below yield
+// one of these per step instead of allocating.
+#[derive(Clone, Copy)]
+pub struct Tuple {
+    values: [usize; MAX_ITEMS],
+    len: usize,
+}
+
+impl Tuple {
+    fn new(len: usize) -> Self {
+        assert!(
+            len <= MAX_ITEMS,
+            "At most {} items are supported.",
+            MAX_ITEMS
+        );
+        return Self {
+            values: [0; MAX_ITEMS],
+            len,
+        };
+    }
+
+    // the indices of the set bits, lowest first.
+    fn from_mask(mask: u64) -> Self {
+        let mut out = Self::new(0);
+        for i in 0..MAX_ITEMS {
+            if mask & (1 << i) != 0 {
+                out.values[out.len] = i;
+                out.len += 1;
+            }
+        }
+        return out;
+    }
+
+    pub fn as_slice(&self) -> &[usize] {
+        return &self.values[..self.len];
+    }
+}
+
+impl Deref for Tuple {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        return self.as_slice();
+    }
+}
+
+impl PartialEq for Tuple {
+    fn eq(&self, other: &Self) -> bool {
+        return self.as_slice() == other.as_slice();
+    }
+}
+
+impl fmt::Debug for Tuple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+// every ordering of the indices 0..n, using heap's algorithm. each
+// permutation differs from the previous one by a single swap.
+pub fn permutations(n: usize) -> Permutations {
+    let mut items = Tuple::new(n);
+    for i in 0..n {
+        items.values[i] = i;
+    }
+    return Permutations {
+        items,
+        counters: [0; MAX_ITEMS],
+        i: 1,
+        started: false,
+    };
+}
+
+pub struct Permutations {
+    items: Tuple,
+    counters: [usize; MAX_ITEMS],
+    i: usize,
+    started: bool,
+}
+
+impl Iterator for Permutations {
+    type Item = Tuple;
+
+    fn next(&mut self) -> Option<Tuple> {
+        if !self.started {
+            self.started = true;
+            return Some(self.items);
+        }
+
+        while self.i < self.items.len {
+            let i = self.i;
+
+            if self.counters[i] < i {
+                let k = if i % 2 == 0 { 0 } else { self.counters[i] };
+                self.items.values.swap(k, i);
+                self.counters[i] += 1;
+                self.i = 1;
+                return Some(self.items);
+            }
+
+            self.counters[i] = 0;
+            self.i += 1;
+        }
+
+        return None;
+    }
+}
+
+// every way to pick k of the indices 0..n, in increasing order. the
+// bitmask of the picked indices is stepped with gosper's hack.
+pub fn combinations(n: usize, k: usize) -> Combinations {
+    assert!(n <= MAX_ITEMS, "At most {} items are supported.", MAX_ITEMS);
+    let mask = if k <= n { Some((1u64 << k) - 1) } else { None };
+    return Combinations { n, mask };
+}
+
+pub struct Combinations {
+    n: usize,
+    mask: Option<u64>,
+}
+
+impl Iterator for Combinations {
+    type Item = Tuple;
+
+    fn next(&mut self) -> Option<Tuple> {
+        let mask = self.mask?;
+
+        self.mask = if mask == 0 {
+            None
+        } else {
+            let lowest = mask & mask.wrapping_neg();
+            let ripple = mask + lowest;
+            let next = (((ripple ^ mask) >> 2) / lowest) | ripple;
+            if next < (1 << self.n) {
+                Some(next)
+            } else {
+                None
+            }
+        };
+
+        return Some(Tuple::from_mask(mask));
+    }
+}
+
+// every subset of the indices 0..n, starting with the empty one.
+pub fn power_set(n: usize) -> PowerSet {
+    assert!(n <= MAX_ITEMS, "At most {} items are supported.", MAX_ITEMS);
+    return PowerSet {
+        end: 1 << n,
+        mask: 0,
+    };
+}
+
+pub struct PowerSet {
+    end: u64,
+    mask: u64,
+}
+
+impl Iterator for PowerSet {
+    type Item = Tuple;
+
+    fn next(&mut self) -> Option<Tuple> {
+        if self.mask >= self.end {
+            return None;
+        }
+        let out = Tuple::from_mask(self.mask);
+        self.mask += 1;
+        return Some(out);
+    }
+}
+
+// every way to split "total" into "parts" numbers that are zero or
+// more, in lexicographic order. like spreading 100 teaspoons over
+// four ingredients.
+pub fn compositions(total: usize, parts: usize) -> Compositions {
+    let mut first = Tuple::new(parts);
+    let done = match parts {
+        0 => total != 0,
+        _ => {
+            first.values[parts - 1] = total;
+            false
+        }
+    };
+    return Compositions {
+        current: first,
+        done,
+    };
+}
+
+pub struct Compositions {
+    current: Tuple,
+    done: bool,
+}
+
+impl Iterator for Compositions {
+    type Item = Tuple;
+
+    fn next(&mut self) -> Option<Tuple> {
+        if self.done {
+            return None;
+        }
+
+        let out = self.current;
+        let values = &mut self.current.values;
+        let last = self.current.len.saturating_sub(1);
+
+        // move one unit from the tail to the rightmost slot that can take it,
+        // and put everything after that slot back into the last one.
+        let mut rest = 0;
+        let mut j = last;
+        loop {
+            if j == 0 {
+                self.done = true;
+                break;
+            }
+            rest += values[j];
+            values[j] = 0;
+            j -= 1;
+            if rest > 0 {
+                values[j] += 1;
+                values[last] = rest - 1;
+                break;
+            }
+        }
+
+        return Some(out);
+    }
+}
+
+mod tests {
+    use crate::combinatorics::*;
+
+    fn collect<I: Iterator<Item = Tuple>>(iter: I) -> Vec<Vec<usize>> {
+        return iter.map(|t| t.to_vec()).collect();
+    }
+
+    #[test]
+    fn permute_three_items() {
+        let perms = collect(permutations(3));
+        let expected = vec![
+            vec![0, 1, 2],
+            vec![1, 0, 2],
+            vec![2, 0, 1],
+            vec![0, 2, 1],
+            vec![1, 2, 0],
+            vec![2, 1, 0],
+        ];
+
+        assert_eq!(expected, perms);
+    }
+
+    #[test]
+    fn count_permutations() {
+        assert_eq!(1, permutations(0).count());
+        assert_eq!(1, permutations(1).count());
+        assert_eq!(5040, permutations(7).count());
+
+        let mut perms = collect(permutations(5));
+        perms.sort();
+        perms.dedup();
+        assert_eq!(120, perms.len());
+    }
+
+    #[test]
+    fn pick_combinations() {
+        let combos = collect(combinations(4, 2));
+        let expected = vec![
+            vec![0, 1],
+            vec![0, 2],
+            vec![1, 2],
+            vec![0, 3],
+            vec![1, 3],
+            vec![2, 3],
+        ];
+
+        assert_eq!(expected, combos);
+        assert_eq!(184_756, combinations(20, 10).count());
+        assert_eq!(1, combinations(3, 0).count());
+        assert_eq!(1, combinations(3, 3).count());
+        assert_eq!(0, combinations(3, 4).count());
+    }
+
+    #[test]
+    fn list_the_power_set() {
+        let subsets = collect(power_set(3));
+
+        assert_eq!(8, subsets.len());
+        assert_eq!(Vec::<usize>::new(), subsets[0]);
+        assert_eq!(vec![0, 2], subsets[5]);
+        assert_eq!(vec![0, 1, 2], subsets[7]);
+    }
+
+    #[test]
+    fn split_into_compositions() {
+        let parts = collect(compositions(2, 3));
+        let expected = vec![
+            vec![0, 0, 2],
+            vec![0, 1, 1],
+            vec![0, 2, 0],
+            vec![1, 0, 1],
+            vec![1, 1, 0],
+            vec![2, 0, 0],
+        ];
+
+        assert_eq!(expected, parts);
+        assert_eq!(176_851, compositions(100, 4).count());
+        assert_eq!(vec![vec![7]], collect(compositions(7, 1)));
+        assert_eq!(1, compositions(0, 0).count());
+        assert_eq!(0, compositions(3, 0).count());
+    }
+}
@@ -1,36 +1,42 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
 use std::fs;
 
 pub fn run() {
     let input = fs::read_to_string("./src/day01_input.txt").unwrap();
-    part1(&input);
-    part2(&input);
+    let depths = match parse_depths(&input) {
+        Ok(depths) => depths,
+        Err(err) => {
+            println!("Day 1: {}", err);
+            return;
+        }
+    };
+    part1(&depths);
+    part2(&depths);
 }
 
-fn part1(input: &str) {
+fn parse_depths(input: &str) -> Result<Vec<i64>, ParseError> {
+    return numbered_lines(input)
+        .map(|(n, line)| parse_token(n, line, line.trim(), "a depth"))
+        .collect();
+}
+
+fn part1(depths: &[i64]) {
     let mut prev = i64::MAX;
     let mut incr_count: usize = 0;
-    for line in input.lines() {
-        let parsed = parse_i64(line);
-        incr_count += usize::from(parsed > prev);
-        prev = parsed;
+    for parsed in depths {
+        incr_count += usize::from(*parsed > prev);
+        prev = *parsed;
     }
     println!("Day 1A: {:?}", incr_count);
 }
 
-fn part2(input: &str) {
-    let lines = input.lines().collect::<Vec<&str>>();
+fn part2(depths: &[i64]) {
     let mut prev = i64::MAX;
     let mut incr_count: usize = 0;
-    for i in 0..lines.len() {
-        let window = parse_i64(lines[i])
-            + parse_i64(lines.get(i + 1).unwrap_or(&"0"))
-            + parse_i64(lines.get(i + 2).unwrap_or(&"0"));
+    for i in 0..depths.len() {
+        let window = depths[i] + depths.get(i + 1).unwrap_or(&0) + depths.get(i + 2).unwrap_or(&0);
         incr_count += usize::from(window > prev);
         prev = window;
     }
     println!("Day 1B: {:?}", incr_count);
 }
-
-fn parse_i64(line: &str) -> i64 {
-    return line.parse::<i64>().unwrap();
-}
@@ -1,3 +1,5 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::fs;
 
 #[derive(Debug)]
@@ -66,30 +68,65 @@ impl Position {
 
 pub fn run() {
     let input = fs::read_to_string("./src/day02_input.txt").unwrap();
-    part1(&input);
-    part2(&input);
+    let commands = match parse_commands(&input) {
+        Ok(commands) => commands,
+        Err(err) => {
+            println!("Day 2: {}", err);
+            return;
+        }
+    };
+    part1(&commands);
+    part2(&commands);
 }
 
-fn part1(input: &str) {
-    let pos = do_the_parsing(&input, Part::One);
+fn part1(commands: &[(Direction, i64)]) {
+    let pos = steer(commands, Part::One);
     println!("Day 2A: {:?}", pos);
 }
 
-fn part2(input: &str) {
-    let pos = do_the_parsing(&input, Part::Two);
+fn part2(commands: &[(Direction, i64)]) {
+    let pos = steer(commands, Part::Two);
     println!("Day 2B: {:?}", pos);
 }
 
-fn do_the_parsing(input: &str, part: Part) -> Position {
-    let pos = input.lines().fold(Position::ZERO, |carry, line| {
-        let (direction, amount_str) = line.split_once(" ").unwrap();
-        let amount = amount_str.parse::<i64>().unwrap();
-        return match direction {
-            "up" => carry.up(amount, part),
-            "down" => carry.down(amount, part),
-            "forward" => carry.forward(amount, part),
-            _ => carry,
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Up,
+    Down,
+    Forward,
+}
+
+fn parse_commands(input: &str) -> Result<Vec<(Direction, i64)>, ParseError> {
+    let mut out = Vec::new();
+    for (n, line) in numbered_lines(input) {
+        let (direction, amount) = scan!((n, line), "{} {}", &str, i64)?;
+        let direction = match direction {
+            "up" => Direction::Up,
+            "down" => Direction::Down,
+            "forward" => Direction::Forward,
+            _ => {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    direction,
+                    "'up', 'down' or 'forward'",
+                ))
+            }
         };
-    });
+        out.push((direction, amount));
+    }
+    return Ok(out);
+}
+
+fn steer(commands: &[(Direction, i64)], part: Part) -> Position {
+    let pos = commands
+        .iter()
+        .fold(Position::ZERO, |carry, (direction, amount)| {
+            return match direction {
+                Direction::Up => carry.up(*amount, part),
+                Direction::Down => carry.down(*amount, part),
+                Direction::Forward => carry.forward(*amount, part),
+            };
+        });
     return pos;
 }
@@ -1,13 +1,38 @@
+use crate::parse::{numbered_lines, ParseError};
 use std::fs;
 
 pub fn run() {
     let input = fs::read_to_string("./src/day03_input.txt").unwrap();
-    part1(&input);
-    part2(&input);
+    let lines = match parse_report(&input) {
+        Ok(lines) => lines,
+        Err(err) => {
+            println!("Day 3: {}", err);
+            return;
+        }
+    };
+    part1(&lines);
+    part2(&lines);
 }
 
 const NUM_BITS: usize = 12;
 
+// every line has to be exactly NUM_BITS binary digits.
+fn parse_report(input: &str) -> Result<Vec<&str>, ParseError> {
+    let mut out = Vec::new();
+    for (n, line) in numbered_lines(input) {
+        let bits = line.trim();
+        if let Some(i) = bits.find(|ch| ch != '0' && ch != '1') {
+            return Err(ParseError::at_token(n, line, &bits[i..], "a binary digit"));
+        }
+        if bits.len() != NUM_BITS {
+            let expected = format!("{} binary digits", NUM_BITS);
+            return Err(ParseError::at_token(n, line, bits, &expected));
+        }
+        out.push(bits);
+    }
+    return Ok(out);
+}
+
 fn accumulate_bits(lines: &[&str]) -> [i64; NUM_BITS] {
     let mut accumulator = [0; NUM_BITS];
 
@@ -27,11 +52,10 @@ fn accumulate_bits(lines: &[&str]) -> [i64; NUM_BITS] {
     return accumulator;
 }
 
-fn part1(input: &str) {
+fn part1(lines: &[&str]) {
     let mut gamma: i64 = 0;
     let mut epsilon: i64 = 0;
-    let lines = input.lines().collect::<Vec<&str>>();
-    let accum = accumulate_bits(&lines);
+    let accum = accumulate_bits(lines);
 
     for i in 0..accum.len() {
         assert_ne!(0, accum[i]);
@@ -53,8 +77,8 @@ enum Kind {
     CO2,
 }
 
-fn find_line_of_kind(input: &str, kind: Kind) -> &str {
-    let mut lines = input.lines().collect::<Vec<&str>>();
+fn find_line_of_kind<'a>(report: &[&'a str], kind: Kind) -> &'a str {
+    let mut lines = report.to_vec();
 
     for i in 0..NUM_BITS {
         let accum = accumulate_bits(&lines);
@@ -100,9 +124,9 @@ fn find_line_of_kind(input: &str, kind: Kind) -> &str {
     return lines[0];
 }
 
-fn part2(input: &str) {
-    let oxy = find_line_of_kind(input, Kind::Oxygen);
-    let co2 = find_line_of_kind(input, Kind::CO2);
+fn part2(lines: &[&str]) {
+    let oxy = find_line_of_kind(lines, Kind::Oxygen);
+    let co2 = find_line_of_kind(lines, Kind::CO2);
 
     println!(
         "Day 3B: Oxygen = {:?}, CO2 = {:?}",
@@ -1,3 +1,5 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
+use crate::scan;
 use std::fs;
 
 type Grid<T> = [[T; 5]; 5];
@@ -69,43 +71,57 @@ impl Board {
 
 pub fn run() {
     let input = fs::read_to_string("./src/day04_input.txt").unwrap();
+    let (numbers_drawn, boards) = match parse_the_thing(&input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            println!("Day 4: {}", err);
+            return;
+        }
+    };
 
-    part1(&input);
-    part2(&input);
+    part1(&numbers_drawn, boards.clone());
+    part2(&numbers_drawn, boards);
 }
 
-fn parse_the_thing(input: &str) -> (Vec<i64>, Vec<Board>) {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let numbers_drawn = lines[0]
-        .split(',')
-        .map(|num| num.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
+fn parse_the_thing(input: &str) -> Result<(Vec<i64>, Vec<Board>), ParseError> {
+    let mut lines = numbered_lines(input);
+    let numbers_drawn = match lines.next() {
+        Some(first) => scan!(first, "{,}", Vec<i64>)?.0,
+        None => return Err(ParseError::at_end(1, "", "the numbers drawn")),
+    };
 
     let mut boards: Vec<Board> = Vec::new();
-    let mut i = 2;
-
-    while i < lines.len() {
-        let mut data: Grid<i64> = [[0; 5]; 5];
-
-        for j in 0..5 {
-            let line = lines[i + j];
-            let numbers = line
-                .split_whitespace()
-                .map(|num| num.parse::<i64>().unwrap())
-                .collect::<Vec<i64>>();
-
-            assert_eq!(5, numbers.len());
-
-            data[j] = numbers.try_into().unwrap();
+    let mut data: Grid<i64> = [[0; 5]; 5];
+    let mut row = 0;
+    let mut last = (1, "");
+
+    // the blank line after every board is skipped by numbered_lines, so
+    // every five lines make up one board.
+    for (n, line) in lines {
+        let mut numbers = line.split_whitespace();
+        for col in 0..5 {
+            data[row][col] = match numbers.next() {
+                Some(num) => parse_token(n, line, num, "a number")?,
+                None => return Err(ParseError::at_end(n, line, "5 numbers in a row")),
+            };
+        }
+        if let Some(extra) = numbers.next() {
+            return Err(ParseError::at_token(n, line, extra, "5 numbers in a row"));
         }
 
-        boards.push(Board::from_array(data));
+        row += 1;
+        if row == 5 {
+            boards.push(Board::from_array(data));
+            row = 0;
+        }
+        last = (n, line);
+    }
 
-        // there is one blank line after every board.
-        i += 6;
+    if row != 0 {
+        return Err(ParseError::at_end(last.0, last.1, "5 rows on every board"));
     }
 
-    return (numbers_drawn, boards);
+    return Ok((numbers_drawn, boards));
 }
 
 fn get_board_score(board: &Board, drawn_number: i64) -> i64 {
@@ -115,10 +131,8 @@ fn get_board_score(board: &Board, drawn_number: i64) -> i64 {
     return unmarked_sum * drawn_number;
 }
 
-fn part1(input: &str) {
-    let (numbers_drawn, mut boards) = parse_the_thing(input);
-
-    for num in numbers_drawn {
+fn part1(numbers_drawn: &[i64], mut boards: Vec<Board>) {
+    for num in numbers_drawn.iter().copied() {
         for board in &mut boards {
             board.maybe_mark_number(num);
 
@@ -131,11 +145,10 @@ fn part1(input: &str) {
     }
 }
 
-fn part2(input: &str) {
-    let (numbers_drawn, mut boards) = parse_the_thing(input);
+fn part2(numbers_drawn: &[i64], mut boards: Vec<Board>) {
     let mut last_winner: Option<(Board, i64)> = None;
 
-    for num in numbers_drawn {
+    for num in numbers_drawn.iter().copied() {
         for board in &mut boards {
             // skip boards that already won.
             if board.is_winner() {
@@ -1,10 +1,19 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::collections::HashMap;
 use std::fs;
 
 pub fn run() {
     let input = fs::read_to_string("./src/day05_input.txt").unwrap();
-    part1(&input);
-    part2(&input);
+    let segments = match parse_segments(&input) {
+        Ok(segments) => segments,
+        Err(err) => {
+            println!("Day 5: {}", err);
+            return;
+        }
+    };
+    part1(&segments);
+    part2(&segments);
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -19,27 +28,24 @@ enum Part {
     Two,
 }
 
-fn parse_the_thing(input: &str, part: Part) -> HashMap<Point, usize> {
-    let lines = input.trim().lines().collect::<Vec<&str>>();
-    let mut map: HashMap<Point, usize> = HashMap::new();
-
-    for line in lines {
-        let parts = line
-            .split("->")
-            .collect::<Vec<&str>>()
-            .iter()
-            .map(|chunk| chunk.split(',').collect::<Vec<&str>>())
-            .flatten()
-            .map(|num| num.trim().parse::<i64>().unwrap())
-            .collect::<Vec<i64>>();
+fn parse_segments(input: &str) -> Result<Vec<(Point, Point)>, ParseError> {
+    let mut out = Vec::new();
+    for (n, line) in numbered_lines(input) {
+        let (x1, y1, x2, y2) = scan!((n, line), "{},{} -> {},{}", i64, i64, i64, i64)?;
+        out.push((Point { x: x1, y: y1 }, Point { x: x2, y: y2 }));
+    }
+    return Ok(out);
+}
 
-        assert_eq!(4, parts.len());
+fn draw_segments(segments: &[(Point, Point)], part: Part) -> HashMap<Point, usize> {
+    let mut map: HashMap<Point, usize> = HashMap::new();
 
+    for (from, to) in segments {
         // the lines can go left-to-right, right-to-left, up-to-down and down-to-up.
-        let x1 = parts[0];
-        let y1 = parts[1];
-        let x2 = parts[2];
-        let y2 = parts[3];
+        let x1 = from.x;
+        let y1 = from.y;
+        let x2 = to.x;
+        let y2 = to.y;
 
         let x_addend = if x1 == x2 {
             0
@@ -77,8 +83,8 @@ fn parse_the_thing(input: &str, part: Part) -> HashMap<Point, usize> {
     return map;
 }
 
-fn part1(input: &str) {
-    let pts = parse_the_thing(input, Part::One);
+fn part1(segments: &[(Point, Point)]) {
+    let pts = draw_segments(segments, Part::One);
     let mut num_overlaps: usize = 0;
 
     for (_, qty) in pts {
@@ -90,8 +96,8 @@ fn part1(input: &str) {
     println!("Day 5A: {:?}", num_overlaps);
 }
 
-fn part2(input: &str) {
-    let pts = parse_the_thing(input, Part::Two);
+fn part2(segments: &[(Point, Point)]) {
+    let pts = draw_segments(segments, Part::Two);
     let mut num_overlaps: usize = 0;
 
     for (_, qty) in pts {
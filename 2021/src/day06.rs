@@ -1,24 +1,46 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
 use std::fs;
 
 pub fn run() {
     let input = fs::read_to_string("./src/day06_input.txt").unwrap();
-    part1(&input);
-    part2(&input);
+    let fish = match parse_fish(&input) {
+        Ok(fish) => fish,
+        Err(err) => {
+            println!("Day 6: {}", err);
+            return;
+        }
+    };
+    part1(&fish);
+    part2(&fish);
 }
 
-fn parse_and_calculate_count_for_day(input: &str, day: usize) -> usize {
-    let nums: Vec<i8> = input
-        .trim()
-        .split(',')
-        .map(|num| num.parse::<i8>().unwrap())
-        .collect();
-
+// the timers are all on the first line, and go from 0 to 8.
+fn parse_fish(input: &str) -> Result<[usize; 9], ParseError> {
+    let (n, line) = match numbered_lines(input).next() {
+        Some(first) => first,
+        None => return Err(ParseError::at_end(1, "", "a list of timers")),
+    };
     let mut fish_by_timer_value = [0usize; 9];
 
-    for t in nums {
-        fish_by_timer_value[t as usize] += 1;
+    for num in line.split(',') {
+        let t: usize = parse_token(n, line, num.trim(), "a timer from 0 to 8")?;
+        if t >= fish_by_timer_value.len() {
+            return Err(ParseError::at_token(
+                n,
+                line,
+                num.trim(),
+                "a timer from 0 to 8",
+            ));
+        }
+        fish_by_timer_value[t] += 1;
     }
 
+    return Ok(fish_by_timer_value);
+}
+
+fn calculate_count_for_day(fish: &[usize; 9], day: usize) -> usize {
+    let mut fish_by_timer_value = *fish;
+
     for _ in 0..day {
         let zero_tmp = fish_by_timer_value[0];
 
@@ -36,13 +58,10 @@ fn parse_and_calculate_count_for_day(input: &str, day: usize) -> usize {
     return fish_by_timer_value.iter().sum();
 }
 
-fn part1(input: &str) {
-    println!("Day 6A: {:?}", parse_and_calculate_count_for_day(input, 80));
+fn part1(fish: &[usize; 9]) {
+    println!("Day 6A: {:?}", calculate_count_for_day(fish, 80));
 }
 
-fn part2(input: &str) {
-    println!(
-        "Day 6B: {:?}",
-        parse_and_calculate_count_for_day(input, 256)
-    );
+fn part2(fish: &[usize; 9]) {
+    println!("Day 6B: {:?}", calculate_count_for_day(fish, 256));
 }
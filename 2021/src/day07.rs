@@ -1,12 +1,16 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::fs;
 
 pub fn run() {
     let input = fs::read_to_string("./src/day07_input.txt").unwrap();
-    let mut numbers: Vec<i64> = input
-        .trim()
-        .split(',')
-        .map(|num| num.parse::<i64>().unwrap())
-        .collect();
+    let mut numbers = match parse_positions(&input) {
+        Ok(numbers) => numbers,
+        Err(err) => {
+            println!("Day 7: {}", err);
+            return;
+        }
+    };
 
     numbers.sort();
 
@@ -14,6 +18,13 @@ pub fn run() {
     part2(&numbers);
 }
 
+fn parse_positions(input: &str) -> Result<Vec<i64>, ParseError> {
+    return match numbered_lines(input).next() {
+        Some(first) => Ok(scan!(first, "{,}", Vec<i64>)?.0),
+        None => Err(ParseError::at_end(1, "", "a list of positions")),
+    };
+}
+
 fn part1(input: &[i64]) {
     assert_eq!(0, input.len() % 2);
 
@@ -1,20 +1,44 @@
+use crate::combinatorics::permutations;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
 
 pub fn run() {
     let input = fs::read_to_string("./src/day08_input.txt").unwrap();
-    let lines: Vec<&str> = input.lines().collect();
-    part1(&lines);
-    part2(&lines);
+    let displays = match parse_displays(&input) {
+        Ok(displays) => displays,
+        Err(err) => {
+            println!("Day 8: {}", err);
+            return;
+        }
+    };
+    part1(&displays);
+    part2(&displays);
+}
+
+// the ten patterns and the output digits of every display, with each code
+// using the segments "a" to "g".
+fn parse_displays(input: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    let mut out = Vec::new();
+    for (n, line) in numbered_lines(input) {
+        let (patterns, output_digits) = scan!((n, line), "{} | {}", &str, &str)?;
+        for code in line.split_whitespace() {
+            if code != "|" && !code.chars().all(|chr| ('a'..='g').contains(&chr)) {
+                return Err(ParseError::at_token(n, line, code, "segments from a to g"));
+            }
+        }
+        out.push((patterns, output_digits));
+    }
+    return Ok(out);
 }
 
-fn part1(lines: &[&str]) {
+fn part1(displays: &[(&str, &str)]) {
     let mut count: usize = 0;
 
-    for line in lines {
-        let (_, output_digits) = line.split_once('|').unwrap();
-        for code in output_digits.trim().split_whitespace() {
+    for (_, output_digits) in displays {
+        for code in output_digits.split_whitespace() {
             count += match code.len() {
                 2 | 3 | 4 | 7 => 1,
                 _ => 0,
@@ -113,39 +137,21 @@ const DIGITS_AS_SEGMENTS: [&[Segment]; 10] = [
 
 type Orientation = HashMap<char, Segment>;
 
-fn make_orientations(stack: &[Segment], out: &mut Vec<Orientation>) {
-    if stack.len() == 7 {
-        let mut ori = Orientation::new();
-        let chars: Vec<char> = ('a'..='g').collect();
-        for i in 0..chars.len() {
-            ori.insert(chars[i], stack[i]);
-        }
-        out.push(ori);
-        return;
-    }
-
-    for seg in SEGMENTS {
-        if stack.contains(&seg) {
-            continue;
-        }
-        let mut next = stack.to_vec();
-        next.push(seg);
-        make_orientations(&next, out);
-    }
+fn make_orientation(order: &[usize]) -> Orientation {
+    return ('a'..='g')
+        .zip(order.iter().map(|i| SEGMENTS[*i]))
+        .collect();
 }
 
 fn attempt_orientation<'a>(
-    line: &'a str,
+    patterns: &'a str,
     ori: &Orientation,
 ) -> Option<HashMap<usize, HashSet<char>>> {
     assert_eq!(7, ori.len());
 
     let mut matches: HashMap<usize, HashSet<char>> = HashMap::new();
 
-    for code in line.split_whitespace() {
-        if code == "|" {
-            continue;
-        }
+    for code in patterns.split_whitespace() {
         let segments: HashSet<Segment> = code.chars().map(|chr| *ori.get(&chr).unwrap()).collect();
         for i in 0..DIGITS_AS_SEGMENTS.len() {
             let maybe_match = DIGITS_AS_SEGMENTS[i];
@@ -166,19 +172,19 @@ fn attempt_orientation<'a>(
     };
 }
 
-fn part2(lines: &[&str]) {
-    let mut orientations = Vec::new();
-
+fn part2(displays: &[(&str, &str)]) {
     // create factorial(7) orientations = 5040.
-    make_orientations(&[], &mut orientations);
+    let orientations: Vec<Orientation> = permutations(7)
+        .map(|order| make_orientation(&order))
+        .collect();
 
     let mut sum = 0;
 
-    for line in lines {
+    for (patterns, output_digits) in displays {
         let mut found = None;
 
         for ori in &orientations {
-            found = attempt_orientation(line, ori);
+            found = attempt_orientation(patterns, ori);
             if found.is_some() {
                 break;
             }
@@ -187,10 +193,9 @@ fn part2(lines: &[&str]) {
         assert!(found.is_some());
 
         let stuff = found.unwrap();
-        let (_, output_digits) = line.split_once('|').unwrap();
         let mut digit_str = String::new();
 
-        for chunk in output_digits.trim().split_whitespace() {
+        for chunk in output_digits.split_whitespace() {
             let output_chars: HashSet<char> = chunk.chars().collect();
             let (found_digit, _) = stuff.iter().find(|kv| *kv.1 == output_chars).unwrap();
 
@@ -1,3 +1,5 @@
+use crate::image;
+use crate::parse::{numbered_lines, ParseError};
 use std::collections::HashSet;
 use std::fs;
 
@@ -7,20 +9,49 @@ type Grid = [[u8; SIZE_X]; SIZE_Y];
 
 pub fn run() {
     let input = fs::read_to_string("./src/day09_input.txt").unwrap();
-    let mut data: Grid = [[0; 100]; 100];
+    let data = match parse_heights(&input) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("Day 9: {}", err);
+            return;
+        }
+    };
+
+    part1(&data);
+    part2(&data);
+}
+
+fn parse_heights(input: &str) -> Result<Grid, ParseError> {
+    let mut data: Grid = [[0; SIZE_X]; SIZE_Y];
     let mut i = 0;
+    let mut last = (1, "");
 
-    for line in input.trim().lines() {
-        let digits: Vec<u8> = line.chars().map(|chr| (chr as u8) - 48).collect();
-        assert_eq!(SIZE_X, digits.len());
-        data[i] = digits.try_into().unwrap();
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        if i == SIZE_Y {
+            let expected = format!("{} rows", SIZE_Y);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+        if let Some(bad) = text.find(|chr: char| !chr.is_ascii_digit()) {
+            return Err(ParseError::at_token(n, line, &text[bad..], "a digit"));
+        }
+        if text.len() != SIZE_X {
+            let expected = format!("{} digits", SIZE_X);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+        for (x, chr) in text.bytes().enumerate() {
+            data[i][x] = chr - b'0';
+        }
         i += 1;
+        last = (n, line);
     }
 
-    assert_eq!(SIZE_Y, i);
+    if i != SIZE_Y {
+        let expected = format!("{} rows", SIZE_Y);
+        return Err(ParseError::at_end(last.0, last.1, &expected));
+    }
 
-    part1(&data);
-    part2(&data);
+    return Ok(data);
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -137,4 +168,31 @@ fn part2(grid: &Grid) {
     let c = &basins[basins.len() - 3];
 
     println!("Day 9B: {}", a.len() * b.len() * c.len());
+
+    if image::is_enabled() {
+        let img = basins_to_image(grid, &basins[(basins.len() - 3)..]);
+        image::save("day09_basins.png", &img.scaled(4).to_png());
+    }
+}
+
+// heights 0-9 in shades of blue, then the same heights in red for the highlighted basins.
+fn make_palette() -> Vec<image::Rgb> {
+    let mut palette: Vec<image::Rgb> = Vec::new();
+    for v in 0..10 {
+        let shade = (v * 25) as u8;
+        palette.push([shade / 2, shade / 2, 30 + shade]);
+    }
+    for v in 0..10 {
+        let shade = (v * 25) as u8;
+        palette.push([100 + shade / 2, shade / 4, shade / 4]);
+    }
+    return palette;
+}
+
+fn basins_to_image(grid: &Grid, highlighted: &[HashSet<Point>]) -> image::Image {
+    return image::Image::from_fn(SIZE_X, SIZE_Y, &make_palette(), |x, y| {
+        let pt = maybe_get_point(grid, x as i64, y as i64).unwrap();
+        let is_highlighted = highlighted.iter().any(|b| b.contains(&pt));
+        return pt.value as u8 + if is_highlighted { 10 } else { 0 };
+    });
 }
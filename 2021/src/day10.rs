@@ -1,87 +1,85 @@
-use core::panic;
+use crate::parse::{numbered_lines, ParseError};
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day10_input.txt").unwrap();
-    let lines: Vec<&str> = input.lines().collect();
-
-    part1(&lines);
-    part2(&lines);
-}
-
-fn get_closing_char(chr: char) -> char {
-    return match chr {
-        '[' => ']',
-        '(' => ')',
-        '{' => '}',
-        '<' => '>',
-        _ => panic!("Invalid character: {}.", chr),
-    };
-}
-
-fn get_corrupt_score(chr: char) -> i64 {
-    return match chr {
-        ')' => 3,
-        ']' => 57,
-        '}' => 1197,
-        '>' => 25137,
-        _ => panic!("Cannot determine score of {}.", chr),
+    let results = match parse_lines(&input) {
+        Ok(results) => results,
+        Err(err) => {
+            println!("Day 10: {}", err);
+            return;
+        }
     };
-}
 
-fn get_incomplete_score(chr: char) -> i64 {
-    return match chr {
-        ')' => 1,
-        ']' => 2,
-        '}' => 3,
-        '>' => 4,
-        _ => panic!("Cannot determine score of {}.", chr),
-    };
+    part1(&results);
+    part2(&results);
 }
 
+// the opening and closing character of each kind of bracket, and what it
+// scores when it is corrupt or missing.
+const BRACKETS: [(char, char, i64, i64); 4] = [
+    ('(', ')', 3, 1),
+    ('[', ']', 57, 2),
+    ('{', '}', 1197, 3),
+    ('<', '>', 25137, 4),
+];
+
+#[derive(Debug, Clone)]
+// brackets are kept as their index in BRACKETS.
 enum ParseResult {
     Valid,
-    Corrupt { expected: char, found: char },
-    Incomplete { tokens: Vec<char> },
+    Corrupt { expected: usize, found: usize },
+    Incomplete { tokens: Vec<usize> },
 }
 
-fn parse(line: &str) -> ParseResult {
-    let chars: Vec<char> = line.chars().collect();
-    let mut stack: Vec<char> = Vec::new();
-
-    for chr in chars {
-        match chr {
-            '[' | '(' | '{' | '<' => {
-                stack.push(chr);
-            }
-            ']' | ')' | '}' | '>' => {
-                let popped = stack.pop();
-                let expected = get_closing_char(popped.unwrap());
-
-                if chr != expected {
-                    return ParseResult::Corrupt {
-                        expected: expected,
-                        found: chr,
-                    };
+// corrupt and incomplete lines are part of the puzzle, anything that is
+// not a bracket is an error in the input.
+fn parse(n: usize, line: &str) -> Result<ParseResult, ParseError> {
+    let text = line.trim();
+    let mut stack: Vec<usize> = Vec::new();
+
+    for (i, chr) in text.char_indices() {
+        if let Some(kind) = BRACKETS.iter().position(|b| b.0 == chr) {
+            stack.push(kind);
+        } else if let Some(kind) = BRACKETS.iter().position(|b| b.1 == chr) {
+            let popped = match stack.pop() {
+                Some(popped) => popped,
+                None => {
+                    let token = &text[i..(i + 1)];
+                    return Err(ParseError::at_token(n, line, token, "an opening bracket"));
                 }
+            };
+
+            if kind != popped {
+                return Ok(ParseResult::Corrupt {
+                    expected: popped,
+                    found: kind,
+                });
             }
-            _ => panic!("Unexpected character: {}.", chr),
-        };
+        } else {
+            let token = &text[i..(i + chr.len_utf8())];
+            return Err(ParseError::at_token(n, line, token, "a bracket"));
+        }
     }
 
     if stack.is_empty() {
-        return ParseResult::Valid;
+        return Ok(ParseResult::Valid);
     }
 
-    return ParseResult::Incomplete { tokens: stack };
+    return Ok(ParseResult::Incomplete { tokens: stack });
 }
 
-fn part1(lines: &[&str]) {
+fn parse_lines(input: &str) -> Result<Vec<ParseResult>, ParseError> {
+    return numbered_lines(input)
+        .map(|(n, line)| parse(n, line))
+        .collect();
+}
+
+fn part1(results: &[ParseResult]) {
     let mut score = 0;
 
-    for line in lines {
-        let parsed = parse(line);
+    for parsed in results {
         score += match parsed {
-            ParseResult::Corrupt { found, expected: _ } => get_corrupt_score(found),
+            ParseResult::Corrupt { found, expected: _ } => BRACKETS[*found].2,
             _ => 0,
         };
     }
@@ -89,12 +87,11 @@ fn part1(lines: &[&str]) {
     println!("Day 10A: {:?}", score);
 }
 
-fn part2(lines: &[&str]) {
+fn part2(results: &[ParseResult]) {
     let mut scores: Vec<i64> = Vec::new();
 
-    for line in lines {
-        let parsed = parse(line);
-        let inner_score: Option<i64> = match parsed {
+    for parsed in results {
+        let inner_score: Option<i64> = match parsed.clone() {
             ParseResult::Corrupt {
                 expected: _,
                 found: _,
@@ -105,12 +102,12 @@ fn part2(lines: &[&str]) {
                 while !tokens.is_empty() {
                     let token = tokens.pop().unwrap();
                     score *= 5;
-                    score += get_incomplete_score(get_closing_char(token));
+                    score += BRACKETS[token].3;
                 }
 
                 Some(score)
             }
-            ParseResult::Valid => panic!("Line must not be valid, yet."),
+            ParseResult::Valid => None,
         };
 
         if let Some(s) = inner_score {
@@ -122,3 +119,33 @@ fn part2(lines: &[&str]) {
 
     println!("Day 10B: {:?}", scores[(scores.len() - 1) / 2]);
 }
+
+mod tests {
+    use crate::day10::*;
+
+    #[test]
+    fn find_corrupt_and_incomplete_lines() {
+        let results =
+            parse_lines("[({(<(())[]>[[{[]{<()<>>\n{([(<{}[<>[]}>{[]{[(<()>\n()").unwrap();
+        assert!(matches!(results[0], ParseResult::Incomplete { .. }));
+        assert!(matches!(
+            results[1],
+            ParseResult::Corrupt {
+                expected: 1,
+                found: 2
+            }
+        ));
+        assert!(matches!(results[2], ParseResult::Valid));
+
+        let err = parse_lines("()\n  (x)").unwrap_err();
+        assert_eq!(
+            (2, 4, "a bracket".to_string()),
+            (err.line, err.column, err.expected)
+        );
+        let err = parse_lines("())").unwrap_err();
+        assert_eq!(
+            (1, 3, "an opening bracket".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::parse::{numbered_lines, ParseError};
+
 #[derive(Debug, Clone, Copy)]
 struct Point {
     x: i64,
@@ -26,28 +28,54 @@ type Grid = [[Octopus; SIZE_X]; SIZE_Y];
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day11_input.txt").unwrap();
-    let mut grid = [[Octopus::EMPTY; SIZE_X]; SIZE_Y];
+    let grid = match parse_grid(&input) {
+        Ok(grid) => grid,
+        Err(err) => {
+            println!("Day 11: {}", err);
+            return;
+        }
+    };
 
-    let mut x = 0;
+    part1(grid);
+    part2(grid);
+}
+
+fn parse_grid(input: &str) -> Result<Grid, ParseError> {
+    let mut grid = [[Octopus::EMPTY; SIZE_X]; SIZE_Y];
     let mut y = 0;
-    for line in input.trim().lines() {
-        for chr in line.chars() {
-            grid[y][x].energy = (chr as i64) - 48;
+    let mut last = (1, "");
+
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        if y == SIZE_Y {
+            let expected = format!("{} rows", SIZE_Y);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+        if let Some(bad) = text.find(|chr: char| !chr.is_ascii_digit()) {
+            return Err(ParseError::at_token(n, line, &text[bad..], "a digit"));
+        }
+        if text.len() != SIZE_X {
+            let expected = format!("{} digits", SIZE_X);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+
+        for (x, chr) in text.bytes().enumerate() {
+            grid[y][x].energy = (chr - b'0') as i64;
             grid[y][x].location = Point {
                 x: x as i64,
                 y: y as i64,
             };
-            x += 1;
         }
-        assert_eq!(SIZE_X, x);
-
         y += 1;
-        x = 0;
+        last = (n, line);
     }
-    assert_eq!(SIZE_Y, y);
 
-    part1(grid);
-    part2(grid);
+    if y != SIZE_Y {
+        let expected = format!("{} rows", SIZE_Y);
+        return Err(ParseError::at_end(last.0, last.1, &expected));
+    }
+
+    return Ok(grid);
 }
 
 fn maybe_get_point(grid: &Grid, x: i64, y: i64) -> Option<Point> {
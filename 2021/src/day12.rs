@@ -1,18 +1,30 @@
+use crate::dot;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::collections::HashMap;
 
 type Caves = HashMap<String, Vec<String>>;
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day12_input.txt").unwrap();
-    let caves = parse_caves(&input);
+    let caves = match parse_caves(&input) {
+        Ok(caves) => caves,
+        Err(err) => {
+            println!("Day 12: {}", err);
+            return;
+        }
+    };
+    if dot::is_enabled() {
+        dot::save("day12_caves.dot", &caves_to_graph(&caves));
+    }
     part1(&caves);
     part2(&caves);
 }
 
-fn parse_caves(input: &str) -> Caves {
+fn parse_caves(input: &str) -> Result<Caves, ParseError> {
     let mut out = Caves::new();
-    for line in input.trim().lines() {
-        let (from, to) = line.split_once('-').unwrap();
+    for (n, line) in numbered_lines(input) {
+        let (from, to) = scan!((n, line), "{}-{}", &str, &str)?;
 
         let a = out.entry(from.to_string()).or_insert(Vec::new());
         a.push(to.to_string());
@@ -20,7 +32,34 @@ fn parse_caves(input: &str) -> Caves {
         let b = out.entry(to.to_string()).or_insert(Vec::new());
         b.push(from.to_string());
     }
-    return out;
+    return Ok(out);
+}
+
+// big caves are boxes, and each tunnel is drawn once.
+fn caves_to_graph(caves: &Caves) -> dot::Graph {
+    let mut graph = dot::Graph::undirected("day12");
+    let mut names: Vec<&String> = caves.keys().collect();
+    names.sort();
+
+    for name in &names {
+        let shape = match name.as_str() {
+            "start" | "end" => "doublecircle",
+            _ if name.chars().all(char::is_uppercase) => "box",
+            _ => "circle",
+        };
+        graph.node(name, &[("shape", shape)]);
+    }
+
+    for name in &names {
+        let mut tunnels: Vec<&String> = caves[*name].iter().filter(|to| name < to).collect();
+        tunnels.sort();
+
+        for to in tunnels {
+            graph.edge(name, to, &[]);
+        }
+    }
+
+    return graph;
 }
 
 fn make_paths_part1(visited: &[String], caves: &Caves, out: &mut Vec<Vec<String>>) {
@@ -89,3 +128,40 @@ fn part2(caves: &Caves) {
 
     println!("Day 12B: {}", out.len());
 }
+
+mod tests {
+    use crate::day12::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end
+"#;
+
+    #[test]
+    fn count_the_example_paths() {
+        let caves = parse_caves(EXAMPLE_INPUT).unwrap();
+
+        let mut out = Vec::new();
+        make_paths_part1(&["start".to_string()], &caves, &mut out);
+        assert_eq!(10, out.len());
+
+        let mut out = Vec::new();
+        make_paths_part2(&["start".to_string()], &caves, &mut out);
+        assert_eq!(36, out.len());
+    }
+
+    #[test]
+    fn draw_the_example() {
+        let caves = parse_caves(EXAMPLE_INPUT).unwrap();
+
+        assert_eq!(
+            include_str!("day12_example.dot"),
+            caves_to_graph(&caves).to_dot()
+        );
+    }
+}
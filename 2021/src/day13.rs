@@ -1,11 +1,22 @@
+use crate::image;
+use crate::ocr;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::Hash;
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day13_input.txt").unwrap();
-    part1(&input);
-    part2(&input);
+    let (paper, folds) = match parse(&input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            println!("Day 13: {}", err);
+            return;
+        }
+    };
+    part1(&paper, &folds);
+    part2(&paper, &folds);
 }
 
 #[derive(Debug)]
@@ -27,44 +38,31 @@ enum Fold {
     Left { x: i64 },
 }
 
-enum ParserState {
-    Point,
-    Fold,
-}
-
-fn parse(input: &str) -> (Paper, Vec<Fold>) {
+// the dots come first, then the folds. there has to be at least one fold.
+fn parse(input: &str) -> Result<(Paper, Vec<Fold>), ParseError> {
     let mut parsed_pts: HashSet<Point> = HashSet::new();
     let mut folds: Vec<Fold> = Vec::new();
-    let mut parse_state = ParserState::Point;
-
-    for line in input.trim().lines() {
-        if line.is_empty() {
-            parse_state = ParserState::Fold;
-            continue;
-        }
-
-        match parse_state {
-            ParserState::Point => {
-                let (x_str, y_str) = line.split_once(',').unwrap();
-                parsed_pts.insert(Point {
-                    x: x_str.parse().unwrap(),
-                    y: y_str.parse().unwrap(),
-                });
-            }
-            ParserState::Fold => {
-                let parts: Vec<&str> = line.split(&[' ', '='][..]).collect();
-
-                match parts[2] {
-                    "x" => folds.push(Fold::Left {
-                        x: parts[3].parse().unwrap(),
-                    }),
-                    "y" => folds.push(Fold::Up {
-                        y: parts[3].parse().unwrap(),
-                    }),
-                    _ => panic!("Unexpected parts[2]: {:?}", parts[2]),
-                }
+    let mut last = (1, "");
+
+    for (n, line) in numbered_lines(input) {
+        if line.trim().starts_with("fold") {
+            let (axis, at) = scan!((n, line), "fold along {}={}", &str, i64)?;
+            match axis {
+                "x" => folds.push(Fold::Left { x: at }),
+                "y" => folds.push(Fold::Up { y: at }),
+                _ => return Err(ParseError::at_token(n, line, axis, "'x' or 'y'")),
             }
+        } else if !folds.is_empty() {
+            return Err(ParseError::at_token(n, line, line.trim(), "'fold along'"));
+        } else {
+            let (x, y) = scan!((n, line), "{},{}", i64, i64)?;
+            parsed_pts.insert(Point { x, y });
         }
+        last = (n, line);
+    }
+
+    if folds.is_empty() {
+        return Err(ParseError::at_end(last.0, last.1, "a fold"));
     }
 
     let size = parsed_pts.iter().fold(Point { x: 0, y: 0 }, |carry, pt| {
@@ -80,7 +78,7 @@ fn parse(input: &str) -> (Paper, Vec<Fold>) {
         width: size.x,
     };
 
-    return (paper, folds);
+    return Ok((paper, folds));
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -162,24 +160,55 @@ fn print_paper(paper: &Paper) {
     println!()
 }
 
-fn part1(input: &str) {
-    let (mut paper, folds) = parse(input);
+fn read_paper(paper: &Paper) -> Option<String> {
+    return ocr::recognize_points(paper.points.iter().map(|pt| (pt.x, pt.y)));
+}
 
-    paper = do_fold(&paper, folds[0]);
+fn part1(paper: &Paper, folds: &[Fold]) {
+    let paper = do_fold(paper, folds[0]);
     let visible_dots = paper.points.len();
 
     println!("Day 13A: {:?}", visible_dots);
 }
 
-fn part2(input: &str) {
-    let (mut paper, folds) = parse(input);
+fn part2(paper: &Paper, folds: &[Fold]) {
+    let mut paper = do_fold(paper, folds[0]);
+    for fold in &folds[1..] {
+        paper = do_fold(&paper, *fold);
+    }
+
+    match read_paper(&paper) {
+        Some(code) => println!("Day 13B: {}", code),
+        None => {
+            // a human will have to read it.
+            println!("Day 13B:");
+            print_paper(&paper);
+        }
+    }
 
-    for fold in folds {
-        paper = do_fold(&paper, fold);
+    if image::is_enabled() {
+        image::save(
+            "day13_paper.png",
+            &paper_to_image(&paper).scaled(10).to_png(),
+        );
     }
+}
 
-    println!("Day 13B:");
-    print_paper(&paper);
+const PAPER_PALETTE: [image::Rgb; 2] = [[250, 250, 240], [30, 30, 30]];
+
+fn paper_to_image(paper: &Paper) -> image::Image {
+    return image::Image::from_fn(
+        paper.width as usize,
+        paper.height as usize,
+        &PAPER_PALETTE,
+        |x, y| {
+            let pt = Point {
+                x: x as i64,
+                y: y as i64,
+            };
+            return u8::from(paper.points.contains(&pt));
+        },
+    );
 }
 
 mod tests {
@@ -219,4 +248,44 @@ mod tests {
 
         assert_eq!(MaybeFoldedPoint::Folded(Point { x: 5, y: 10 }), pt);
     }
+
+    #[test]
+    fn read_the_folded_example() {
+        let input = "6,10\n0,14\n9,10\n0,3\n10,4\n4,11\n6,0\n6,12\n4,1\n0,13\n10,12\n3,4\n3,0\n8,4\n1,10\n2,14\n8,10\n9,0\n\nfold along y=7\nfold along x=5";
+        let (mut paper, folds) = parse(input).unwrap();
+
+        for fold in folds {
+            paper = do_fold(&paper, fold);
+        }
+
+        // the example folds into a square, which is not a letter.
+        assert_eq!(16, paper.points.len());
+        assert_eq!(None, read_paper(&paper));
+    }
+
+    #[test]
+    fn paper_to_image_marks_dots() {
+        let (paper, _) = parse("0,0\n2,1\n\nfold along y=5").unwrap();
+        let img = paper_to_image(&paper);
+
+        assert_eq!((3, 2), (img.width, img.height));
+        assert_eq!(1, img.get(0, 0));
+        assert_eq!(0, img.get(1, 0));
+        assert_eq!(1, img.get(2, 1));
+    }
+
+    #[test]
+    fn report_parse_errors() {
+        let check = |input: &str, line: usize, column: usize, expected: &str| {
+            let err = parse(input).unwrap_err();
+            assert_eq!(
+                (line, column, expected.to_string()),
+                (err.line, err.column, err.expected)
+            );
+        };
+        check("0,0\n2;1\n\nfold along y=5", 2, 2, "','");
+        check("0,0\n\nfold along z=5", 3, 12, "'x' or 'y'");
+        check("0,0\n\nfold along y=5\n1,1", 4, 1, "'fold along'");
+        check("0,0\n2,1\n", 2, 4, "a fold");
+    }
 }
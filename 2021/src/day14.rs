@@ -1,83 +1,90 @@
+use crate::memo::Memo;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::{collections::HashMap};
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day14_input.txt").unwrap();
-    part1(&input);
-    part2(&input);
+    let (template, rules) = match parse(&input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            println!("Day 14: {}", err);
+            return;
+        }
+    };
+    part1(template, &rules);
+    part2(template, &rules);
 }
 
 type Rules<'a> = HashMap<&'a str, char>;
 
-fn parse(input: &str) -> (&str, Rules) {
-    let lines: Vec<&str> = input.trim().lines().collect();
-    let template = lines[0].trim();
+fn parse(input: &str) -> Result<(&str, Rules), ParseError> {
+    let mut lines = numbered_lines(input);
+    let template = match lines.next() {
+        Some((_, line)) => line.trim(),
+        None => return Err(ParseError::at_end(1, "", "a polymer template")),
+    };
     let mut rules = Rules::new();
 
-    for i in 2..lines.len() {
-        let (pair, insert_element) = lines[i].trim().split_once(" -> ").unwrap();
-        rules.insert(pair, insert_element.chars().nth(0).unwrap());
+    for (n, line) in lines {
+        let (pair, insert_element) = scan!((n, line), "{} -> {}", &str, char)?;
+        if pair.chars().count() != 2 {
+            return Err(ParseError::at_token(n, line, pair, "a pair of elements"));
+        }
+        rules.insert(pair, insert_element);
     }
 
-    return (template, rules);
+    return Ok((template, rules));
 }
 
 type KnownExpansion = HashMap<char, i64>;
 
-fn polymerize_and_count_chars(input: &str, rules: &Rules, iters: i64) -> HashMap<char, i64> {
-    fn rec(pair: &str, rules: &Rules, expand_to_depth: i64, cache: &mut HashMap<(String, i64), KnownExpansion>, out: &mut HashMap<char, i64>) {
-        assert_eq!(2, pair.len());
-
-        let cache_key = (pair.to_string(), expand_to_depth);
-
-        if let Some(known) = cache.get(&cache_key) {
-            for (ch, count) in known {
-                let e = out.entry(*ch).or_insert(0);
-                *e += count;
+// counts the characters inserted between the two characters of "pair".
+fn expand_pair(
+    pair: (char, char),
+    rules: &Rules,
+    depth: i64,
+    memo: &mut Memo<(char, char, i64), KnownExpansion>,
+) -> KnownExpansion {
+    return memo.get_or_compute((pair.0, pair.1, depth), |memo| {
+        let key: String = [pair.0, pair.1].iter().collect();
+        let ch = rules[key.as_str()];
+        let mut out = KnownExpansion::new();
+        out.insert(ch, 1);
+
+        if depth > 1 {
+            for side in [(pair.0, ch), (ch, pair.1)] {
+                for (c, count) in expand_pair(side, rules, depth - 1, memo) {
+                    *out.entry(c).or_insert(0) += count;
+                }
             }
-            return;
-        }
-        
-        // cache the entire subtree.
-        let mut inner_out = HashMap::new();
-        let ch = rules[pair];
-        inner_out.insert(ch, 1);
-
-        if expand_to_depth > 1 {
-            let next_expand_to = expand_to_depth - 1;
-            let left = format!("{}{}", &pair[0..1], ch);
-            let right = format!("{}{}", ch, &pair[1..2]);
-
-            rec(&left, rules, next_expand_to, cache, &mut inner_out);
-            rec(&right, rules, next_expand_to, cache, &mut inner_out);
-        }
-
-        for (ch, count) in &inner_out {
-            let e = out.entry(*ch).or_insert(0);
-            *e += count;
         }
 
-        cache.insert(cache_key, inner_out);
-    }
+        return out;
+    });
+}
 
+fn polymerize_and_count_chars(input: &str, rules: &Rules, iters: i64) -> HashMap<char, i64> {
     let mut out = HashMap::new();
-    let mut cache = HashMap::new();
+    let mut memo = Memo::new();
+    let chars: Vec<char> = input.chars().collect();
 
-    for ch in input.chars() {
-        let e = out.entry(ch).or_insert(0);
+    for ch in &chars {
+        let e = out.entry(*ch).or_insert(0);
         *e += 1;
     }
 
-    for i in 0..(input.len() - 1) {
-        let pair = &input[i..(i + 2)];
-        rec(pair, rules, iters, &mut cache, &mut out);
+    for pair in chars.windows(2) {
+        for (ch, count) in expand_pair((pair[0], pair[1]), rules, iters, &mut memo) {
+            *out.entry(ch).or_insert(0) += count;
+        }
     }
-    
+
     return out;
 }
 
-fn polymerize_and_get_the_diff(input: &str, iterations: i64) -> i64 {
-    let (template, rules) = parse(input);
-    let chars = polymerize_and_count_chars(template, &rules, iterations);
+fn polymerize_and_get_the_diff(template: &str, rules: &Rules, iterations: i64) -> i64 {
+    let chars = polymerize_and_count_chars(template, rules, iterations);
 
     let max = chars.values().max().unwrap();
     let min = chars.values().min().unwrap();
@@ -85,12 +92,12 @@ fn polymerize_and_get_the_diff(input: &str, iterations: i64) -> i64 {
     return max - min;
 }
 
-fn part1(input: &str) {
-    println!("Day 14A: {}", polymerize_and_get_the_diff(input, 10));
+fn part1(template: &str, rules: &Rules) {
+    println!("Day 14A: {}", polymerize_and_get_the_diff(template, rules, 10));
 }
 
-fn part2(input: &str) {
-    println!("Day 14B: {}", polymerize_and_get_the_diff(input, 40));
+fn part2(template: &str, rules: &Rules) {
+    println!("Day 14B: {}", polymerize_and_get_the_diff(template, rules, 40));
 }
 
 mod tests {
@@ -128,7 +135,7 @@ CN -> C
 
     #[test]
     fn parse_the_example() {        
-        let (template, rules) = parse(EXAMPLE_INPUT);
+        let (template, rules) = parse(EXAMPLE_INPUT).unwrap();
 
         assert_eq!("NNCB", template);
         assert_eq!(16, rules.len());
@@ -136,7 +143,7 @@ CN -> C
 
     #[test]
     fn polymerize_the_example() {
-        let (template, rules) = parse(EXAMPLE_INPUT);
+        let (template, rules) = parse(EXAMPLE_INPUT).unwrap();
         assert_eq!(count_chars("NCNBCHB"), polymerize_and_count_chars(template, &rules, 1));
         assert_eq!(count_chars("NBCCNBBBCBHCB"), polymerize_and_count_chars(template, &rules, 2));
         assert_eq!(count_chars("NBBBCNCCNBBNBNBBCHBHHBCHB"), polymerize_and_count_chars(template, &rules, 3));
@@ -145,6 +152,16 @@ CN -> C
 
     #[test]
     fn calculate_diff_of_example() {
-        assert_eq!(1588, polymerize_and_get_the_diff(EXAMPLE_INPUT, 10));
+        let (template, rules) = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(1588, polymerize_and_get_the_diff(template, &rules, 10));
+    }
+
+    #[test]
+    fn report_bad_rules() {
+        let err = parse("NNCB\n\nCH -> B\nCHH -> N").unwrap_err();
+        assert_eq!((4, 1, "a pair of elements".to_string()), (err.line, err.column, err.expected));
+
+        let err = parse("NNCB\n\nCH => B").unwrap_err();
+        assert_eq!((3, 4, "' -> '".to_string()), (err.line, err.column, err.expected));
     }
 }
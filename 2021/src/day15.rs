@@ -1,3 +1,4 @@
+use crate::parse::{numbered_lines, ParseError};
 use std::collections::BinaryHeap;
 use std::collections::HashSet;
 use std::hash::Hash;
@@ -6,7 +7,13 @@ use std::hash::Hasher;
 pub fn run() {
     let input = std::fs::read_to_string("src/day15_input.txt").unwrap();
 
-    let first = parse::<100>(&input);
+    let first = match parse::<100>(&input) {
+        Ok(first) => first,
+        Err(err) => {
+            println!("Day 15: {}", err);
+            return;
+        }
+    };
     println!("Day 15A: {}", get_risk_of_safest_path(first));
 
     // this may overflow the stack (especially on debug builds).
@@ -57,29 +64,44 @@ struct Grid<const N: usize> {
     data: [[Node; N]; N],
 }
 
-fn parse<const N: usize>(input: &str) -> Grid<N> {
+fn parse<const N: usize>(input: &str) -> Result<Grid<N>, ParseError> {
     let mut out = [[Node::EMPTY; N]; N];
     let mut y = 0;
+    let mut last = (1, "");
 
-    for line in input.trim().lines() {
-        let chars: Vec<char> = line.trim().chars().collect();
-        assert_eq!(N, chars.len());
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        if y == N {
+            let expected = format!("{} rows", N);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+        if let Some(bad) = text.find(|chr: char| !chr.is_ascii_digit()) {
+            return Err(ParseError::at_token(n, line, &text[bad..], "a digit"));
+        }
+        if text.len() != N {
+            let expected = format!("{} digits", N);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
 
-        for x in 0..N {
+        for (x, chr) in text.bytes().enumerate() {
             out[y][x] = Node {
                 least_risky_path: NOT_VISITED_RISK,
-                risk: chars[x] as i16 - 48,
+                risk: (chr - b'0') as i16,
                 x: x as i16,
                 y: y as i16,
             }
         }
 
         y += 1;
+        last = (n, line);
     }
 
-    assert_eq!(N, y);
+    if y != N {
+        let expected = format!("{} rows", N);
+        return Err(ParseError::at_end(last.0, last.1, &expected));
+    }
 
-    return Grid { data: out };
+    return Ok(Grid { data: out });
 }
 
 // https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm#Algorithm
@@ -263,7 +285,7 @@ mod tests {
 
     #[test]
     fn find_the_example_path() {
-        let grid = parse::<10>(TEST_INPUT);
+        let grid = parse::<10>(TEST_INPUT).unwrap();
         let safest = get_risk_of_safest_path(grid);
 
         assert_eq!(40, safest);
@@ -271,9 +293,9 @@ mod tests {
 
     #[test]
     fn grow_times_5() {
-        let grid = parse::<10>(TEST_INPUT);
+        let grid = parse::<10>(TEST_INPUT).unwrap();
         let second = grow::<10, 50>(&grid);
-        let expected = parse::<50>(TEST_INPUT_TIMES_FIVE);
+        let expected = parse::<50>(TEST_INPUT_TIMES_FIVE).unwrap();
 
         for y in 0..50 {
             for x in 0..50 {
@@ -284,11 +306,11 @@ mod tests {
 
     #[test]
     fn find_path_of_enlarged_example() {
-        let grid = parse::<50>(TEST_INPUT_TIMES_FIVE);
+        let grid = parse::<50>(TEST_INPUT_TIMES_FIVE).unwrap();
 
         assert_eq!(315, get_risk_of_safest_path(grid));
 
-        let grid = parse::<10>(TEST_INPUT);
+        let grid = parse::<10>(TEST_INPUT).unwrap();
         let second = grow::<10, 50>(&grid);
 
         assert_eq!(315, get_risk_of_safest_path(second));
@@ -1,9 +1,15 @@
-use core::{num, panic};
+use crate::parse::{numbered_lines, ParseError};
 use std::collections::HashMap;
 
 pub fn run() {
     let input = std::fs::read_to_string("src/day16_input.txt").unwrap();
-    let packets = parse(&input);
+    let packets = match parse(&input) {
+        Ok(packets) => packets,
+        Err(err) => {
+            println!("Day 16: {}", err);
+            return;
+        }
+    };
 
     assert_eq!(1, packets.len());
 
@@ -80,21 +86,46 @@ impl Expression {
     }
 }
 
-fn read_header(input: &str, index: usize) -> (u8, u8, usize) {
-    let version_str = &input[index..(index + 3)];
+// the transmission as a string of "0" and "1", along with where it came
+// from, so that a packet that runs past the end can be pointed at.
+struct Bits<'a> {
+    n: usize,
+    line: &'a str,
+    hex: &'a str,
+    text: String,
+}
+
+impl<'a> Bits<'a> {
+    // every hex digit is four bits, so a bit is blamed on its digit.
+    fn take(&self, index: usize, len: usize) -> Result<&str, ParseError> {
+        if index + len > self.text.len() {
+            let digit = std::cmp::min(index / 4, self.hex.len());
+            return Err(ParseError::at_token(
+                self.n,
+                self.line,
+                &self.hex[digit..],
+                "the rest of the packet",
+            ));
+        }
+        return Ok(&self.text[index..(index + len)]);
+    }
+}
+
+fn read_header(input: &Bits, index: usize) -> Result<(u8, u8, usize), ParseError> {
+    let version_str = input.take(index, 3)?;
     let version = u8::from_str_radix(version_str, 2).unwrap();
-    let type_id_str = &input[(index + 3)..(index + 6)];
+    let type_id_str = input.take(index + 3, 3)?;
     let type_id = u8::from_str_radix(type_id_str, 2).unwrap();
 
-    return (version, type_id, index + 6);
+    return Ok((version, type_id, index + 6));
 }
 
-fn read_decimal(input: &str, index: usize) -> (Expression, usize) {
+fn read_decimal(input: &Bits, index: usize) -> Result<(Expression, usize), ParseError> {
     let mut i = index;
     let mut num = String::new();
 
     loop {
-        let bits = &input[i..(i + 5)];
+        let bits = input.take(i, 5)?;
 
         let is_last_group = &bits[0..1] == "0";
         num.push_str(&bits[1..]);
@@ -106,59 +137,62 @@ fn read_decimal(input: &str, index: usize) -> (Expression, usize) {
         }
     }
 
+    if num.len() > 64 {
+        let digit = std::cmp::min(index / 4, input.hex.len());
+        return Err(ParseError::at_token(
+            input.n,
+            input.line,
+            &input.hex[digit..],
+            "a literal that fits in 64 bits",
+        ));
+    }
     let parsed = u64::from_str_radix(&num, 2).unwrap();
 
-    return (Expression::Literal(parsed), i);
+    return Ok((Expression::Literal(parsed), i));
 }
 
-fn read_operator_packets(input: &str, index: usize) -> (Vec<Packet>, usize) {
-    let length_type = &input[index..(index + 1)];
+fn read_operator_packets(input: &Bits, index: usize) -> Result<(Vec<Packet>, usize), ParseError> {
+    let length_type = input.take(index, 1)?;
     let mut packets = Vec::new();
     let next: usize = match length_type {
         "0" => {
-            let num_bits_str = &input[(index + 1)..(index + 16)];
+            let num_bits_str = input.take(index + 1, 15)?;
             let num_bits = usize::from_str_radix(num_bits_str, 2).unwrap();
             let mut next = index + 16;
             let index_when_done = next + num_bits;
 
             while next < index_when_done {
-                let (packet, n) = read_packet(&input, next).unwrap();
+                let (packet, n) = read_packet(input, next)?;
                 packets.push(packet);
                 next = n;
             }
 
             index + 16 + num_bits
         }
-        "1" => {
-            let num_packets_str = &input[(index + 1)..(index + 12)];
+        _ => {
+            let num_packets_str = input.take(index + 1, 11)?;
             let num_packets = usize::from_str_radix(num_packets_str, 2).unwrap();
             let mut next = index + 12;
 
             for _ in 0..num_packets {
-                let (packet, n) = read_packet(input, next).unwrap();
+                let (packet, n) = read_packet(input, next)?;
                 next = n;
                 packets.push(packet);
             }
 
             next
         }
-        _ => panic!("Unknown length type {}.", length_type),
     };
 
-    return (packets, next);
+    return Ok((packets, next));
 }
 
-fn read_packet(input: &str, index: usize) -> Option<(Packet, usize)> {
-    // detect hex padding.
-    if (input.len() - index) < 8 {
-        return None;
-    }
-
-    let (version, type_id, next) = read_header(input, index);
+fn read_packet(input: &Bits, index: usize) -> Result<(Packet, usize), ParseError> {
+    let (version, type_id, next) = read_header(input, index)?;
     let (expr, next) = match type_id {
-        4 => read_decimal(input, next),
+        4 => read_decimal(input, next)?,
         _ => {
-            let (children, next) = read_operator_packets(input, next);
+            let (children, next) = read_operator_packets(input, next)?;
             let expr = match type_id {
                 0 => Expression::Sum(children),
                 1 => Expression::Product(children),
@@ -166,8 +200,8 @@ fn read_packet(input: &str, index: usize) -> Option<(Packet, usize)> {
                 3 => Expression::Maximum(children),
                 5 => Expression::GreaterThan(children),
                 6 => Expression::LessThan(children),
-                7 => Expression::Equal(children),
-                _ => panic!("Unexpected type_id {}.", type_id),
+                // the header only has three bits, so this is 7.
+                _ => Expression::Equal(children),
             };
             (expr, next)
         }
@@ -179,35 +213,44 @@ fn read_packet(input: &str, index: usize) -> Option<(Packet, usize)> {
         expression: expr,
     };
 
-    return Some((packet, next));
+    return Ok((packet, next));
 }
 
-fn to_binary_string(input: &str) -> String {
-    return input
-        .trim()
-        .chars()
-        .map(|ch| {
-            let num = u8::from_str_radix(&ch.to_string(), 16).unwrap();
-            return format!("{:04b}", num);
-        })
-        .collect::<String>();
+fn to_binary_string(input: &str) -> Result<Bits, ParseError> {
+    let (n, line) = match numbered_lines(input).next() {
+        Some(first) => first,
+        None => return Err(ParseError::at_end(1, "", "a hexadecimal transmission")),
+    };
+    let hex = line.trim();
+    let mut text = String::new();
+
+    for (i, ch) in hex.char_indices() {
+        let num = match ch.to_digit(16) {
+            Some(num) => num,
+            None => {
+                let token = &hex[i..(i + ch.len_utf8())];
+                return Err(ParseError::at_token(n, line, token, "a hexadecimal digit"));
+            }
+        };
+        text.push_str(&format!("{:04b}", num));
+    }
+
+    return Ok(Bits { n, line, hex, text });
 }
 
-fn parse(input: &str) -> Vec<Packet> {
-    let bin_str = to_binary_string(input);
+fn parse(input: &str) -> Result<Vec<Packet>, ParseError> {
+    let bits = to_binary_string(input)?;
     let mut packets = Vec::<Packet>::new();
     let mut i = 0;
 
-    loop {
-        if let Some((packet, next)) = read_packet(&bin_str, i) {
-            packets.push(packet);
-            i = next;
-        } else {
-            break;
-        }
+    // whatever is left after the last packet is hex padding.
+    while bits.text.len() - i >= 8 {
+        let (packet, next) = read_packet(&bits, i)?;
+        packets.push(packet);
+        i = next;
     }
 
-    return packets;
+    return Ok(packets);
 }
 
 fn sum_versions(packets: &[Packet]) -> u64 {
@@ -232,17 +275,20 @@ mod tests {
 
     #[test]
     fn converts_to_binary() {
-        assert_eq!("110100101111111000101000", to_binary_string("D2FE28"));
+        assert_eq!(
+            "110100101111111000101000",
+            to_binary_string("D2FE28").unwrap().text
+        );
         assert_eq!(
             "00111000000000000110111101000101001010010001001000000000",
-            to_binary_string("38006F45291200")
+            to_binary_string("38006F45291200").unwrap().text
         );
     }
 
     #[test]
     fn reads_header_correctly() {
-        let str = to_binary_string("D2FE28");
-        let (version, type_id, next) = read_header(&str, 0);
+        let bits = to_binary_string("D2FE28").unwrap();
+        let (version, type_id, next) = read_header(&bits, 0).unwrap();
 
         assert_eq!(6, version);
         assert_eq!(4, type_id);
@@ -251,8 +297,8 @@ mod tests {
 
     #[test]
     fn reads_decimals_correctly() {
-        let str = to_binary_string("D2FE28");
-        let (data, next) = read_decimal(&str, 6);
+        let bits = to_binary_string("D2FE28").unwrap();
+        let (data, next) = read_decimal(&bits, 6).unwrap();
 
         assert_eq!(Expression::Literal(2021), data);
         assert_eq!(21, next);
@@ -260,7 +306,7 @@ mod tests {
 
     #[test]
     fn reads_operator_correctly() {
-        let packets = parse("38006F45291200");
+        let packets = parse("38006F45291200").unwrap();
 
         assert_eq!(1, packets.len());
         assert_eq!(
@@ -283,4 +329,20 @@ mod tests {
             packets[0]
         );
     }
+
+    #[test]
+    fn reports_broken_transmissions() {
+        let err = parse("D2FG28").unwrap_err();
+        assert_eq!(
+            (4, "a hexadecimal digit".to_string()),
+            (err.column, err.expected)
+        );
+
+        // the literal 2021 is cut off before its third group of bits.
+        let err = parse("D2FE").unwrap_err();
+        assert_eq!(
+            (5, "the rest of the packet".to_string()),
+            (err.column, err.expected)
+        );
+    }
 }
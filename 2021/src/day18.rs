@@ -0,0 +1,309 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
+use std::fmt;
+
+pub fn run() {
+    let path = "src/day18_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 18: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let numbers = match parse_numbers(&input) {
+        Ok(numbers) => numbers,
+        Err(err) => {
+            println!("Day 18: {}", err);
+            return;
+        }
+    };
+
+    part1(&numbers);
+    part2(&numbers);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Snailfish {
+    Regular(u32),
+    Pair(Box<Snailfish>, Box<Snailfish>),
+}
+
+impl fmt::Display for Snailfish {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Snailfish::Regular(n) => write!(f, "{}", n),
+            Snailfish::Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+fn parse_numbers(input: &str) -> Result<Vec<Snailfish>, ParseError> {
+    return numbered_lines(input)
+        .map(|(n, line)| parse(n, line))
+        .collect();
+}
+
+fn parse(n: usize, line: &str) -> Result<Snailfish, ParseError> {
+    let mut reader = Reader {
+        n,
+        line,
+        text: line.trim(),
+        pos: 0,
+    };
+    let out = reader.read_number()?;
+    if reader.pos != reader.text.len() {
+        return Err(reader.error("the end of the line"));
+    }
+    return Ok(out);
+}
+
+// reads one line, keeping the whole of it around for the errors.
+struct Reader<'a> {
+    n: usize,
+    line: &'a str,
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, expected: &str) -> ParseError {
+        return ParseError::at_token(self.n, self.line, &self.text[self.pos..], expected);
+    }
+
+    fn expect(&mut self, byte: u8, expected: &str) -> Result<(), ParseError> {
+        if self.text.as_bytes().get(self.pos) != Some(&byte) {
+            return Err(self.error(expected));
+        }
+        self.pos += 1;
+        return Ok(());
+    }
+
+    fn read_number(&mut self) -> Result<Snailfish, ParseError> {
+        if self.text.as_bytes().get(self.pos) == Some(&b'[') {
+            self.pos += 1;
+            let left = self.read_number()?;
+            self.expect(b',', "','")?;
+            let right = self.read_number()?;
+            self.expect(b']', "']'")?;
+            return Ok(Snailfish::Pair(Box::new(left), Box::new(right)));
+        }
+
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error("'[' or a regular number"));
+        }
+        let digits = &self.text[start..self.pos];
+        let value = parse_token(self.n, self.line, digits, "a regular number")?;
+        return Ok(Snailfish::Regular(value));
+    }
+}
+
+impl Snailfish {
+    fn magnitude(&self) -> u64 {
+        return match self {
+            Snailfish::Regular(n) => *n as u64,
+            Snailfish::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        };
+    }
+
+    fn add_to_leftmost(&mut self, value: u32) {
+        match self {
+            Snailfish::Regular(n) => *n += value,
+            Snailfish::Pair(left, _) => left.add_to_leftmost(value),
+        }
+    }
+
+    fn add_to_rightmost(&mut self, value: u32) {
+        match self {
+            Snailfish::Regular(n) => *n += value,
+            Snailfish::Pair(_, right) => right.add_to_rightmost(value),
+        }
+    }
+
+    // explodes the leftmost pair that is nested inside four others. the
+    // result is what still has to be added to the left and to the right,
+    // once the recursion gets back to a number on that side.
+    fn explode(&mut self, depth: usize) -> Option<(u32, u32)> {
+        let (left, right) = match self {
+            Snailfish::Regular(_) => return None,
+            Snailfish::Pair(left, right) => (left, right),
+        };
+
+        if depth >= 4 {
+            if let (Snailfish::Regular(a), Snailfish::Regular(b)) = (&**left, &**right) {
+                let out = (*a, *b);
+                *self = Snailfish::Regular(0);
+                return Some(out);
+            }
+        }
+
+        if let Some((a, b)) = left.explode(depth + 1) {
+            right.add_to_leftmost(b);
+            return Some((a, 0));
+        }
+        if let Some((a, b)) = right.explode(depth + 1) {
+            left.add_to_rightmost(a);
+            return Some((0, b));
+        }
+        return None;
+    }
+
+    // splits the leftmost number of ten or more.
+    fn split(&mut self) -> bool {
+        match self {
+            Snailfish::Regular(n) if *n >= 10 => {
+                let left = Snailfish::Regular(*n / 2);
+                let right = Snailfish::Regular((*n + 1) / 2);
+                *self = Snailfish::Pair(Box::new(left), Box::new(right));
+                return true;
+            }
+            Snailfish::Regular(_) => return false,
+            Snailfish::Pair(left, right) => return left.split() || right.split(),
+        }
+    }
+
+    fn reduce(&mut self) {
+        loop {
+            if self.explode(0).is_some() {
+                continue;
+            }
+            if !self.split() {
+                break;
+            }
+        }
+    }
+}
+
+fn add(a: &Snailfish, b: &Snailfish) -> Snailfish {
+    let mut out = Snailfish::Pair(Box::new(a.clone()), Box::new(b.clone()));
+    out.reduce();
+    return out;
+}
+
+fn sum(numbers: &[Snailfish]) -> Snailfish {
+    return numbers[1..]
+        .iter()
+        .fold(numbers[0].clone(), |carry, n| add(&carry, n));
+}
+
+fn part1(numbers: &[Snailfish]) {
+    println!("Day 18A: {}", sum(numbers).magnitude());
+}
+
+fn part2(numbers: &[Snailfish]) {
+    let mut best = 0;
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate() {
+            if i != j {
+                best = std::cmp::max(best, add(a, b).magnitude());
+            }
+        }
+    }
+    println!("Day 18B: {}", best);
+}
+
+mod tests {
+    use crate::day18::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+"#;
+
+    fn explode_once(line: &str) -> String {
+        let mut n = parse(1, line).unwrap();
+        assert!(n.explode(0).is_some());
+        return n.to_string();
+    }
+
+    #[test]
+    fn explode_the_examples() {
+        assert_eq!("[[[[0,9],2],3],4]", explode_once("[[[[[9,8],1],2],3],4]"));
+        assert_eq!("[7,[6,[5,[7,0]]]]", explode_once("[7,[6,[5,[4,[3,2]]]]]"));
+        assert_eq!("[[6,[5,[7,0]]],3]", explode_once("[[6,[5,[4,[3,2]]]],1]"));
+        assert_eq!(
+            "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            explode_once("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]")
+        );
+        assert_eq!(
+            "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            explode_once("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]")
+        );
+    }
+
+    #[test]
+    fn add_and_reduce() {
+        let total = add(
+            &parse(1, "[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap(),
+            &parse(1, "[1,1]").unwrap(),
+        );
+        assert_eq!("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", total.to_string());
+
+        let numbers: Vec<Snailfish> = ["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"]
+            .iter()
+            .map(|l| parse(1, l).unwrap())
+            .collect();
+        assert_eq!("[[[[5,0],[7,4]],[5,5]],[6,6]]", sum(&numbers).to_string());
+    }
+
+    #[test]
+    fn calculate_magnitudes() {
+        assert_eq!(143, parse(1, "[[1,2],[[3,4],5]]").unwrap().magnitude());
+        assert_eq!(
+            3488,
+            parse(1, "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]")
+                .unwrap()
+                .magnitude()
+        );
+    }
+
+    #[test]
+    fn do_the_homework() {
+        let numbers = parse_numbers(EXAMPLE_INPUT).unwrap();
+        let total = sum(&numbers);
+        assert_eq!(
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]",
+            total.to_string()
+        );
+        assert_eq!(4140, total.magnitude());
+
+        let best = numbers
+            .iter()
+            .flat_map(|a| numbers.iter().map(move |b| (a, b)))
+            .filter(|(a, b)| a != b)
+            .map(|(a, b)| add(a, b).magnitude())
+            .max();
+        assert_eq!(Some(3993), best);
+    }
+
+    #[test]
+    fn report_bad_numbers() {
+        let check = |input: &str, line: usize, column: usize, expected: &str| {
+            let err = parse_numbers(input).unwrap_err();
+            assert_eq!(
+                (line, column, expected.to_string()),
+                (err.line, err.column, err.expected),
+                "{}",
+                input
+            );
+        };
+
+        check("[1,2]\n  [[1,2],3", 2, 11, "']'");
+        check("[1,2]\n[[1,2]]", 2, 7, "','");
+        check("[1,[2,x]]", 1, 7, "'[' or a regular number");
+        check("[1,2]]", 1, 6, "the end of the line");
+        check("[1,99999999999]", 1, 4, "a regular number");
+    }
+}
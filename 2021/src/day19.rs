@@ -0,0 +1,475 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+use std::collections::{HashMap, HashSet};
+
+pub fn run() {
+    let path = "src/day19_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 19: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let scanners = match parse(&input) {
+        Ok(scanners) => scanners,
+        Err(err) => {
+            println!("Day 19: {}", err);
+            return;
+        }
+    };
+
+    match align(&scanners, OVERLAP) {
+        Some(map) => {
+            println!("Day 19A: {}", map.beacons.len());
+            println!("Day 19B: {}", largest_distance(&map.scanners));
+        }
+        None => println!("Day 19: some scanners do not overlap with the others"),
+    }
+}
+
+type Point = [i64; 3];
+
+// two scanners that see at least this many of the same beacons are
+// taken to overlap.
+const OVERLAP: usize = 12;
+
+// the beacons each scanner sees, in the order the scanners are listed.
+fn parse(input: &str) -> Result<Vec<Vec<Point>>, ParseError> {
+    let mut out: Vec<Vec<Point>> = Vec::new();
+
+    for (n, line) in numbered_lines(input) {
+        if line.trim().starts_with("---") {
+            scan!((n, line), "--- scanner {} ---", usize)?;
+            out.push(Vec::new());
+            continue;
+        }
+
+        let (x, y, z) = scan!((n, line), "{},{},{}", i64, i64, i64)?;
+        match out.last_mut() {
+            Some(beacons) => beacons.push([x, y, z]),
+            None => {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    line.trim(),
+                    "'--- scanner 0 ---'",
+                ))
+            }
+        }
+    }
+
+    return Ok(out);
+}
+
+type Rotation = [[i64; 3]; 3];
+
+// the 24 ways a scanner can face: every matrix that picks each axis once,
+// with either sign, and does not mirror.
+fn rotations() -> Vec<Rotation> {
+    let mut out = Vec::new();
+    let orders = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+
+    for order in orders {
+        for signs in 0..8 {
+            let mut m = [[0; 3]; 3];
+            for row in 0..3 {
+                m[row][order[row]] = if signs & (1 << row) == 0 { 1 } else { -1 };
+            }
+            if determinant(&m) == 1 {
+                out.push(m);
+            }
+        }
+    }
+
+    return out;
+}
+
+fn determinant(m: &Rotation) -> i64 {
+    return m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+}
+
+fn rotate(m: &Rotation, p: Point) -> Point {
+    return [0, 1, 2].map(|row| (0..3).map(|col| m[row][col] * p[col]).sum());
+}
+
+#[derive(Debug)]
+struct Map {
+    // every beacon, as seen from the first scanner.
+    beacons: HashSet<Point>,
+    // where each scanner is, in the same order as the input.
+    scanners: Vec<Point>,
+}
+
+// tries to place "beacons" next to beacons that are already placed. every
+// pair of beacons votes for the offset that would put one on the other.
+fn find_offset(placed: &[Point], beacons: &[Point], overlap: usize) -> Option<Point> {
+    let mut votes: HashMap<Point, usize> = HashMap::new();
+    for a in placed {
+        for b in beacons {
+            let offset = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+            let count = votes.entry(offset).or_insert(0);
+            *count += 1;
+            if *count >= overlap {
+                return Some(offset);
+            }
+        }
+    }
+    return None;
+}
+
+// places every scanner relative to the first one. each newly placed
+// scanner is only compared against the ones that were not placed yet, so
+// no pair is tried twice. None when some scanner does not fit anywhere.
+fn align(scanners: &[Vec<Point>], overlap: usize) -> Option<Map> {
+    let rotations = rotations();
+    let mut placed: Vec<Option<(Point, Vec<Point>)>> = vec![None; scanners.len()];
+    placed[0] = Some(([0, 0, 0], scanners[0].clone()));
+    let mut queue = vec![0];
+
+    while let Some(known) = queue.pop() {
+        let (_, known_beacons) = placed[known].clone().unwrap();
+
+        for i in 0..scanners.len() {
+            if placed[i].is_some() {
+                continue;
+            }
+            for m in &rotations {
+                let rotated: Vec<Point> = scanners[i].iter().map(|p| rotate(m, *p)).collect();
+                if let Some(offset) = find_offset(&known_beacons, &rotated, overlap) {
+                    let moved = rotated
+                        .iter()
+                        .map(|p| [p[0] + offset[0], p[1] + offset[1], p[2] + offset[2]])
+                        .collect();
+                    placed[i] = Some((offset, moved));
+                    queue.push(i);
+                    break;
+                }
+            }
+        }
+    }
+
+    let placed: Vec<(Point, Vec<Point>)> = placed.into_iter().collect::<Option<_>>()?;
+    return Some(Map {
+        beacons: placed.iter().flat_map(|(_, b)| b.iter().copied()).collect(),
+        scanners: placed.iter().map(|(s, _)| *s).collect(),
+    });
+}
+
+fn largest_distance(points: &[Point]) -> i64 {
+    let mut best = 0;
+    for a in points {
+        for b in points {
+            let d = (0..3).map(|i| (a[i] - b[i]).abs()).sum();
+            best = std::cmp::max(best, d);
+        }
+    }
+    return best;
+}
+
+mod tests {
+    use crate::day19::*;
+    use crate::lcg::Lcg;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14
+"#;
+
+    // one scanner, turned five different ways.
+    const ORIENTATIONS: &'static str = r#"
+--- scanner 0 ---
+-1,-1,1
+-2,-2,2
+-3,-3,3
+-2,-3,1
+5,6,-4
+8,0,7
+
+--- scanner 0 ---
+1,-1,1
+2,-2,2
+3,-3,3
+2,-1,3
+-5,4,-6
+-8,-7,0
+
+--- scanner 0 ---
+-1,-1,-1
+-2,-2,-2
+-3,-3,-3
+-1,-3,-2
+4,6,5
+-7,0,8
+
+--- scanner 0 ---
+1,1,-1
+2,2,-2
+3,3,-3
+1,3,-2
+-4,-6,5
+7,0,8
+
+--- scanner 0 ---
+1,1,1
+2,2,2
+3,3,3
+3,1,2
+-6,-4,-5
+0,7,-8
+"#;
+
+    #[test]
+    fn map_the_example() {
+        let scanners = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(5, scanners.len());
+
+        let map = align(&scanners, OVERLAP).unwrap();
+        assert_eq!(
+            vec![
+                [0, 0, 0],
+                [68, -1246, -43],
+                [1105, -1205, 1229],
+                [-92, -2380, -20],
+                [-20, -1133, 1061]
+            ],
+            map.scanners
+        );
+        assert_eq!(79, map.beacons.len());
+        assert!(map.beacons.contains(&[-892, 524, 684]));
+        assert!(map.beacons.contains(&[1889, -1729, 1762]));
+        assert_eq!(3621, largest_distance(&map.scanners));
+    }
+
+    #[test]
+    fn report_bad_scanners() {
+        let err = parse("1,2,3\n--- scanner 0 ---").unwrap_err();
+        assert_eq!(
+            (1, 1, "'--- scanner 0 ---'".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse("--- scanner 0 ---\n1,2\n").unwrap_err();
+        assert_eq!(
+            (2, 4, "','".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
+
+    #[test]
+    fn turn_every_way() {
+        let all = rotations();
+        assert_eq!(24, all.len());
+
+        let unique: HashSet<Point> = all.iter().map(|m| rotate(m, [1, 2, 3])).collect();
+        assert_eq!(24, unique.len());
+
+        let views = parse(ORIENTATIONS).unwrap();
+        assert_eq!(5, views.len());
+        for view in &views {
+            let found = all.iter().any(|m| {
+                return views[0].iter().map(|p| rotate(m, *p)).collect::<Vec<_>>() == *view;
+            });
+            assert!(found, "{:?}", view);
+        }
+    }
+
+    #[test]
+    fn map_the_trench() {
+        let mut lcg = Lcg::new(19);
+        let beacons: Vec<Point> = (0..2000)
+            .map(|_| [0, 1, 2].map(|_| lcg.below(5001) as i64 - 2500))
+            .collect();
+        let positions: Vec<Point> = vec![
+            [0, 0, 0],
+            [1100, 0, 0],
+            [1100, -1000, 0],
+            [0, 0, 1200],
+            [-1050, 80, 1200],
+        ];
+
+        // what each scanner sees within 1000 on every axis, turned some way.
+        let all = rotations();
+        let scanners: Vec<Vec<Point>> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let m = all[(i * 7) % 24];
+                let inverse = all
+                    .iter()
+                    .find(|n| rotate(n, rotate(&m, [1, 2, 3])) == [1, 2, 3])
+                    .unwrap();
+                return beacons
+                    .iter()
+                    .filter(|b| (0..3).all(|k| (b[k] - s[k]).abs() <= 1000))
+                    .map(|b| rotate(inverse, [b[0] - s[0], b[1] - s[1], b[2] - s[2]]))
+                    .collect();
+            })
+            .collect();
+        let seen: HashSet<Point> = beacons
+            .iter()
+            .filter(|b| {
+                return positions
+                    .iter()
+                    .any(|s| (0..3).all(|k| (b[k] - s[k]).abs() <= 1000));
+            })
+            .copied()
+            .collect();
+
+        let map = align(&scanners, OVERLAP).unwrap();
+        assert_eq!(positions, map.scanners);
+        assert_eq!(seen, map.beacons);
+        assert_eq!(
+            1050 + 1100 + 1000 + 80 + 1200,
+            largest_distance(&map.scanners)
+        );
+
+        // a scanner far away from the rest cannot be placed.
+        let mut lost = scanners.clone();
+        lost.push(vec![[5000, 5000, 5000]; 3]);
+        assert!(align(&lost, OVERLAP).is_none());
+    }
+}
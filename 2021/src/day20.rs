@@ -0,0 +1,208 @@
+use crate::parse::{numbered_lines, ParseError};
+
+pub fn run() {
+    let path = "src/day20_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 20: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let (algorithm, picture) = match parse(&input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            println!("Day 20: {}", err);
+            return;
+        }
+    };
+
+    for (name, steps) in [("A", 2), ("B", 50)] {
+        match enhance(&picture, &algorithm, steps).lit() {
+            Some(lit) => println!("Day 20{}: {}", name, lit),
+            None => println!("Day 20{}: infinitely many", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Picture {
+    pixels: Vec<Vec<bool>>,
+    // every pixel outside of "pixels" on the infinite picture.
+    background: bool,
+}
+
+impl Picture {
+    fn get(&self, x: i64, y: i64) -> bool {
+        if x < 0 || y < 0 {
+            return self.background;
+        }
+        return self
+            .pixels
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .unwrap_or(self.background);
+    }
+
+    // None when the background is lit, since that is infinitely many.
+    fn lit(&self) -> Option<usize> {
+        if self.background {
+            return None;
+        }
+        return Some(self.pixels.iter().flatten().filter(|p| **p).count());
+    }
+}
+
+const ALGORITHM_SIZE: usize = 512;
+
+// one line for the algorithm, then the rows of the picture, which all have
+// to be as wide as the first one.
+fn parse(input: &str) -> Result<(Vec<bool>, Picture), ParseError> {
+    let mut algorithm: Vec<bool> = Vec::new();
+    let mut pixels: Vec<Vec<bool>> = Vec::new();
+    let mut last = (1, "");
+
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        if let Some(bad) = text.find(|ch| ch != '#' && ch != '.') {
+            return Err(ParseError::at_token(n, line, &text[bad..], "'#' or '.'"));
+        }
+        let row: Vec<bool> = text.chars().map(|ch| ch == '#').collect();
+
+        if algorithm.is_empty() {
+            if row.len() != ALGORITHM_SIZE {
+                let expected = format!("{} pixels in the algorithm", ALGORITHM_SIZE);
+                return Err(ParseError::at_token(n, line, text, &expected));
+            }
+            algorithm = row;
+        } else {
+            if !pixels.is_empty() && row.len() != pixels[0].len() {
+                let expected = format!("{} pixels in the row", pixels[0].len());
+                return Err(ParseError::at_token(n, line, text, &expected));
+            }
+            pixels.push(row);
+        }
+        last = (n, line);
+    }
+
+    if pixels.is_empty() {
+        return Err(ParseError::at_end(last.0, last.1, "a picture"));
+    }
+
+    let picture = Picture {
+        pixels,
+        background: false,
+    };
+    return Ok((algorithm, picture));
+}
+
+// every step the picture grows by one pixel on each side. the rest of it
+// is all background, which changes all at once: it reads 0 or 511.
+fn enhance_once(picture: &Picture, algorithm: &[bool]) -> Picture {
+    let height = picture.pixels.len() as i64;
+    let width = picture.pixels.first().map_or(0, |row| row.len()) as i64;
+
+    let pixels = (-1..=height)
+        .map(|y| {
+            return (-1..=width)
+                .map(|x| {
+                    let mut index = 0;
+                    for dy in -1..=1 {
+                        for dx in -1..=1 {
+                            index = index * 2 + picture.get(x + dx, y + dy) as usize;
+                        }
+                    }
+                    return algorithm[index];
+                })
+                .collect();
+        })
+        .collect();
+
+    let background = if picture.background {
+        algorithm[511]
+    } else {
+        algorithm[0]
+    };
+
+    return Picture { pixels, background };
+}
+
+fn enhance(picture: &Picture, algorithm: &[bool], steps: usize) -> Picture {
+    let mut out = picture.clone();
+    for _ in 0..steps {
+        out = enhance_once(&out, algorithm);
+    }
+    return out;
+}
+
+mod tests {
+    use crate::day20::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###
+"#;
+
+    #[test]
+    fn enhance_the_example() {
+        let (algorithm, picture) = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(10, picture.lit().unwrap());
+        assert_eq!(Some(35), enhance(&picture, &algorithm, 2).lit());
+        assert_eq!(Some(3351), enhance(&picture, &algorithm, 50).lit());
+    }
+
+    #[test]
+    fn flash_the_background() {
+        // everything dark turns lit and everything lit turns dark.
+        let algorithm: Vec<bool> = (0..512).map(|i| i & 0b10000 == 0).collect();
+        let picture = Picture {
+            pixels: vec![vec![true, false], vec![false, false]],
+            background: false,
+        };
+
+        let once = enhance(&picture, &algorithm, 1);
+        assert!(once.background);
+        assert_eq!(None, once.lit());
+        assert!(!once.get(1, 1));
+        assert!(once.get(-5, 100));
+
+        let twice = enhance(&picture, &algorithm, 2);
+        assert_eq!(Some(1), twice.lit());
+        assert!(twice.get(2, 2));
+    }
+
+    #[test]
+    fn report_bad_pictures() {
+        let algorithm = EXAMPLE_INPUT.trim().lines().next().unwrap();
+
+        let err = parse(&format!("{}\n\n#..#.\n#..\n", algorithm)).unwrap_err();
+        assert_eq!(
+            (4, 1, "5 pixels in the row".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse(&format!("{}\n\n  #.x#.\n", algorithm)).unwrap_err();
+        assert_eq!(
+            (3, 5, "'#' or '.'".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse("#.#\n\n#..#.").unwrap_err();
+        assert_eq!(
+            (1, 1, "512 pixels in the algorithm".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse(algorithm).unwrap_err();
+        assert_eq!(
+            (1, 513, "a picture".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::memo::Memo;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+
+pub fn run() {
+    let path = "src/day21_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 21: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let (first, second) = match parse(&input) {
+        Ok(positions) => positions,
+        Err(err) => {
+            println!("Day 21: {}", err);
+            return;
+        }
+    };
+
+    println!("Day 21A: {}", play_deterministic(first, second));
+
+    let (a, b) = count_wins(first, second);
+    println!("Day 21B: {}", std::cmp::max(a, b));
+}
+
+// the players have to be listed in order, each on a space from 1 to 10.
+fn parse(input: &str) -> Result<(u8, u8), ParseError> {
+    let mut positions: Vec<u8> = Vec::new();
+    let mut last = (1, "");
+
+    for (n, line) in numbered_lines(input) {
+        let (player, position) = scan!((n, line), "Player {} starting position: {}", usize, u8)?;
+        if positions.len() == 2 {
+            return Err(ParseError::at_token(
+                n,
+                line,
+                line.trim(),
+                "only two players",
+            ));
+        }
+        if player != positions.len() + 1 {
+            let token = line.trim().trim_start_matches("Player ");
+            let expected = format!("player {}", positions.len() + 1);
+            return Err(ParseError::at_token(n, line, token, &expected));
+        }
+        if !(1..=10).contains(&position) {
+            let token = line.trim_end().rsplit(' ').next().unwrap();
+            return Err(ParseError::at_token(n, line, token, "a space from 1 to 10"));
+        }
+        positions.push(position);
+        last = (n, line);
+    }
+
+    if positions.len() != 2 {
+        return Err(ParseError::at_end(last.0, last.1, "two players"));
+    }
+    return Ok((positions[0], positions[1]));
+}
+
+fn advance(position: u8, steps: u64) -> u8 {
+    return ((position as u64 - 1 + steps) % 10 + 1) as u8;
+}
+
+// the die rolls 1 to 100 and around again. returns the losing score
+// times the number of rolls.
+fn play_deterministic(first: u8, second: u8) -> u64 {
+    let mut positions = [first, second];
+    let mut scores = [0u64; 2];
+    let mut rolls = 0;
+    let mut turn = 0;
+
+    loop {
+        let steps: u64 = (0..3).map(|i| (rolls + i) % 100 + 1).sum();
+        rolls += 3;
+        positions[turn] = advance(positions[turn], steps);
+        scores[turn] += positions[turn] as u64;
+
+        if scores[turn] >= 1000 {
+            return scores[1 - turn] * rolls;
+        }
+        turn = 1 - turn;
+    }
+}
+
+// the sums of three rolls of a three-sided die, and in how many
+// universes each of them comes up.
+const DIRAC_ROLLS: [(u64, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+const DIRAC_TARGET: u8 = 21;
+
+type State = (u8, u8, u8, u8);
+
+// the universes in which the player about to move wins, and the ones in
+// which the other player does. the key is (position, score) for the
+// player to move and then for the other one.
+fn wins_from(state: State, memo: &mut Memo<State, (u64, u64)>) -> (u64, u64) {
+    return memo.get_or_compute(state, |memo| {
+        let (position, score, other_position, other_score) = state;
+        let mut out = (0, 0);
+
+        for (steps, universes) in DIRAC_ROLLS {
+            let next = advance(position, steps);
+            let next_score = score + next;
+            if next_score >= DIRAC_TARGET {
+                out.0 += universes;
+                continue;
+            }
+
+            // the other player moves next, so the roles swap.
+            let (theirs, mine) = wins_from((other_position, other_score, next, next_score), memo);
+            out.0 += mine * universes;
+            out.1 += theirs * universes;
+        }
+
+        return out;
+    });
+}
+
+fn count_wins(first: u8, second: u8) -> (u64, u64) {
+    let mut memo = Memo::new();
+    return wins_from((first, 0, second, 0), &mut memo);
+}
+
+mod tests {
+    use crate::day21::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+Player 1 starting position: 4
+Player 2 starting position: 8
+"#;
+
+    #[test]
+    fn play_the_example() {
+        let (first, second) = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!((4, 8), (first, second));
+        assert_eq!(739785, play_deterministic(first, second));
+        assert_eq!(
+            (444356092776315, 341960390180808),
+            count_wins(first, second)
+        );
+    }
+
+    #[test]
+    fn move_around_the_board() {
+        assert_eq!(10, advance(4, 6));
+        assert_eq!(1, advance(10, 1));
+        assert_eq!(3, advance(8, 105));
+
+        // every turn splits each universe into 27.
+        assert_eq!(27, DIRAC_ROLLS.iter().map(|(_, u)| u).sum::<u64>());
+    }
+
+    #[test]
+    fn report_bad_players() {
+        let check = |input: &str, line: usize, column: usize, expected: &str| {
+            let err = parse(input).unwrap_err();
+            assert_eq!(
+                (line, column, expected.to_string()),
+                (err.line, err.column, err.expected),
+                "{}",
+                input
+            );
+        };
+
+        check(
+            "Player 1 starting position: 11",
+            1,
+            29,
+            "a space from 1 to 10",
+        );
+        check("Player 2 starting position: 1", 1, 8, "player 1");
+        check("Player 1 starting position: 1", 1, 30, "two players");
+        check(
+            "Player 1 starting position: 1\nPlayer 2 starting position: 1\nPlayer 3 starting position: 1",
+            3,
+            1,
+            "only two players",
+        );
+    }
+}
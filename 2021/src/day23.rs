@@ -0,0 +1,267 @@
+use crate::parse::{numbered_lines, ParseError};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+pub fn run() {
+    let path = "src/day23_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 23: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let rows = match parse(&input) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Day 23: {}", err);
+            return;
+        }
+    };
+
+    match least_energy(Burrow::<2>::from_rows(&rows)) {
+        Some(energy) => println!("Day 23A: {}", energy),
+        None => println!("Day 23A: the amphipods cannot be sorted"),
+    }
+    match least_energy(Burrow::<4>::from_rows(&unfold(&rows))) {
+        Some(energy) => println!("Day 23B: {}", energy),
+        None => println!("Day 23B: the amphipods cannot be sorted"),
+    }
+}
+
+const EMPTY: u8 = 0;
+const HALLWAY: usize = 11;
+// amphipods never stop right outside of a room.
+const STOPS: [usize; 7] = [0, 1, 3, 5, 7, 9, 10];
+const ENERGY: [u64; 4] = [1, 10, 100, 1000];
+
+const ROWS: usize = 2;
+
+// the amphipods in each row of the rooms, from the top, as 1 for A up
+// to 4 for D. the walls and the empty hallway are skipped.
+fn parse(input: &str) -> Result<Vec<[u8; 4]>, ParseError> {
+    let mut out = Vec::new();
+    let mut last = (1, "");
+
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        last = (n, line);
+        if let Some(bad) = text.find(|ch| !"#. ABCD".contains(ch)) {
+            return Err(ParseError::at_token(
+                n,
+                line,
+                &text[bad..],
+                "an amphipod from A to D",
+            ));
+        }
+        let kinds: Vec<u8> = text
+            .chars()
+            .filter(|ch| ('A'..='D').contains(ch))
+            .map(|ch| ch as u8 - b'A' + 1)
+            .collect();
+        if kinds.is_empty() {
+            continue;
+        }
+        if kinds.len() != 4 || out.len() == ROWS {
+            let expected = format!("{} rows of four amphipods", ROWS);
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+        out.push([kinds[0], kinds[1], kinds[2], kinds[3]]);
+    }
+
+    if out.len() != ROWS {
+        let expected = format!("{} rows of four amphipods", ROWS);
+        return Err(ParseError::at_end(last.0, last.1, &expected));
+    }
+    return Ok(out);
+}
+
+// the second part finds two more rows folded up in the diagram.
+fn unfold(rows: &[[u8; 4]]) -> Vec<[u8; 4]> {
+    let mut out = rows.to_vec();
+    out.splice(1..1, [[4, 3, 2, 1], [4, 2, 1, 3]]);
+    return out;
+}
+
+fn entrance(room: usize) -> usize {
+    return 2 + 2 * room;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Burrow<const D: usize> {
+    hallway: [u8; HALLWAY],
+    // rooms[r][0] is the spot right next to the hallway.
+    rooms: [[u8; D]; 4],
+}
+
+impl<const D: usize> Burrow<D> {
+    fn from_rows(rows: &[[u8; 4]]) -> Self {
+        assert_eq!(D, rows.len(), "Expected {} rows of amphipods.", D);
+        let mut rooms = [[EMPTY; D]; 4];
+        for (depth, row) in rows.iter().enumerate() {
+            for room in 0..4 {
+                rooms[room][depth] = row[room];
+            }
+        }
+        return Self {
+            hallway: [EMPTY; HALLWAY],
+            rooms,
+        };
+    }
+
+    fn is_sorted(&self) -> bool {
+        return (0..4).all(|r| self.rooms[r].iter().all(|a| *a as usize == r + 1));
+    }
+
+    // whether nobody is in the hallway between "from" and "to", not
+    // counting "from" itself.
+    fn is_clear(&self, from: usize, to: usize) -> bool {
+        let range = if from < to {
+            (from + 1)..=to
+        } else {
+            to..=(from - 1)
+        };
+        return self.hallway[range].iter().all(|a| *a == EMPTY);
+    }
+
+    // every state one move away, and the energy it takes to get there.
+    fn moves(&self) -> Vec<(Burrow<D>, u64)> {
+        let mut out = Vec::new();
+
+        // into their own room, which only makes sense once it has
+        // nobody else left in it.
+        for h in 0..HALLWAY {
+            let kind = self.hallway[h];
+            if kind == EMPTY {
+                continue;
+            }
+            let room = kind as usize - 1;
+            let slots = &self.rooms[room];
+            if slots.iter().any(|a| *a != EMPTY && *a != kind) || !self.is_clear(h, entrance(room))
+            {
+                continue;
+            }
+            let depth = slots.iter().rposition(|a| *a == EMPTY).unwrap();
+
+            let mut next = *self;
+            next.hallway[h] = EMPTY;
+            next.rooms[room][depth] = kind;
+            let steps = h.abs_diff(entrance(room)) + depth + 1;
+            out.push((next, steps as u64 * ENERGY[room]));
+        }
+
+        // out of a room that still has someone in the wrong place.
+        for room in 0..4 {
+            let depth = match self.rooms[room].iter().position(|a| *a != EMPTY) {
+                Some(depth) => depth,
+                None => continue,
+            };
+            if self.rooms[room][depth..]
+                .iter()
+                .all(|a| *a as usize == room + 1)
+            {
+                continue;
+            }
+            let kind = self.rooms[room][depth];
+
+            for stop in STOPS {
+                if !self.is_clear(entrance(room), stop) {
+                    continue;
+                }
+                let mut next = *self;
+                next.rooms[room][depth] = EMPTY;
+                next.hallway[stop] = kind;
+                let steps = depth + 1 + stop.abs_diff(entrance(room));
+                out.push((next, steps as u64 * ENERGY[kind as usize - 1]));
+            }
+        }
+
+        return out;
+    }
+}
+
+// dijkstra over the states of the burrow.
+fn least_energy<const D: usize>(start: Burrow<D>) -> Option<u64> {
+    let mut queue = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((energy, burrow))) = queue.pop() {
+        if burrow.is_sorted() {
+            return Some(energy);
+        }
+        if !seen.insert(burrow) {
+            continue;
+        }
+        for (next, cost) in burrow.moves() {
+            if !seen.contains(&next) {
+                queue.push(Reverse((energy + cost, next)));
+            }
+        }
+    }
+
+    return None;
+}
+
+mod tests {
+    use crate::day23::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+"#;
+
+    #[test]
+    fn parse_the_example() {
+        let rows = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(vec![[2, 3, 2, 4], [1, 4, 3, 1]], rows);
+
+        let burrow = Burrow::<2>::from_rows(&rows);
+        assert_eq!([[2, 1], [3, 4], [2, 3], [4, 1]], burrow.rooms);
+        assert!(!burrow.is_sorted());
+        assert!(Burrow::<2>::from_rows(&[[1, 2, 3, 4], [1, 2, 3, 4]]).is_sorted());
+
+        let err = parse("###B#C#B#D###\n  #A#E#C#A#").unwrap_err();
+        assert_eq!(
+            (2, 6, "an amphipod from A to D".to_string()),
+            (err.line, err.column, err.expected)
+        );
+        let err = parse("###B#C#B#D###\n  #A#D#C#").unwrap_err();
+        assert_eq!(
+            (2, 3, "2 rows of four amphipods".to_string()),
+            (err.line, err.column, err.expected)
+        );
+        let err = parse("#...........#\n###B#C#B#D###\n  #####").unwrap_err();
+        assert_eq!(
+            (3, 8, "2 rows of four amphipods".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
+
+    #[test]
+    fn take_single_steps() {
+        // C steps aside to the right, so D can go past it into its room.
+        let burrow = Burrow::<1>::from_rows(&[[1, 2, 4, 3]]);
+        assert_eq!(
+            Some(2 * 100 + 2 * 1000 + 2 * 1000 + 4 * 100),
+            least_energy(burrow)
+        );
+
+        // nothing moves out of a room that is already done.
+        let done = Burrow::<1>::from_rows(&[[1, 2, 3, 4]]);
+        assert!(done.moves().is_empty());
+    }
+
+    #[test]
+    fn sort_the_example() {
+        let rows = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(Some(12521), least_energy(Burrow::<2>::from_rows(&rows)));
+        assert_eq!(
+            Some(44169),
+            least_energy(Burrow::<4>::from_rows(&unfold(&rows)))
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
+use crate::scan;
+
+pub fn run() {
+    let path = "src/day24_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 24: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let program = match parse(&input) {
+        Ok(program) => program,
+        Err(err) => {
+            println!("Day 24: {}", err);
+            return;
+        }
+    };
+
+    let blocks = match find_blocks(&program) {
+        Some(blocks) => blocks,
+        None => {
+            println!("Day 24: the program does not look like MONAD");
+            return;
+        }
+    };
+    match model_numbers(&blocks) {
+        Some((largest, smallest)) => {
+            // double check the answers on the real thing.
+            assert!(is_valid(&program, largest));
+            assert!(is_valid(&program, smallest));
+            println!("Day 24A: {}", largest);
+            println!("Day 24B: {}", smallest);
+        }
+        None => println!("Day 24: no model number is valid"),
+    }
+}
+
+// the registers w, x, y and z.
+type Registers = [i64; 4];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operand {
+    Register(usize),
+    Number(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+fn parse_register(name: &str) -> Option<usize> {
+    return match name {
+        "w" => Some(0),
+        "x" => Some(1),
+        "y" => Some(2),
+        "z" => Some(3),
+        _ => None,
+    };
+}
+
+fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut out = Vec::new();
+
+    for (n, line) in numbered_lines(input) {
+        let register = |name: &str| -> Result<usize, ParseError> {
+            return parse_register(name)
+                .ok_or_else(|| ParseError::at_token(n, line, name, "a register from w to z"));
+        };
+
+        if line.trim().starts_with("inp") {
+            let (a,) = scan!((n, line), "inp {}", &str)?;
+            out.push(Instruction::Inp(register(a)?));
+            continue;
+        }
+
+        let (name, a, b) = scan!((n, line), "{} {} {}", &str, &str, &str)?;
+        let a = register(a)?;
+        let b = match parse_register(b) {
+            Some(r) => Operand::Register(r),
+            None => Operand::Number(parse_token(n, line, b, "a register or a number")?),
+        };
+        out.push(match name {
+            "add" => Instruction::Add(a, b),
+            "mul" => Instruction::Mul(a, b),
+            "div" => Instruction::Div(a, b),
+            "mod" => Instruction::Mod(a, b),
+            "eql" => Instruction::Eql(a, b),
+            _ => return Err(ParseError::at_token(n, line, name, "an instruction")),
+        });
+    }
+
+    return Ok(out);
+}
+
+// None when the program runs out of input or would crash the alu.
+fn execute(program: &[Instruction], input: &[i64]) -> Option<Registers> {
+    let mut registers: Registers = [0; 4];
+    let mut input = input.iter();
+
+    for instruction in program {
+        let value = |b: Operand| -> i64 {
+            return match b {
+                Operand::Register(r) => registers[r],
+                Operand::Number(n) => n,
+            };
+        };
+
+        match *instruction {
+            Instruction::Inp(a) => registers[a] = *input.next()?,
+            Instruction::Add(a, b) => registers[a] += value(b),
+            Instruction::Mul(a, b) => registers[a] *= value(b),
+            Instruction::Div(a, b) => {
+                let b = value(b);
+                if b == 0 {
+                    return None;
+                }
+                registers[a] /= b;
+            }
+            Instruction::Mod(a, b) => {
+                let b = value(b);
+                if registers[a] < 0 || b <= 0 {
+                    return None;
+                }
+                registers[a] %= b;
+            }
+            Instruction::Eql(a, b) => registers[a] = (registers[a] == value(b)) as i64,
+        }
+    }
+
+    return Some(registers);
+}
+
+fn digits(number: u64) -> Vec<i64> {
+    return number
+        .to_string()
+        .chars()
+        .map(|ch| ch.to_digit(10).unwrap() as i64)
+        .collect();
+}
+
+fn is_valid(program: &[Instruction], number: u64) -> bool {
+    let digits = digits(number);
+    if digits.contains(&0) {
+        return false;
+    }
+    return execute(program, &digits).map_or(false, |r| r[3] == 0);
+}
+
+// what is different between the fourteen parts of MONAD, one per digit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Block {
+    // 1 to push the digit onto z, 26 to pop one off.
+    divisor: i64,
+    // added to the popped value, to compare it with the digit.
+    check: i64,
+    // added to the digit before it is pushed.
+    offset: i64,
+}
+
+// every part of the program reads a digit and then does this, with its
+// own three numbers filled in.
+fn block_template(block: &Block) -> Vec<Instruction> {
+    use Instruction::*;
+    use Operand::*;
+    let (w, x, y, z) = (0, 1, 2, 3);
+
+    return vec![
+        Inp(w),
+        Mul(x, Number(0)),
+        Add(x, Register(z)),
+        Mod(x, Number(26)),
+        Div(z, Number(block.divisor)),
+        Add(x, Number(block.check)),
+        Eql(x, Register(w)),
+        Eql(x, Number(0)),
+        Mul(y, Number(0)),
+        Add(y, Number(25)),
+        Mul(y, Register(x)),
+        Add(y, Number(1)),
+        Mul(z, Register(y)),
+        Mul(y, Number(0)),
+        Add(y, Register(w)),
+        Add(y, Number(block.offset)),
+        Mul(y, Register(x)),
+        Add(z, Register(y)),
+    ];
+}
+
+// reads the numbers out of every part, or None when some part does not
+// match the template.
+fn find_blocks(program: &[Instruction]) -> Option<Vec<Block>> {
+    let length = block_template(&Block {
+        divisor: 1,
+        check: 0,
+        offset: 0,
+    })
+    .len();
+    if program.len() % length != 0 {
+        return None;
+    }
+
+    let number = |instruction: Instruction| -> Option<i64> {
+        return match instruction {
+            Instruction::Div(_, Operand::Number(n)) | Instruction::Add(_, Operand::Number(n)) => {
+                Some(n)
+            }
+            _ => None,
+        };
+    };
+
+    let mut out = Vec::new();
+    for part in program.chunks(length) {
+        let block = Block {
+            divisor: number(part[4])?,
+            check: number(part[5])?,
+            offset: number(part[15])?,
+        };
+        if block_template(&block) != part || ![1, 26].contains(&block.divisor) {
+            return None;
+        }
+        out.push(block);
+    }
+    return Some(out);
+}
+
+// z is a stack of base 26 digits. a part that divides by 1 always pushes,
+// since its check is too large to ever match a digit. a part that
+// divides by 26 pops, and must not push again, which ties its digit to
+// the digit that pushed: digit[j] = digit[i] + offset[i] + check[j].
+// returns the largest and the smallest model number.
+fn model_numbers(blocks: &[Block]) -> Option<(u64, u64)> {
+    let mut largest = vec![0; blocks.len()];
+    let mut smallest = vec![0; blocks.len()];
+    let mut stack = Vec::new();
+
+    for (j, block) in blocks.iter().enumerate() {
+        if block.divisor == 1 {
+            stack.push(j);
+            continue;
+        }
+
+        let i = stack.pop()?;
+        let diff = blocks[i].offset + block.check;
+        if diff.abs() > 8 {
+            return None;
+        }
+        largest[i] = std::cmp::min(9, 9 - diff);
+        largest[j] = largest[i] + diff;
+        smallest[i] = std::cmp::max(1, 1 - diff);
+        smallest[j] = smallest[i] + diff;
+    }
+    if !stack.is_empty() {
+        return None;
+    }
+
+    let to_number = |digits: &[i64]| digits.iter().fold(0, |n, d| n * 10 + *d as u64);
+    return Some((to_number(&largest), to_number(&smallest)));
+}
+
+mod tests {
+    use crate::day24::*;
+
+    fn make_monad(blocks: &[Block]) -> Vec<Instruction> {
+        return blocks.iter().flat_map(block_template).collect();
+    }
+
+    #[test]
+    fn run_the_example_programs() {
+        let negate = parse("inp x\nmul x -1").unwrap();
+        assert_eq!(Some([0, -7, 0, 0]), execute(&negate, &[7]));
+
+        let three_times = parse("inp z\ninp x\nmul z 3\neql z x").unwrap();
+        assert_eq!(1, execute(&three_times, &[2, 6]).unwrap()[3]);
+        assert_eq!(0, execute(&three_times, &[2, 7]).unwrap()[3]);
+
+        let binary = parse(
+            r#"
+inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2
+"#,
+        )
+        .unwrap();
+        assert_eq!(Some([1, 0, 1, 1]), execute(&binary, &[11]));
+        assert_eq!(Some([0, 1, 1, 0]), execute(&binary, &[6]));
+
+        // out of input, and the things the alu cannot do.
+        assert_eq!(None, execute(&three_times, &[2]));
+        assert_eq!(None, execute(&parse("inp x\ndiv x 0").unwrap(), &[1]));
+        assert_eq!(None, execute(&parse("inp x\nmod x 5").unwrap(), &[-1]));
+    }
+
+    #[test]
+    fn report_bad_instructions() {
+        let check = |input: &str, line: usize, column: usize, expected: &str| {
+            let err = parse(input).unwrap_err();
+            assert_eq!(
+                (line, column, expected.to_string()),
+                (err.line, err.column, err.expected),
+                "{}",
+                input
+            );
+        };
+
+        check("inp x\nsub x 1", 2, 1, "an instruction");
+        check("inp v", 1, 5, "a register from w to z");
+        check("inp w\nadd w 1\nmul q w", 3, 5, "a register from w to z");
+        check("add x 1.5", 1, 7, "a register or a number");
+        check("mul x", 1, 6, "' '");
+    }
+
+    #[test]
+    fn read_the_blocks() {
+        let blocks = [
+            Block {
+                divisor: 1,
+                check: 12,
+                offset: 4,
+            },
+            Block {
+                divisor: 26,
+                check: -7,
+                offset: 3,
+            },
+        ];
+        let program = make_monad(&blocks);
+        assert_eq!(Some(blocks.to_vec()), find_blocks(&program));
+        assert_eq!(None, find_blocks(&program[1..]));
+
+        let mut changed = program.clone();
+        changed[10] = Instruction::Mul(2, Operand::Number(3));
+        assert_eq!(None, find_blocks(&changed));
+    }
+
+    #[test]
+    fn match_a_brute_force_search() {
+        // push, push, pop, pop.
+        let blocks = [
+            Block {
+                divisor: 1,
+                check: 11,
+                offset: 6,
+            },
+            Block {
+                divisor: 1,
+                check: 14,
+                offset: 2,
+            },
+            Block {
+                divisor: 26,
+                check: -5,
+                offset: 9,
+            },
+            Block {
+                divisor: 26,
+                check: -2,
+                offset: 1,
+            },
+        ];
+        let program = make_monad(&blocks);
+        let valid: Vec<u64> = (1111..=9999).filter(|n| is_valid(&program, *n)).collect();
+        assert!(!valid.is_empty());
+
+        let (largest, smallest) = model_numbers(&blocks).unwrap();
+        assert_eq!(*valid.last().unwrap(), largest);
+        assert_eq!(valid[0], smallest);
+
+        // the digits would have to be ten apart.
+        let impossible = [
+            Block {
+                divisor: 1,
+                check: 11,
+                offset: 12,
+            },
+            Block {
+                divisor: 26,
+                check: -2,
+                offset: 1,
+            },
+        ];
+        assert_eq!(None, model_numbers(&impossible));
+    }
+}
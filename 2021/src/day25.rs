@@ -0,0 +1,203 @@
+use crate::parse::{numbered_lines, ParseError};
+
+pub fn run() {
+    let path = "src/day25_input.txt";
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("Day 25: cannot read {}: {}", path, err);
+            return;
+        }
+    };
+    let herds = match parse(&input) {
+        Ok(herds) => herds,
+        Err(err) => {
+            println!("Day 25: {}", err);
+            return;
+        }
+    };
+
+    println!("Day 25: {}", steps_until_stuck(herds));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+type Herds = Vec<Vec<Cell>>;
+
+// every row has to be as wide as the first one, as the herds wrap around.
+fn parse(input: &str) -> Result<Herds, ParseError> {
+    let mut out: Herds = Vec::new();
+
+    for (n, line) in numbered_lines(input) {
+        let text = line.trim();
+        if let Some(bad) = text.find(|ch| !".>v".contains(ch)) {
+            return Err(ParseError::at_token(
+                n,
+                line,
+                &text[bad..],
+                "'.', '>' or 'v'",
+            ));
+        }
+        if !out.is_empty() && text.len() != out[0].len() {
+            let expected = format!("{} cells in the row", out[0].len());
+            return Err(ParseError::at_token(n, line, text, &expected));
+        }
+
+        let row = text
+            .chars()
+            .map(|ch| match ch {
+                '>' => Cell::East,
+                'v' => Cell::South,
+                _ => Cell::Empty,
+            })
+            .collect();
+        out.push(row);
+    }
+
+    return Ok(out);
+}
+
+fn to_string(herds: &Herds) -> String {
+    return herds
+        .iter()
+        .map(|row| {
+            return row
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Empty => '.',
+                    Cell::East => '>',
+                    Cell::South => 'v',
+                })
+                .collect::<String>();
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
+
+// moves one herd, all at once, wrapping around the edges. every cucumber
+// looks at where the others were before any of them moved.
+fn move_herd(herds: &Herds, kind: Cell) -> (Herds, bool) {
+    let height = herds.len();
+    let width = herds[0].len();
+    let mut out = herds.clone();
+    let mut moved = false;
+
+    for y in 0..height {
+        for x in 0..width {
+            if herds[y][x] != kind {
+                continue;
+            }
+            let (nx, ny) = match kind {
+                Cell::East => ((x + 1) % width, y),
+                _ => (x, (y + 1) % height),
+            };
+            if herds[ny][nx] == Cell::Empty {
+                out[y][x] = Cell::Empty;
+                out[ny][nx] = kind;
+                moved = true;
+            }
+        }
+    }
+
+    return (out, moved);
+}
+
+// the east herd moves first, then the south one.
+fn step(herds: &Herds) -> (Herds, bool) {
+    let (herds, east_moved) = move_herd(herds, Cell::East);
+    let (herds, south_moved) = move_herd(&herds, Cell::South);
+    return (herds, east_moved || south_moved);
+}
+
+// the first step on which nobody moves.
+fn steps_until_stuck(mut herds: Herds) -> usize {
+    let mut steps = 1;
+    loop {
+        let (next, moved) = step(&herds);
+        if !moved {
+            return steps;
+        }
+        herds = next;
+        steps += 1;
+    }
+}
+
+mod tests {
+    use crate::day25::*;
+
+    const EXAMPLE_INPUT: &'static str = r#"
+v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>
+"#;
+
+    #[test]
+    fn move_in_a_line() {
+        let herds = parse("...>>>>>...").unwrap();
+        let (once, _) = step(&herds);
+        assert_eq!("...>>>>.>..", to_string(&once));
+        let (twice, _) = step(&once);
+        assert_eq!("...>>>.>.>.", to_string(&twice));
+    }
+
+    #[test]
+    fn wrap_around() {
+        let herds = parse(
+            r#"
+...>...
+.......
+......>
+v.....>
+......>
+.......
+..vvv..
+"#,
+        )
+        .unwrap();
+        let (once, _) = step(&herds);
+        assert_eq!(
+            "..vv>..\n.......\n>......\nv.....>\n>......\n.......\n....v..",
+            to_string(&once)
+        );
+    }
+
+    #[test]
+    fn wait_for_the_example() {
+        let herds = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(EXAMPLE_INPUT.trim(), to_string(&herds));
+
+        let (once, moved) = step(&herds);
+        assert!(moved);
+        assert_eq!(
+            "....>.>v.>\nv.v>.>v.v.\n>v>>..>v..\n>>v>v>.>.v\n.>v.v...v.\nv>>.>vvv..\n..v...>>..\nvv...>>vv.\n>.v.v..v.v",
+            to_string(&once)
+        );
+        assert_eq!(58, steps_until_stuck(herds));
+    }
+
+    #[test]
+    fn report_bad_herds() {
+        let err = parse("v..>\n.v<.").unwrap_err();
+        assert_eq!(
+            (2, 3, "'.', '>' or 'v'".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse("v..>\n  .v.").unwrap_err();
+        assert_eq!(
+            (2, 3, "4 cells in the row".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
+}
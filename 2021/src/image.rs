@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+pub type Rgb = [u8; 3];
+
+// every pixel is an index into the palette, so a grid only has
+// to map its cells to a "kind" and the palette decides the color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    palette: Vec<Rgb>,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, palette: &[Rgb]) -> Self {
+        assert!(!palette.is_empty() && palette.len() <= 256);
+
+        return Self {
+            width,
+            height,
+            palette: palette.to_vec(),
+            pixels: vec![0; width * height],
+        };
+    }
+
+    pub fn from_fn<F: FnMut(usize, usize) -> u8>(
+        width: usize,
+        height: usize,
+        palette: &[Rgb],
+        mut kind_at: F,
+    ) -> Self {
+        let mut image = Self::new(width, height, palette);
+        for y in 0..height {
+            for x in 0..width {
+                image.set(x, y, kind_at(x, y));
+            }
+        }
+        return image;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        return self.pixels[y * self.width + x];
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, kind: u8) {
+        assert!(
+            (kind as usize) < self.palette.len(),
+            "No color for kind {}",
+            kind
+        );
+        self.pixels[y * self.width + x] = kind;
+    }
+
+    // tiny grids (like folded paper) are unreadable at one pixel per cell.
+    pub fn scaled(&self, factor: usize) -> Self {
+        return Self::from_fn(
+            self.width * factor,
+            self.height * factor,
+            &self.palette,
+            |x, y| self.get(x / factor, y / factor),
+        );
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for kind in &self.pixels {
+            out.extend_from_slice(&self.palette[*kind as usize]);
+        }
+        return out;
+    }
+
+    // an indexed color PNG where the image data is stored in
+    // uncompressed deflate blocks. big, but trivially correct.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut out = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth 8, color type 3 (indexed), default compression, filter and interlacing.
+        header.extend_from_slice(&[8, 3, 0, 0, 0]);
+        write_png_chunk(&mut out, b"IHDR", &header);
+
+        let palette: Vec<u8> = self.palette.iter().flatten().copied().collect();
+        write_png_chunk(&mut out, b"PLTE", &palette);
+
+        let mut scanlines = Vec::with_capacity((self.width + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            // filter type "none".
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+        write_png_chunk(&mut out, b"IDAT", &zlib_store(&scanlines));
+        write_png_chunk(&mut out, b"IEND", &[]);
+
+        return out;
+    }
+}
+
+// a sequence of equally sized frames, typically one per simulation step.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    width: usize,
+    height: usize,
+    palette: Vec<Rgb>,
+    frames: Vec<Vec<u8>>,
+    delay_centiseconds: u16,
+}
+
+impl Animation {
+    pub fn new(delay_centiseconds: u16) -> Self {
+        return Self {
+            width: 0,
+            height: 0,
+            palette: Vec::new(),
+            frames: Vec::new(),
+            delay_centiseconds,
+        };
+    }
+
+    pub fn push_frame(&mut self, frame: &Image) {
+        if self.frames.is_empty() {
+            self.width = frame.width;
+            self.height = frame.height;
+            self.palette = frame.palette.clone();
+        }
+
+        assert_eq!((self.width, self.height), (frame.width, frame.height));
+        assert_eq!(self.palette, frame.palette);
+
+        self.frames.push(frame.pixels.clone());
+    }
+
+    pub fn len(&self) -> usize {
+        return self.frames.len();
+    }
+
+    pub fn to_gif(&self) -> Vec<u8> {
+        assert!(
+            !self.frames.is_empty(),
+            "Cannot encode an animation without frames."
+        );
+
+        // the color table must have a power of two size, at least 2.
+        let mut color_bits: u8 = 1;
+        while (1 << color_bits) < self.palette.len() {
+            color_bits += 1;
+        }
+
+        let mut out = b"GIF89a".to_vec();
+        out.extend_from_slice(&(self.width as u16).to_le_bytes());
+        out.extend_from_slice(&(self.height as u16).to_le_bytes());
+        // global color table, 8 bits per primary color.
+        out.push(0x80 | 0x70 | (color_bits - 1));
+        out.push(0);
+        out.push(0);
+
+        for i in 0..(1 << color_bits) {
+            out.extend_from_slice(self.palette.get(i).unwrap_or(&[0, 0, 0]));
+        }
+
+        // loop forever.
+        out.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+        for frame in &self.frames {
+            out.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+            out.extend_from_slice(&self.delay_centiseconds.to_le_bytes());
+            out.extend_from_slice(&[0x00, 0x00]);
+
+            out.push(0x2c);
+            out.extend_from_slice(&[0, 0, 0, 0]);
+            out.extend_from_slice(&(self.width as u16).to_le_bytes());
+            out.extend_from_slice(&(self.height as u16).to_le_bytes());
+            out.push(0);
+
+            let min_code_size = std::cmp::max(2, color_bits);
+            out.push(min_code_size);
+
+            for block in lzw_encode(frame, min_code_size).chunks(255) {
+                out.push(block.len() as u8);
+                out.extend_from_slice(block);
+            }
+            out.push(0);
+        }
+
+        out.push(0x3b);
+
+        return out;
+    }
+}
+
+pub fn is_enabled() -> bool {
+    return std::env::args().any(|arg| arg == "--images");
+}
+
+pub fn save(name: &str, bytes: &[u8]) {
+    let dir = std::path::Path::new("images");
+    std::fs::create_dir_all(dir).unwrap();
+
+    let path = dir.join(name);
+    std::fs::write(&path, bytes).unwrap();
+
+    println!("Wrote {}", path.display());
+}
+
+fn write_png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (!(crc & 1)).wrapping_add(1);
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    return !crc;
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let mut a: u32 = 1;
+    let mut b: u32 = 0;
+
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    return (b << 16) | a;
+}
+
+fn zlib_store(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        out.push(u8::from(is_final));
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+
+    return out;
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.current |= (code as u32) << self.bit_count;
+        self.bit_count += size as u32;
+
+        while self.bit_count >= 8 {
+            self.bytes.push((self.current & 0xff) as u8);
+            self.current >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push((self.current & 0xff) as u8);
+        }
+        return self.bytes;
+    }
+}
+
+const LZW_MAX_CODE: u16 = 4095;
+
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear_code: u16 = 1 << min_code_size;
+    let end_code = clear_code + 1;
+
+    let mut writer = BitWriter {
+        bytes: Vec::new(),
+        current: 0,
+        bit_count: 0,
+    };
+    let mut dict: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut code_size = min_code_size + 1;
+    let mut next_code = end_code + 1;
+
+    writer.write(clear_code, code_size);
+
+    let mut iter = indices.iter();
+    let mut prefix: u16 = match iter.next() {
+        Some(first) => *first as u16,
+        None => {
+            writer.write(end_code, code_size);
+            return writer.finish();
+        }
+    };
+
+    for &index in iter {
+        if let Some(&code) = dict.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.write(prefix, code_size);
+
+        if next_code <= LZW_MAX_CODE {
+            dict.insert((prefix, index), next_code);
+            if next_code == (1 << code_size) && code_size < 12 {
+                code_size += 1;
+            }
+            next_code += 1;
+        } else {
+            // the table is full, start over.
+            writer.write(clear_code, code_size);
+            dict.clear();
+            code_size = min_code_size + 1;
+            next_code = end_code + 1;
+        }
+
+        prefix = index as u16;
+    }
+
+    writer.write(prefix, code_size);
+    writer.write(end_code, code_size);
+
+    return writer.finish();
+}
+
+mod tests {
+    use crate::image::*;
+
+    const PALETTE: [Rgb; 3] = [[0, 0, 0], [255, 255, 255], [255, 0, 0]];
+
+    // a straightforward decoder, only used to verify the encoder.
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear_code: u16 = 1 << min_code_size;
+        let end_code = clear_code + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut code_size = min_code_size + 1;
+        let mut prev: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        let mut bit_pos = 0;
+
+        loop {
+            let mut code: u16 = 0;
+            for i in 0..code_size {
+                let byte = data[(bit_pos + i as usize) / 8];
+                let bit = (byte >> ((bit_pos + i as usize) % 8)) & 1;
+                code |= (bit as u16) << i;
+            }
+            bit_pos += code_size as usize;
+
+            if code == clear_code {
+                table = (0..clear_code).map(|c| vec![c as u8]).collect();
+                table.push(Vec::new());
+                table.push(Vec::new());
+                code_size = min_code_size + 1;
+                prev = None;
+                continue;
+            }
+            if code == end_code {
+                return out;
+            }
+
+            let entry = match &prev {
+                None => table[code as usize].clone(),
+                Some(p) => {
+                    let entry = if (code as usize) < table.len() {
+                        table[code as usize].clone()
+                    } else {
+                        [&p[..], &p[0..1]].concat()
+                    };
+                    if table.len() <= LZW_MAX_CODE as usize {
+                        table.push([&p[..], &entry[0..1]].concat());
+                    }
+                    entry
+                }
+            };
+
+            if table.len() == (1 << code_size) && code_size < 12 {
+                code_size += 1;
+            }
+
+            out.extend_from_slice(&entry);
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn encode_ppm() {
+        let img = Image::from_fn(2, 2, &PALETTE, |x, y| ((x + y) % 3) as u8);
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend_from_slice(&[0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 0, 0]);
+
+        assert_eq!(expected, img.to_ppm());
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+        assert_eq!(0x11e6_0398, adler32(b"Wikipedia"));
+    }
+
+    #[test]
+    fn encode_png() {
+        let img = Image::new(3, 2, &PALETTE);
+        let png = img.to_png();
+
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[0..8]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(&[0, 0, 0, 3, 0, 0, 0, 2, 8, 3, 0, 0, 0], &png[16..29]);
+        // the IEND chunk always has the same checksum.
+        assert_eq!(&[0xae, 0x42, 0x60, 0x82], &png[png.len() - 4..]);
+    }
+
+    #[test]
+    fn scale_image() {
+        let img = Image::from_fn(2, 1, &PALETTE, |x, _| x as u8).scaled(2);
+
+        assert_eq!((4, 2), (img.width, img.height));
+        assert_eq!(0, img.get(1, 1));
+        assert_eq!(1, img.get(2, 0));
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let data: Vec<u8> = (0..20_000).map(|i| ((i * 7 + i / 13) % 3) as u8).collect();
+        let encoded = lzw_encode(&data, 2);
+
+        assert_eq!(data, lzw_decode(&encoded, 2));
+    }
+
+    #[test]
+    fn encode_gif() {
+        let mut anim = Animation::new(10);
+        anim.push_frame(&Image::new(4, 4, &PALETTE));
+        anim.push_frame(&Image::from_fn(4, 4, &PALETTE, |x, _| (x % 3) as u8));
+        let gif = anim.to_gif();
+
+        assert_eq!(2, anim.len());
+        assert_eq!(b"GIF89a", &gif[0..6]);
+        // 3 colors are padded to a table of 4.
+        assert_eq!(0xf1, gif[10]);
+        assert_eq!(0x3b, *gif.last().unwrap());
+    }
+}
@@ -1,3 +1,4 @@
+mod combinatorics;
 mod day01;
 mod day02;
 mod day03;
@@ -15,6 +16,20 @@ mod day14;
 mod day15;
 mod day16;
 mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
+mod dot;
+mod image;
+mod lcg;
+mod memo;
+mod ocr;
+mod parse;
 
 fn main() {
     // day01::run();
@@ -34,4 +49,12 @@ fn main() {
     // day15::run();
     // day16::run();
     day17::run();
+    // day18::run();
+    // day19::run();
+    // day20::run();
+    // day21::run();
+    // day22::run();
+    // day23::run();
+    // day24::run();
+    // day25::run();
 }
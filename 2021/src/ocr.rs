@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+
+// the two fonts that advent of code draws its answers with. glyphs
+// are compared after trimming empty columns, so the widths may vary.
+struct Font {
+    height: usize,
+    glyphs: &'static [(char, &'static [&'static str])],
+}
+
+#[rustfmt::skip]
+const FONT_4X6: Font = Font {
+    height: 6,
+    glyphs: &[
+        ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+        ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+        ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+        ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+        ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+        ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+        ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+        ('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+        ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+        ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+        ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+        ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+        ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+        ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+        ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+        ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+    ],
+};
+
+#[rustfmt::skip]
+const FONT_6X10: Font = Font {
+    height: 10,
+    glyphs: &[
+        ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+        ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+        ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+        ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+        ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+        ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+        ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+        ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+        ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+        ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+        ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+    ],
+};
+
+const FONTS: [Font; 2] = [FONT_4X6, FONT_6X10];
+
+// reads the letters of a text render like "#..#\n####\n...", where
+// '#' is a lit pixel and any other character is dark.
+pub fn recognize_text(text: &str) -> Option<String> {
+    let rows: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    return recognize(width, rows.len(), |x, y| {
+        return rows[y].as_bytes().get(x) == Some(&b'#');
+    });
+}
+
+// reads the letters drawn by a set of lit (x, y) points.
+pub fn recognize_points<I: IntoIterator<Item = (i64, i64)>>(points: I) -> Option<String> {
+    let points: HashSet<(i64, i64)> = points.into_iter().collect();
+    let min_x = points.iter().map(|pt| pt.0).min()?;
+    let max_x = points.iter().map(|pt| pt.0).max()?;
+    let min_y = points.iter().map(|pt| pt.1).min()?;
+    let max_y = points.iter().map(|pt| pt.1).max()?;
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+
+    return recognize(width, height, |x, y| {
+        let pt = (min_x + x as i64, min_y + y as i64);
+        return points.contains(&pt);
+    });
+}
+
+fn recognize<F: Fn(usize, usize) -> bool>(
+    width: usize,
+    height: usize,
+    is_lit: F,
+) -> Option<String> {
+    let is_column_empty = |x: usize| (0..height).all(|y| !is_lit(x, y));
+    let is_row_empty = |y: usize| (0..width).all(|x| !is_lit(x, y));
+
+    // the drawing may have some empty space around it.
+    let top = (0..height).find(|y| !is_row_empty(*y))?;
+    let bottom = (0..height).rev().find(|y| !is_row_empty(*y))?;
+    let glyph_height = bottom - top + 1;
+    let font = FONTS.iter().find(|f| f.height == glyph_height)?;
+
+    let mut out = String::new();
+    let mut x = 0;
+
+    while x < width {
+        if is_column_empty(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && !is_column_empty(x) {
+            x += 1;
+        }
+
+        let (ch, _) = font.glyphs.iter().find(|(_, rows)| {
+            return glyph_matches(rows, x - start, |gx, gy| is_lit(start + gx, top + gy));
+        })?;
+
+        out.push(*ch);
+    }
+
+    return Some(out);
+}
+
+fn glyph_matches<F: Fn(usize, usize) -> bool>(rows: &[&str], width: usize, is_lit: F) -> bool {
+    let is_glyph_column_empty = |x: usize| rows.iter().all(|row| row.as_bytes()[x] != b'#');
+    let glyph_width = rows[0].len();
+    let first = (0..glyph_width)
+        .find(|x| !is_glyph_column_empty(*x))
+        .unwrap();
+    let last = (0..glyph_width)
+        .rev()
+        .find(|x| !is_glyph_column_empty(*x))
+        .unwrap();
+
+    if last - first + 1 != width {
+        return false;
+    }
+
+    for (y, row) in rows.iter().enumerate() {
+        for x in 0..width {
+            let expected = row.as_bytes()[first + x] == b'#';
+            if expected != is_lit(x, y) {
+                return false;
+            }
+        }
+    }
+
+    return true;
+}
+
+mod tests {
+    use crate::ocr::*;
+
+    #[test]
+    fn recognize_small_font() {
+        let text = r#"
+            .##..###..#..#.####.###...##..#..#.####.
+            #..#.#..#.#..#.#....#..#.#..#.#..#....#.
+            #..#.###..####.###..#..#.#....#..#...#..
+            ####.#..#.#..#.#....###..#....#..#..#...
+            #..#.#..#.#..#.#....#.#..#..#.#..#.#....
+            #..#.###..#..#.####.#..#..##...##..####.
+        "#;
+
+        assert_eq!(Some("ABHERCUZ".to_string()), recognize_text(text));
+    }
+
+    #[test]
+    fn recognize_large_font() {
+        let text = r#"
+            #....#..#####.
+            #....#..#....#
+            .#..#...#....#
+            .#..#...#....#
+            ..##....#####.
+            ..##....#.....
+            .#..#...#.....
+            .#..#...#.....
+            #....#..#.....
+            #....#..#.....
+        "#;
+
+        assert_eq!(Some("XP".to_string()), recognize_text(text));
+    }
+
+    #[test]
+    fn recognize_points_anywhere() {
+        // an "L" and an "I" drawn somewhere in the negative quadrant.
+        let mut points = Vec::new();
+        for y in -10..-4 {
+            points.push((-20, y));
+            points.push((-13, y));
+        }
+        for x in -19..-16 {
+            points.push((x, -5));
+        }
+        for x in [-14, -12] {
+            points.push((x, -10));
+            points.push((x, -5));
+        }
+
+        assert_eq!(Some("LI".to_string()), recognize_points(points));
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        assert_eq!(None, recognize_text("#.#\n.#.\n#.#\n.#.\n#.#\n.#."));
+    }
+}
@@ -1,16 +1,31 @@
+use crate::image;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-pub fn do_part_1() -> i64 {
-    let map = parse_input(INPUT).expand_to_covered_area();
-    return count_positions_where_a_beacon_cannot_be_present(&map, 2_000_000);
+pub fn do_part_1() -> Result<i64, ParseError> {
+    let map = parse_input(INPUT)?.expand_to_covered_area();
+    return Ok(count_positions_where_a_beacon_cannot_be_present(
+        &map, 2_000_000,
+    ));
 }
 
-pub fn do_part_2() -> i64 {
-    let map = parse_input(INPUT);
+// the real map spans millions of coordinates, so it is sampled down to roughly 1000 pixels.
+pub fn save_coverage_image() -> Result<(), ParseError> {
+    let map = parse_input(INPUT)?.expand_to_covered_area();
+    let width = map.bottom_right.x - map.top_left.x + 1;
+    let step = std::cmp::max(1, width / 1000);
+    let img = map.to_image_with_coverage(true, 0, step);
+    image::save("day15_coverage.png", &img.to_png());
+    return Ok(());
+}
+
+pub fn do_part_2() -> Result<i64, ParseError> {
+    let map = parse_input(INPUT)?;
     let pt = map.find_uncovered_point(0, 4_000_000).unwrap();
     let res = (pt.x as i64) * 4_000_000 + pt.y as i64;
-    return res;
+    return Ok(res);
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -57,6 +72,9 @@ enum Kind {
     Covered,
 }
 
+// indexed by the discriminant of Kind.
+const KIND_PALETTE: [image::Rgb; 4] = [[15, 15, 25], [60, 140, 255], [255, 80, 60], [70, 70, 90]];
+
 impl SensorMap {
     fn new(sensors: &[Sensor], expand_to_covered_area: bool) -> Self {
         let mut min_x = i32::MAX;
@@ -175,49 +193,48 @@ impl SensorMap {
         }
         return s;
     }
-}
-
-fn parse_input(data: &str) -> SensorMap {
-    let values = find_integers(data);
-    let mut sensors: Vec<Sensor> = Vec::new();
 
-    assert_eq!(0, values.len() % 4);
-
-    for chunk in values.chunks(4) {
-        let sensor_pt = Point::new(chunk[0], chunk[1]);
-        let beacon_pt = Point::new(chunk[2], chunk[3]);
-        sensors.push(Sensor {
-            position: sensor_pt,
-            beacon: beacon_pt,
+    fn to_image_with_coverage(
+        &self,
+        with_coverage: bool,
+        expand_with: i32,
+        step: i32,
+    ) -> image::Image {
+        let x_min = self.top_left.x - expand_with;
+        let y_min = self.top_left.y - expand_with;
+        let width = ((self.bottom_right.x + expand_with - x_min) / step + 1) as usize;
+        let height = ((self.bottom_right.y + expand_with - y_min) / step + 1) as usize;
+
+        return image::Image::from_fn(width, height, &KIND_PALETTE, |x, y| {
+            let pt = Point::new(x_min + (x as i32) * step, y_min + (y as i32) * step);
+            let kind = match self.kind_at(pt) {
+                Kind::Covered if !with_coverage => Kind::None,
+                k => k,
+            };
+            return kind as u8;
         });
     }
-
-    return SensorMap::new(&sensors, false);
 }
 
-// what am i doing with my life
-fn find_integers(data: &str) -> Vec<i32> {
-    let mut out: Vec<i32> = Vec::new();
-    let mut i: usize = 0;
-    let bytes = data.as_bytes();
-
-    while i < bytes.len() {
-        let mut k = i;
-
-        while bytes[k].is_ascii_digit() || bytes[k] == b'-' {
-            k += 1;
-        }
+fn parse_input(data: &str) -> Result<SensorMap, ParseError> {
+    let mut sensors: Vec<Sensor> = Vec::new();
 
-        if k != i {
-            let thing: i32 = data[i..k].parse().unwrap();
-            out.push(thing);
-            i = k;
-        } else {
-            i += 1;
-        }
+    for (n, line) in numbered_lines(data) {
+        let (sx, sy, bx, by) = scan!(
+            (n, line),
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            i32,
+            i32,
+            i32,
+            i32
+        )?;
+        sensors.push(Sensor {
+            position: Point::new(sx, sy),
+            beacon: Point::new(bx, by),
+        });
     }
 
-    return out;
+    return Ok(SensorMap::new(&sensors, false));
 }
 
 fn count_positions_where_a_beacon_cannot_be_present(map: &SensorMap, y: i32) -> i64 {
@@ -242,7 +259,7 @@ mod tests {
 
     #[test]
     fn test_count_positions_where_a_beacon_cannot_be_present() {
-        let data = parse_input(TEST_INPUT);
+        let data = parse_input(TEST_INPUT).unwrap();
         let s = count_positions_where_a_beacon_cannot_be_present(&data, 10);
 
         // println!("{:?}", data);
@@ -252,7 +269,7 @@ mod tests {
 
     #[test]
     fn test_find_uncovered_point() {
-        let data = parse_input(TEST_INPUT);
+        let data = parse_input(TEST_INPUT).unwrap();
         let pt = data.find_uncovered_point(0, 20);
 
         assert_eq!(Point::new(14, 11), pt.unwrap());
@@ -260,7 +277,7 @@ mod tests {
 
     #[test]
     fn test_to_string() {
-        let data = parse_input(TEST_INPUT);
+        let data = parse_input(TEST_INPUT).unwrap();
         let s = r#"
 ....S.......................
 ......................S.....
@@ -291,6 +308,38 @@ B...........................
 
         assert_eq!(s.trim(), res);
     }
+
+    #[test]
+    fn test_to_image() {
+        let data = parse_input(TEST_INPUT).unwrap();
+        let s = data.to_string_with_coverage(true, 0);
+        let img = data.to_image_with_coverage(true, 0, 1);
+
+        assert_eq!(28, img.width);
+        assert_eq!(23, img.height);
+
+        let lines: Vec<&[u8]> = s.lines().map(|line| line.as_bytes()).collect();
+        let expected = image::Image::from_fn(28, 23, &KIND_PALETTE, |x, y| {
+            let kind = match lines[y][x] {
+                b'B' => Kind::Beacon,
+                b'S' => Kind::Sensor,
+                b'#' => Kind::Covered,
+                _ => Kind::None,
+            };
+            return kind as u8;
+        });
+        assert_eq!(expected, img);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err =
+            parse_input("\nSensor at x=2, y=18: closest beacon is at x=-2, y=1 5").unwrap_err();
+        assert_eq!(
+            (2, 51, "an integer".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
 }
 
 const TEST_INPUT: &'static str = r#"
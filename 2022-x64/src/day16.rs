@@ -1,13 +1,35 @@
-use std::collections::{hash_map::Entry, HashMap};
+use crate::dot;
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
+use std::collections::{HashMap, VecDeque};
 
-pub fn do_part_1() -> i32 {
-    let valves = parse_input(TEST_INPUT);
-    println!("{:?}", valves);
+// the puzzle input is not checked in, so it is read from here at run time.
+pub const INPUT_PATH: &'static str = "src/day16_input.txt";
 
-    return 0;
+pub fn do_part_1(input: &str) -> Result<i32, ParseError> {
+    let network = compress(&parse_input(input)?);
+    return Ok(max_release(&network, MAX_ELAPSED_MINUTES));
+}
+
+pub fn do_part_2(input: &str) -> Result<i32, ParseError> {
+    let network = compress(&parse_input(input)?);
+    return Ok(max_release_with_elephant(
+        &network,
+        MAX_ELAPSED_MINUTES - ELEPHANT_TRAINING_MINUTES,
+    ));
+}
+
+pub fn save_graph(input: &str) -> Result<(), ParseError> {
+    let valves = parse_input(input)?;
+    dot::save("day16_valves.dot", &valves_to_graph(&valves));
+    return Ok(());
 }
 
 const MAX_ELAPSED_MINUTES: i32 = 30;
+const ELEPHANT_TRAINING_MINUTES: i32 = 4;
+const START_VALVE: &'static str = "AA";
+// one bit per valve worth opening, and a table entry per set of them.
+const MAX_USEFUL_VALVES: usize = 20;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Valve {
@@ -16,26 +38,247 @@ struct Valve {
     flow_rate: i32,
 }
 
-fn parse_input(data: &str) -> Vec<Valve> {
+// there has to be a start valve, and few enough valves that release
+// something to keep one bit for each.
+fn parse_input(data: &str) -> Result<Vec<Valve>, ParseError> {
     let mut out: Vec<Valve> = Vec::new();
-    for line in data.trim().lines() {
-        let parts: Vec<&str> = line.split(" ").collect();
-        let name = parts[1];
-        let (_, flow_rate_str) = parts[4].split_once('=').unwrap();
-        let leads_to: Vec<String> = parts[9..]
-            .iter()
-            .map(|p| p.trim_matches(',').to_string())
-            .collect();
+    let mut useful = 0;
+    let mut last = (1, "");
 
-        let valve = Valve {
+    for (n, line) in numbered_lines(data) {
+        let (name, flow_rate, leads_to) = scan!(
+            (n, line),
+            "Valve {} has flow rate={}; [tunnel leads to valve|tunnels lead to valves] {,}",
+            &str,
+            i32,
+            Vec<String>
+        )?;
+
+        if flow_rate > 0 {
+            useful += 1;
+            if useful > MAX_USEFUL_VALVES {
+                let rate = line.split_once('=').unwrap().1;
+                let expected = format!("at most {} valves with a flow rate", MAX_USEFUL_VALVES);
+                return Err(ParseError::at_token(n, line, rate, &expected));
+            }
+        }
+
+        out.push(Valve {
             name: name.to_string(),
-            leads_to: leads_to,
-            flow_rate: flow_rate_str.trim_matches(';').parse().unwrap(),
-        };
+            leads_to,
+            flow_rate,
+        });
+        last = (n, line);
+    }
 
-        out.push(valve);
+    if out.is_empty() {
+        return Err(ParseError::at_end(1, "", "a list of valves"));
     }
-    return out;
+    if !out.iter().any(|v| v.name == START_VALVE) {
+        let expected = format!("a valve named {}", START_VALVE);
+        return Err(ParseError::at_end(last.0, last.1, &expected));
+    }
+    return Ok(out);
+}
+
+// valves that release nothing are greyed out, and each tunnel is drawn once.
+fn valves_to_graph(valves: &[Valve]) -> dot::Graph {
+    let mut graph = dot::Graph::undirected("day16");
+
+    for valve in valves {
+        let label = format!("{}\nrate={}", valve.name, valve.flow_rate);
+        let color = if valve.flow_rate > 0 { "black" } else { "gray" };
+        graph.node(&valve.name, &[("label", &label), ("color", color)]);
+    }
+
+    for (i, valve) in valves.iter().enumerate() {
+        for to in &valve.leads_to {
+            // a tunnel back to an earlier valve was drawn from that valve.
+            let drawn = valves[..i]
+                .iter()
+                .any(|v| v.name == *to && v.leads_to.contains(&valve.name));
+            if !drawn {
+                graph.edge(&valve.name, to, &[]);
+            }
+        }
+    }
+
+    return graph;
+}
+
+// only the valves that release something matter, so the tunnels are
+// boiled down to the shortest walk between each pair of those. the start
+// is kept as well, as the last one.
+#[derive(Debug, Clone, PartialEq)]
+struct Network {
+    names: Vec<String>,
+    flow_rates: Vec<i32>,
+    distances: Vec<Vec<i32>>,
+    start: usize,
+}
+
+fn compress(valves: &[Valve]) -> Network {
+    let index: HashMap<&str, usize> = valves
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.name.as_str(), i))
+        .collect();
+    let start = index[START_VALVE];
+
+    let mut useful: Vec<usize> = (0..valves.len())
+        .filter(|i| valves[*i].flow_rate > 0)
+        .collect();
+    useful.push(start);
+
+    // a breadth first search from each of them. tunnels to valves that
+    // are not in the input lead nowhere.
+    let distances = useful
+        .iter()
+        .map(|from| {
+            let mut steps = vec![i32::MAX; valves.len()];
+            let mut queue = VecDeque::from([*from]);
+            steps[*from] = 0;
+            while let Some(at) = queue.pop_front() {
+                for to in &valves[at].leads_to {
+                    if let Some(&to) = index.get(to.as_str()) {
+                        if steps[to] == i32::MAX {
+                            steps[to] = steps[at] + 1;
+                            queue.push_back(to);
+                        }
+                    }
+                }
+            }
+            return useful.iter().map(|to| steps[*to]).collect();
+        })
+        .collect();
+
+    return Network {
+        names: useful.iter().map(|i| valves[*i].name.clone()).collect(),
+        flow_rates: useful.iter().map(|i| valves[*i].flow_rate).collect(),
+        distances,
+        start: useful.len() - 1,
+    };
+}
+
+impl Network {
+    fn useful_valves(&self) -> usize {
+        return self.names.len() - 1;
+    }
+
+    // the minutes left once "to" is open, after walking there from "from".
+    fn left_after_opening(&self, from: usize, to: usize, minutes_left: i32) -> i32 {
+        let distance = self.distances[from][to];
+        if distance == i32::MAX {
+            return 0;
+        }
+        return std::cmp::max(0, minutes_left - distance - 1);
+    }
+}
+
+struct Search<'a> {
+    network: &'a Network,
+    best: i32,
+}
+
+impl<'a> Search<'a> {
+    // the most that could still be released if every closed valve was
+    // reached straight from here. when even that is not better than the
+    // best so far, the branch is dropped.
+    fn bound(&self, at: usize, opened: u32, minutes_left: i32) -> i32 {
+        return (0..self.network.useful_valves())
+            .filter(|v| opened & (1 << v) == 0)
+            .map(|v| {
+                return self.network.flow_rates[v]
+                    * self.network.left_after_opening(at, v, minutes_left);
+            })
+            .sum();
+    }
+
+    fn visit(&mut self, at: usize, opened: u32, minutes_left: i32, released: i32) {
+        self.best = std::cmp::max(self.best, released);
+        if released + self.bound(at, opened, minutes_left) <= self.best {
+            return;
+        }
+
+        for next in 0..self.network.useful_valves() {
+            if opened & (1 << next) != 0 {
+                continue;
+            }
+            let left = self.network.left_after_opening(at, next, minutes_left);
+            if left == 0 {
+                continue;
+            }
+            let gain = self.network.flow_rates[next] * left;
+            self.visit(next, opened | (1 << next), left, released + gain);
+        }
+    }
+}
+
+// a depth first search over the order in which to open the valves.
+fn max_release(network: &Network, minutes: i32) -> i32 {
+    let mut search = Search { network, best: 0 };
+    search.visit(network.start, 0, minutes, 0);
+    return search.best;
+}
+
+// the most that one walker can release by opening exactly the valves in
+// each set, for every set that can be opened in time.
+fn best_by_opened(network: &Network, minutes: i32) -> Vec<i32> {
+    fn visit(
+        network: &Network,
+        at: usize,
+        opened: u32,
+        minutes_left: i32,
+        released: i32,
+        best: &mut Vec<i32>,
+    ) {
+        let entry = &mut best[opened as usize];
+        *entry = std::cmp::max(*entry, released);
+
+        for next in 0..network.useful_valves() {
+            if opened & (1 << next) != 0 {
+                continue;
+            }
+            let left = network.left_after_opening(at, next, minutes_left);
+            if left > 0 {
+                let gain = network.flow_rates[next] * left;
+                visit(
+                    network,
+                    next,
+                    opened | (1 << next),
+                    left,
+                    released + gain,
+                    best,
+                );
+            }
+        }
+    }
+
+    let mut best = vec![0; 1 << network.useful_valves()];
+    visit(network, network.start, 0, minutes, 0, &mut best);
+    return best;
+}
+
+// we and the elephant each open a different set of valves. once the table
+// holds the best for any subset of a set, the answer is the best way to
+// split all the valves in two.
+fn max_release_with_elephant(network: &Network, minutes: i32) -> i32 {
+    let mut best = best_by_opened(network, minutes);
+    let n = network.useful_valves();
+
+    for bit in 0..n {
+        for set in 0..best.len() {
+            if set & (1 << bit) != 0 {
+                best[set] = std::cmp::max(best[set], best[set ^ (1 << bit)]);
+            }
+        }
+    }
+
+    let all = best.len() - 1;
+    return (0..best.len())
+        .map(|ours| best[ours] + best[all ^ ours])
+        .max()
+        .unwrap();
 }
 
 #[cfg(test)]
@@ -44,7 +287,7 @@ mod tests {
 
     #[test]
     fn test_parsing() {
-        let stuff = parse_input(TEST_INPUT);
+        let stuff = parse_input(TEST_INPUT).unwrap();
 
         assert_eq!(10, stuff.len());
 
@@ -56,6 +299,97 @@ mod tests {
 
         assert_eq!(valve, stuff[0]);
     }
+
+    #[test]
+    fn test_graph() {
+        let valves = parse_input(TEST_INPUT).unwrap();
+
+        assert_eq!(
+            include_str!("day16_example.dot"),
+            valves_to_graph(&valves).to_dot()
+        );
+    }
+
+    #[test]
+    fn test_compress() {
+        let network = compress(&parse_input(TEST_INPUT).unwrap());
+
+        assert_eq!(
+            vec!["BB", "CC", "DD", "EE", "HH", "JJ", "AA"],
+            network.names
+        );
+        assert_eq!(vec![13, 2, 20, 3, 22, 21, 0], network.flow_rates);
+        assert_eq!(6, network.start);
+        // AA to HH goes through DD, EE, FF and GG.
+        assert_eq!(5, network.distances[6][4]);
+        assert_eq!(network.distances[4][6], network.distances[6][4]);
+    }
+
+    #[test]
+    fn test_release_pressure() {
+        let network = compress(&parse_input(TEST_INPUT).unwrap());
+
+        assert_eq!(1651, max_release(&network, MAX_ELAPSED_MINUTES));
+        assert_eq!(
+            1651,
+            *best_by_opened(&network, MAX_ELAPSED_MINUTES)
+                .iter()
+                .max()
+                .unwrap()
+        );
+        assert_eq!(1707, max_release_with_elephant(&network, 26));
+
+        // no time to get anywhere and open anything.
+        assert_eq!(0, max_release(&network, 1));
+        assert_eq!(20, max_release(&network, 3));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("Valve AA has flow rate=x; tunnel leads to valve BB").unwrap_err();
+        assert_eq!(
+            (1, 24, "an integer".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse_input("\nValve AA has flow\n").unwrap_err();
+        assert_eq!(
+            (2, 18, "' has flow rate='".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse_input("Valve AA has flow rate=1; tunnel leads to").unwrap_err();
+        assert_eq!(
+            (
+                1,
+                27,
+                "'; [tunnel leads to valve|tunnels lead to valves] '".to_string()
+            ),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse_input("Valve AA has flow rate=1; tunnel leads to valves BB").unwrap_err();
+        assert_eq!((1, 27), (err.line, err.column));
+
+        let err =
+            parse_input("Valve BB has flow rate=1; tunnel leads to valve CC\n\n").unwrap_err();
+        assert_eq!(
+            (1, 51, "a valve named AA".to_string()),
+            (err.line, err.column, err.expected)
+        );
+
+        let err = parse_input("\n").unwrap_err();
+        assert_eq!("a list of valves", err.expected);
+
+        let too_many: String = (0..=MAX_USEFUL_VALVES)
+            .map(|i| format!("Valve A{} has flow rate=1; tunnel leads to valve AA\n", i))
+            .collect();
+        let err = parse_input(&too_many).unwrap_err();
+        assert_eq!(
+            (21, 25, "at most 20 valves with a flow rate".to_string()),
+            (err.line, err.column, err.expected)
+        );
+    }
 }
 
 const TEST_INPUT: &'static str = r#"
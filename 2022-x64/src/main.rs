@@ -1,7 +1,38 @@
+mod day14;
 mod day15;
 mod day16;
+mod dot;
+mod image;
+mod parse;
 
 fn main() {
-    println!("Day 16 part 1 = {}", day16::do_part_1());
+    match std::fs::read_to_string(day16::INPUT_PATH) {
+        Ok(input) => {
+            match day16::do_part_1(&input) {
+                Ok(res) => println!("Day 16 part 1 = {}", res),
+                Err(err) => println!("Day 16 part 1: {}", err),
+            };
+            match day16::do_part_2(&input) {
+                Ok(res) => println!("Day 16 part 2 = {}", res),
+                Err(err) => println!("Day 16 part 2: {}", err),
+            };
+
+            if dot::is_enabled() {
+                if let Err(err) = day16::save_graph(&input) {
+                    println!("Day 16 graph: {}", err);
+                }
+            }
+        }
+        Err(err) => println!("Day 16: cannot read {}: {}", day16::INPUT_PATH, err),
+    };
     // println!("Day 15 part 2 = {}", day15::do_part_2());
+
+    if image::is_enabled() {
+        if let Err(err) = day14::save_sand_animations() {
+            println!("Day 14 animation: {}", err);
+        }
+        if let Err(err) = day15::save_coverage_image() {
+            println!("Day 15 image: {}", err);
+        }
+    }
 }
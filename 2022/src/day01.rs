@@ -1,3 +1,4 @@
+use crate::parse::ParseError;
 use crate::println;
 use crate::util::Day;
 
@@ -16,18 +17,18 @@ struct Elf {
     calories: i32,
 }
 
-fn part1() -> i32 {
+fn part1() -> Result<i32, ParseError<'static>> {
     let elves = parse_elves(INPUT);
     let calories = elves.iter().map(|e| e.calories).max().unwrap_or(0);
 
-    return calories;
+    return Ok(calories);
 }
 
-fn part2() -> i32 {
+fn part2() -> Result<i32, ParseError<'static>> {
     let mut elves = parse_elves(INPUT);
     elves.sort_unstable_by(|a, b| b.calories.cmp(&a.calories));
 
-    return elves[0].calories + elves[1].calories + elves[2].calories;
+    return Ok(elves[0].calories + elves[1].calories + elves[2].calories);
 }
 
 fn parse_elves(input: &str) -> Vec<Elf> {
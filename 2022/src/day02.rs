@@ -1,6 +1,8 @@
-use usb_device::endpoint::Out;
-
-use crate::{println, util::Day};
+use crate::{
+    parse::{numbered_lines, ParseError, ScanField},
+    println, scan,
+    util::Day,
+};
 
 type Vec<T> = heapless::Vec<T, 4196>;
 
@@ -8,7 +10,7 @@ pub const DAY_02: Day<i32> = Day {
     year: 2022,
     day: 2,
     parts: &[part1, part2],
-    tests: &[do_test_1, do_test_2],
+    tests: &[do_test_1, do_test_2, test_parse_errors],
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,13 +28,24 @@ impl Hand {
             Self::Scissors => 3,
         };
     }
+}
 
-    fn parse(value: &str) -> Self {
-        return match value {
-            "A" | "X" => Hand::Rock,
-            "B" | "Y" => Hand::Paper,
-            "C" | "Z" => Hand::Scissors,
-            _ => panic!("Bad!"),
+impl<'a> ScanField<'a> for Hand {
+    fn scan_field(
+        line: usize,
+        source_line: &'a str,
+        text: &'a str,
+    ) -> Result<Self, ParseError<'a>> {
+        return match text {
+            "A" | "X" => Ok(Hand::Rock),
+            "B" | "Y" => Ok(Hand::Paper),
+            "C" | "Z" => Ok(Hand::Scissors),
+            _ => Err(ParseError::at_token(
+                line,
+                source_line,
+                text,
+                "'A', 'B', 'C', 'X', 'Y' or 'Z'",
+            )),
         };
     }
 }
@@ -52,13 +65,24 @@ impl Outcome {
             Self::Lose => 0,
         };
     }
+}
 
-    fn parse(value: &str) -> Self {
-        return match value {
-            "X" => Self::Lose,
-            "Y" => Self::Draw,
-            "Z" => Self::Win,
-            _ => panic!("Bad"),
+impl<'a> ScanField<'a> for Outcome {
+    fn scan_field(
+        line: usize,
+        source_line: &'a str,
+        text: &'a str,
+    ) -> Result<Self, ParseError<'a>> {
+        return match text {
+            "X" => Ok(Self::Lose),
+            "Y" => Ok(Self::Draw),
+            "Z" => Ok(Self::Win),
+            _ => Err(ParseError::at_token(
+                line,
+                source_line,
+                text,
+                "'X', 'Y' or 'Z'",
+            )),
         };
     }
 }
@@ -93,36 +117,33 @@ impl GamePart1 {
     }
 }
 
-fn part1() -> i32 {
-    let games = parse_input_p1(INPUT);
+fn part1() -> Result<i32, ParseError<'static>> {
+    let games = parse_input_p1(INPUT)?;
 
-    return games.iter().map(|g| g.score()).sum();
+    return Ok(games.iter().map(|g| g.score()).sum());
 }
 
-fn parse_input_p1(input: &str) -> Vec<GamePart1> {
+fn parse_input_p1(input: &str) -> Result<Vec<GamePart1>, ParseError> {
     let mut stuff = Vec::<GamePart1>::new();
 
-    for line in input.trim().lines() {
-        let (a, b) = line.split_once(" ").expect("Bad!");
-        let game = GamePart1 {
-            me: Hand::parse(b),
-            opponent: Hand::parse(a),
-        };
+    for (n, line) in numbered_lines(input) {
+        let (opponent, me) = scan!((n, line), "{} {}", Hand, Hand)?;
+        let game = GamePart1 { me, opponent };
         stuff.push(game).expect("Bad vec!");
     }
 
-    return stuff;
+    return Ok(stuff);
 }
 
-fn part2() -> i32 {
-    let games = parse_input_p2(INPUT);
+fn part2() -> Result<i32, ParseError<'static>> {
+    let games = parse_input_p2(INPUT)?;
     let mut score: i32 = 0;
 
     for game in games {
         score += game.score();
     }
 
-    return score;
+    return Ok(score);
 }
 
 #[derive(Debug)]
@@ -148,19 +169,16 @@ impl GamePart2 {
     }
 }
 
-fn parse_input_p2(input: &str) -> Vec<GamePart2> {
+fn parse_input_p2(input: &str) -> Result<Vec<GamePart2>, ParseError> {
     let mut stuff = Vec::<GamePart2>::new();
 
-    for line in input.trim().lines() {
-        let (a, b) = line.split_once(" ").expect("Bad!");
-        let game = GamePart2 {
-            opponent: Hand::parse(a),
-            outcome: Outcome::parse(b),
-        };
+    for (n, line) in numbered_lines(input) {
+        let (opponent, outcome) = scan!((n, line), "{} {}", Hand, Outcome)?;
+        let game = GamePart2 { opponent, outcome };
         stuff.push(game).expect("Bad vec!");
     }
 
-    return stuff;
+    return Ok(stuff);
 }
 
 fn do_test_1() {
@@ -169,7 +187,7 @@ A Y
 B X
 C Z
 "###;
-    let games = parse_input_p1(input);
+    let games = parse_input_p1(input).unwrap();
 
     assert_eq!(3, games.len());
     assert_eq!(8, games[0].score());
@@ -183,7 +201,7 @@ A Y
 B X
 C Z
 "###;
-    let games = parse_input_p2(input);
+    let games = parse_input_p2(input).unwrap();
 
     assert_eq!(3, games.len());
     assert_eq!(4, games[0].score());
@@ -191,6 +209,23 @@ C Z
     assert_eq!(7, games[2].score());
 }
 
+fn test_parse_errors() {
+    let err = parse_input_p1("A Y\nD X").unwrap_err();
+    assert_eq!(
+        (2, 1, "'A', 'B', 'C', 'X', 'Y' or 'Z'"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_input_p2("A Y\nB A").unwrap_err();
+    assert_eq!(
+        (2, 3, "'X', 'Y' or 'Z'"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_input_p2("A Y\nBX").unwrap_err();
+    assert_eq!((2, 3, " "), (err.line, err.column, err.expected));
+}
+
 const INPUT: &'static str = r###"
 B Z
 A Z
@@ -1,3 +1,4 @@
+use crate::parse::{numbered_lines, ParseError};
 use crate::util::Day;
 
 type Map<K, V> = heapless::FnvIndexMap<K, V, 128>;
@@ -6,28 +7,29 @@ pub const DAY_03: Day<i32> = Day {
     year: 2022,
     day: 3,
     parts: &[part1, part2],
-    tests: &[do_test_1, do_test_2, do_test_3],
+    tests: &[do_test_1, do_test_2, do_test_3, test_parse_errors],
 };
 
-fn part1() -> i32 {
+fn part1() -> Result<i32, ParseError<'static>> {
     return do_part1(INPUT);
 }
 
-fn part2() -> i32 {
+fn part2() -> Result<i32, ParseError<'static>> {
     return do_part2(INPUT);
 }
 
-fn do_part1(input: &str) -> i32 {
+fn do_part1(input: &str) -> Result<i32, ParseError> {
     let mut sum: i32 = 0;
 
-    for line in input.trim().lines() {
-        let bytes = line.as_bytes();
-        let middle_index = bytes.len() / 2;
-        let a = &bytes[0..middle_index];
-        let b = &bytes[middle_index..];
+    for (n, line) in numbered_lines(input) {
+        let items = line.trim();
+        if items.len() % 2 != 0 {
+            return Err(ParseError::at_end(n, line, "an even number of items"));
+        }
+        let middle_index = items.len() / 2;
 
-        let p1 = parse_ruckstack_string(a);
-        let p2 = parse_ruckstack_string(b);
+        let p1 = parse_ruckstack_string(n, line, &items[0..middle_index])?;
+        let p2 = parse_ruckstack_string(n, line, &items[middle_index..])?;
 
         for (k, v) in p1 {
             if p2.contains_key(&k) {
@@ -35,18 +37,24 @@ fn do_part1(input: &str) -> i32 {
             }
         }
     }
-    return sum;
+    return Ok(sum);
 }
 
-fn do_part2(input: &str) -> i32 {
+fn do_part2<'a>(input: &'a str) -> Result<i32, ParseError<'a>> {
     let mut sum: i32 = 0;
-    let lines: heapless::Vec<&str, 512> = input.trim().lines().collect();
+    let lines: heapless::Vec<(usize, &str), 512> = numbered_lines(input).collect();
     let mut index: usize = 0;
 
     while index < lines.len() {
-        let a = parse_ruckstack_string(lines[index].as_bytes());
-        let b = parse_ruckstack_string(lines[index + 1].as_bytes());
-        let c = parse_ruckstack_string(lines[index + 2].as_bytes());
+        if index + 3 > lines.len() {
+            let (n, line) = lines[lines.len() - 1];
+            return Err(ParseError::at_end(n, line, "a group of three rucksacks"));
+        }
+
+        let parse = |(n, line): (usize, &'a str)| parse_ruckstack_string(n, line, line.trim());
+        let a = parse(lines[index])?;
+        let b = parse(lines[index + 1])?;
+        let c = parse(lines[index + 2])?;
 
         for (k, _) in a {
             if b.contains_key(&k) && c.contains_key(&k) {
@@ -58,7 +66,7 @@ fn do_part2(input: &str) -> i32 {
         index += 3;
     }
 
-    return sum;
+    return Ok(sum);
 }
 
 fn do_test_1() {
@@ -79,7 +87,7 @@ wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw    
 "###;
-    let res = do_part1(&input);
+    let res = do_part1(&input).unwrap();
 
     assert_eq!(157, res);
 }
@@ -93,11 +101,31 @@ fn do_test_3() {
     ttgJtRGJQctTZtZT
     CrZsJsPPZsGzwwsLwLmpwMDw    
     "###;
-    let res = do_part2(&input);
+    let res = do_part2(&input).unwrap();
 
     assert_eq!(70, res);
 }
 
+fn test_parse_errors() {
+    let err = do_part1("vJrwpWtwJgWrhcsFMMfFFhFp\nPmmdzqPrV-PwwTWBwg").unwrap_err();
+    assert_eq!(
+        (2, 10, "an item letter"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = do_part1("PmmdzqPrVvPwwTWBw").unwrap_err();
+    assert_eq!(
+        (1, 18, "an even number of items"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = do_part2("vJrwpWtwJgWrhcsFMMfFFhFp\n  jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL").unwrap_err();
+    assert_eq!(
+        (2, 35, "a group of three rucksacks"),
+        (err.line, err.column, err.expected)
+    );
+}
+
 fn get_priority_of_item(chr: u8) -> i32 {
     let res = match chr {
         b'a'..=b'z' => chr - 96,
@@ -107,11 +135,25 @@ fn get_priority_of_item(chr: u8) -> i32 {
     return res as i32;
 }
 
-fn parse_ruckstack_string(bytes: &[u8]) -> Map<u8, i32> {
+// "items" is a slice of "source_line", so that a bad item can be pointed at.
+fn parse_ruckstack_string<'a>(
+    line: usize,
+    source_line: &'a str,
+    items: &str,
+) -> Result<Map<u8, i32>, ParseError<'a>> {
     let mut res = Map::new();
+    let bytes = items.as_bytes();
 
     for i in 0..bytes.len() {
         let ch = bytes[i];
+        if !ch.is_ascii_alphabetic() {
+            return Err(ParseError::at_token(
+                line,
+                source_line,
+                &items[i..],
+                "an item letter",
+            ));
+        }
         let e = res.entry(ch);
 
         match e {
@@ -124,7 +166,7 @@ fn parse_ruckstack_string(bytes: &[u8]) -> Map<u8, i32> {
         }
     }
 
-    return res;
+    return Ok(res);
 }
 
 const INPUT: &'static str = r###"
@@ -1,34 +1,38 @@
 use heapless::Vec;
 
-use crate::{println, util::Day};
+use crate::{
+    parse::{numbered_lines, ParseError},
+    println, scan,
+    util::Day,
+};
 
 pub const DAY_04: Day<i32> = Day {
     year: 2022,
     day: 4,
     parts: &[do_part_1, do_part_2],
-    tests: &[test_parsing, test_overlaps_fully],
+    tests: &[test_parsing, test_parse_errors, test_overlaps_fully],
 };
 
-fn do_part_1() -> i32 {
-    let stuff = parse_input(INPUT);
+fn do_part_1() -> Result<i32, ParseError<'static>> {
+    let stuff = parse_input(INPUT)?;
     let fully_contained_things = stuff
         .iter()
         .map(|it| it.0.overlaps_fully(&it.1) || it.1.overlaps_fully(&it.0))
         .filter(|it| *it == true)
         .count();
 
-    return fully_contained_things as i32;
+    return Ok(fully_contained_things as i32);
 }
 
-fn do_part_2() -> i32 {
-    let stuff = parse_input(INPUT);
+fn do_part_2() -> Result<i32, ParseError<'static>> {
+    let stuff = parse_input(INPUT)?;
     let fully_contained_things = stuff
         .iter()
         .map(|it| it.0.overlaps_partly(&it.1) || it.1.overlaps_partly(&it.0))
         .filter(|it| *it == true)
         .count();
 
-    return fully_contained_things as i32;
+    return Ok(fully_contained_things as i32);
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -48,27 +52,24 @@ impl Ids {
     }
 }
 
-fn parse_input(input: &str) -> Vec<(Ids, Ids), 1024> {
+fn parse_input(input: &str) -> Result<Vec<(Ids, Ids), 1024>, ParseError> {
     let mut out: Vec<(Ids, Ids), 1024> = Vec::new();
 
-    for line in input.trim().lines() {
-        let mut buffer = [Ids { from: 0, to: 0 }; 2];
-        let mut i = 0;
-
-        for range in line.split(',') {
-            let (from, to) = range.split_once('-').unwrap();
-            let ids = Ids {
-                from: from.parse::<i32>().unwrap(),
-                to: to.parse::<i32>().unwrap(),
-            };
-            buffer[i] = ids;
-            i += 1;
-        }
+    for (n, line) in numbered_lines(input) {
+        let (from_a, to_a, from_b, to_b) = scan!((n, line), "{}-{},{}-{}", i32, i32, i32, i32)?;
+        let a = Ids {
+            from: from_a,
+            to: to_a,
+        };
+        let b = Ids {
+            from: from_b,
+            to: to_b,
+        };
 
-        out.push((buffer[0], buffer[1]));
+        out.push((a, b));
     }
 
-    return out;
+    return Ok(out);
 }
 
 fn test_parsing() {
@@ -80,7 +81,7 @@ fn test_parsing() {
 6-6,4-6
 2-6,4-8
 "#;
-    let parsed = parse_input(stuff);
+    let parsed = parse_input(stuff).unwrap();
 
     assert_eq!(6, parsed.len());
     assert_eq!(2, parsed[0].0.from);
@@ -89,6 +90,14 @@ fn test_parsing() {
     assert_eq!(8, parsed[0].1.to);
 }
 
+fn test_parse_errors() {
+    let err = parse_input("2-4,6-8\n2-3,4-x").unwrap_err();
+    assert_eq!((2, 7, "an integer"), (err.line, err.column, err.expected));
+
+    let err = parse_input("2-4 6-8").unwrap_err();
+    assert_eq!((1, 4, ","), (err.line, err.column, err.expected));
+}
+
 fn test_overlaps_fully() {
     let stuff = r#"
 2-4,6-8
@@ -98,7 +107,7 @@ fn test_overlaps_fully() {
 6-6,4-6
 2-6,4-8
 "#;
-    let parsed = parse_input(stuff);
+    let parsed = parse_input(stuff).unwrap();
     let contains_a = parsed[3].0.overlaps_fully(&parsed[3].1);
     let contains_b = parsed[3].1.overlaps_fully(&parsed[3].0);
 
@@ -1,4 +1,5 @@
-use crate::util::Day;
+use crate::parse::{numbered_lines, ParseError};
+use crate::{scan, util::Day};
 use heapless::Vec;
 
 type String = heapless::String<16>;
@@ -10,6 +11,7 @@ pub const DAY_05: Day<String> = Day {
     tests: &[
         test_parse_crates,
         test_parse_moves,
+        test_parse_move_errors,
         test_execute_moves_part_1,
         test_execute_moves_part_2,
     ],
@@ -28,20 +30,20 @@ struct Move {
     to: i32,
 }
 
-fn do_part_1() -> String {
+fn do_part_1() -> Result<String, ParseError<'static>> {
     let stacks = parse_crates::<9>(INPUT_CRATES);
-    let moves = parse_moves(INPUT_MOVES);
+    let moves = parse_moves(INPUT_MOVES)?;
     let res = execute_moves_part_1(&stacks, &moves);
 
-    return get_crates_on_top(&res);
+    return Ok(get_crates_on_top(&res));
 }
 
-fn do_part_2() -> String {
+fn do_part_2() -> Result<String, ParseError<'static>> {
     let stacks = parse_crates::<9>(INPUT_CRATES);
-    let moves = parse_moves(INPUT_MOVES);
+    let moves = parse_moves(INPUT_MOVES)?;
     let res = execute_moves_part_2(&stacks, &moves);
 
-    return get_crates_on_top(&res);
+    return Ok(get_crates_on_top(&res));
 }
 
 fn execute_moves_part_1(stack: &[CrateStack], moves: &[Move]) -> Vec<CrateStack, 16> {
@@ -125,14 +127,11 @@ fn parse_crates<const N: usize>(data: &str) -> Vec<CrateStack, N> {
     return stacks;
 }
 
-fn parse_moves(data: &str) -> Vec<Move, 512> {
+fn parse_moves(data: &str) -> Result<Vec<Move, 512>, ParseError> {
     let mut moves: Vec<Move, 512> = Vec::new();
 
-    for line in data.trim().lines() {
-        let parts: Vec<&str, 16> = line.split_whitespace().collect();
-        let num_crates: i32 = parts[1].parse().unwrap();
-        let from: i32 = parts[3].parse().unwrap();
-        let to: i32 = parts[5].parse().unwrap();
+    for (n, line) in numbered_lines(data) {
+        let (num_crates, from, to) = scan!((n, line), "move {} from {} to {}", i32, i32, i32)?;
         moves
             .push(Move {
                 num_crates,
@@ -142,7 +141,7 @@ fn parse_moves(data: &str) -> Vec<Move, 512> {
             .unwrap();
     }
 
-    return moves;
+    return Ok(moves);
 }
 
 fn get_crates_on_top(stacks: &[CrateStack]) -> String {
@@ -176,7 +175,7 @@ move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2
 "#;
-    let moves = parse_moves(stuff);
+    let moves = parse_moves(stuff).unwrap();
 
     assert_eq!(4, moves.len());
     assert_eq!(1, moves[0].num_crates);
@@ -184,6 +183,14 @@ move 1 from 1 to 2
     assert_eq!(1, moves[0].to);
 }
 
+fn test_parse_move_errors() {
+    let err = parse_moves("move 1 from 2 to 1\nmove three from 1 to 3").unwrap_err();
+    assert_eq!((2, 6, "an integer"), (err.line, err.column, err.expected));
+
+    let err = parse_moves("move 1 from 2 into 1").unwrap_err();
+    assert_eq!((1, 15, " to "), (err.line, err.column, err.expected));
+}
+
 fn test_execute_moves_part_1() {
     let in_crates = r#"
 [_] [D] [_]
@@ -200,7 +207,7 @@ move 1 from 1 to 2
 
     assert_eq!(3, stacks.len());
 
-    let moves = parse_moves(in_moves);
+    let moves = parse_moves(in_moves).unwrap();
     let res = execute_moves_part_1(&stacks, &moves);
 
     assert_eq!(&[b'C'], res[0].crates);
@@ -228,7 +235,7 @@ move 1 from 1 to 2
 
     assert_eq!(3, stacks.len());
 
-    let moves = parse_moves(in_moves);
+    let moves = parse_moves(in_moves).unwrap();
     let res = execute_moves_part_2(&stacks, &moves);
 
     assert_eq!(&[b'M'], res[0].crates);
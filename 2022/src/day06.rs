@@ -1,3 +1,4 @@
+use crate::parse::ParseError;
 use crate::util::Day;
 use heapless::Entry;
 use heapless::FnvIndexMap;
@@ -11,14 +12,14 @@ pub const DAY_06: Day<i32> = Day {
     tests: &[test_find_marker],
 };
 
-fn do_part_1() -> i32 {
+fn do_part_1() -> Result<i32, ParseError<'static>> {
     let marker = find_start_marker::<4>(INPUT);
-    return marker.unwrap_or(0);
+    return Ok(marker.unwrap_or(0));
 }
 
-fn do_part_2() -> i32 {
+fn do_part_2() -> Result<i32, ParseError<'static>> {
     let marker = find_start_marker::<14>(INPUT);
-    return marker.unwrap_or(0);
+    return Ok(marker.unwrap_or(0));
 }
 
 fn find_start_marker<const N: usize>(value: &str) -> Option<i32> {
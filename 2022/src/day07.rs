@@ -1,4 +1,8 @@
-use crate::{println, util::Day};
+use crate::{
+    parse::{numbered_lines, parse_token, ParseError},
+    println, scan,
+    util::Day,
+};
 
 type Vec<T, const N: usize> = heapless::Vec<T, N>;
 
@@ -6,18 +10,18 @@ pub const DAY_07: Day<i32> = Day {
     year: 2022,
     day: 7,
     parts: &[do_part_1, do_part_2],
-    tests: &[test_parse_stuff, test_max_part1],
+    tests: &[test_parse_stuff, test_parse_errors, test_max_part1],
 };
 
-fn do_part_1() -> i32 {
-    let nodes = parse_input(INPUT);
+fn do_part_1() -> Result<i32, ParseError<'static>> {
+    let nodes = parse_input(INPUT)?;
     let sz = find_total_size_of_nodes_with_maximum_size(&nodes[0], 100_000, &nodes);
 
-    return sz;
+    return Ok(sz);
 }
 
-fn do_part_2() -> i32 {
-    let nodes = parse_input(INPUT);
+fn do_part_2() -> Result<i32, ParseError<'static>> {
+    let nodes = parse_input(INPUT)?;
     let sz = nodes[0].size(&nodes);
     let free_space = 70_000_000 - sz;
     let needed_space = crate::util::max(30_000_000 - free_space, 0);
@@ -28,7 +32,7 @@ fn do_part_2() -> i32 {
 
     dirs.sort_unstable_by(|a, b| a.size(&nodes).cmp(&b.size(&nodes)));
 
-    return dirs[0].size(&nodes);
+    return Ok(dirs[0].size(&nodes));
 }
 
 #[derive(Debug, Clone)]
@@ -110,40 +114,51 @@ fn find_directories_of_minimum_size<'a>(
     }
 }
 
-fn parse_input(data: &str) -> Vec<Node, 512> {
+// the path only ever holds directories, since "cd" refuses to enter a file.
+fn parse_input(data: &str) -> Result<Vec<Node, 512>, ParseError> {
     let mut nodes: Vec<Node, 512> = Vec::new();
     let root = Node::Directory("/", Vec::new());
 
     nodes.push(root).unwrap();
 
     let mut path: Vec<usize, 64> = Vec::new();
-    let lines: Vec<&str, 2048> = data.trim().lines().collect();
+    path.push(0).unwrap();
+
+    let lines: Vec<(usize, &str), 2048> = numbered_lines(data).collect();
     let mut line_index: usize = 0;
 
     while line_index < lines.len() {
-        let line = lines[line_index];
+        let (n, line) = lines[line_index];
 
         if line.starts_with("$ cd") {
-            let arg = &line[5..];
+            let (arg,) = scan!((n, line), "$ cd {}", &str)?;
 
             match arg {
                 "/" => {
-                    path.clear();
-                    path.push(0).unwrap();
+                    path.truncate(1);
                 }
                 ".." => {
-                    path.pop().unwrap();
+                    if path.len() == 1 {
+                        return Err(ParseError::at_token(
+                            n,
+                            line,
+                            arg,
+                            "a directory below the root",
+                        ));
+                    }
+                    path.pop();
                 }
                 _ => {
                     let current_node_index = *path.last().unwrap();
                     let current_node = &nodes[current_node_index];
 
-                    if let Some(child_index) =
-                        current_node.find_index_of_child_with_name(arg, &nodes)
-                    {
-                        path.push(child_index).unwrap();
-                    } else {
-                        panic!("Child node with name '{}' does not exist.", arg);
+                    match current_node.find_index_of_child_with_name(arg, &nodes) {
+                        Some(child_index) if matches!(nodes[child_index], Node::Directory(..)) => {
+                            path.push(child_index).unwrap();
+                        }
+                        _ => {
+                            return Err(ParseError::at_token(n, line, arg, "a listed directory"));
+                        }
                     }
                 }
             }
@@ -152,38 +167,40 @@ fn parse_input(data: &str) -> Vec<Node, 512> {
         } else if line.starts_with("$ ls") {
             line_index += 1;
 
-            while line_index < lines.len() && !lines[line_index].starts_with("$") {
-                let next_line = lines[line_index];
-                let parts: Vec<&str, 8> = next_line.split_whitespace().collect();
+            while line_index < lines.len() && !lines[line_index].1.starts_with("$") {
+                let (n, next_line) = lines[line_index];
+                let (size, name) = scan!((n, next_line), "{} {}", &str, &str)?;
                 let current_node_index = *path.last().unwrap();
 
-                let n: Node = match parts[0] {
-                    "dir" => Node::Directory(parts[1], Vec::new()),
-                    _ => Node::File(parts[1], parts[0].parse().unwrap()),
+                let node: Node = match size {
+                    "dir" => Node::Directory(name, Vec::new()),
+                    _ => Node::File(
+                        name,
+                        parse_token(n, next_line, size, "'dir' or a file size")?,
+                    ),
                 };
 
-                nodes.push(n).unwrap();
+                nodes.push(node).unwrap();
 
                 let child_node_index = nodes.len() - 1;
-                let current_node = &mut nodes[current_node_index];
 
-                match current_node {
-                    Node::Directory(_, child_indices) => {
-                        child_indices.push(child_node_index).unwrap();
-                    }
-                    Node::File(_, _) => {
-                        panic!("Cannot add a child to a file node.");
-                    }
+                if let Node::Directory(_, child_indices) = &mut nodes[current_node_index] {
+                    child_indices.push(child_node_index).unwrap();
                 }
 
                 line_index += 1;
             }
         } else {
-            panic!("Bad line! {}", line);
+            return Err(ParseError::at_token(
+                n,
+                line,
+                line.trim(),
+                "'$ cd' or '$ ls'",
+            ));
         }
     }
 
-    return nodes;
+    return Ok(nodes);
 }
 
 fn test_parse_stuff() {
@@ -212,11 +229,37 @@ $ ls
 5626152 d.ext
 7214296 k
     "#;
-    let nodes = parse_input(data);
+    let nodes = parse_input(data).unwrap();
 
     assert_eq!(48_381_165, nodes[0].size(&nodes));
 }
 
+fn test_parse_errors() {
+    let err = parse_input("$ cd /\n$ ls\n14848514 b.txt\n$ cd b.txt").unwrap_err();
+    assert_eq!(
+        (4, 6, "a listed directory"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_input("$ cd /\n$ ls\n14.5 b.txt").unwrap_err();
+    assert_eq!(
+        (3, 1, "'dir' or a file size"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_input("$ cd ..").unwrap_err();
+    assert_eq!(
+        (1, 6, "a directory below the root"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_input("$ cd /\ndir a").unwrap_err();
+    assert_eq!(
+        (2, 1, "'$ cd' or '$ ls'"),
+        (err.line, err.column, err.expected)
+    );
+}
+
 fn test_max_part1() {
     let data = r#"
 $ cd /
@@ -243,7 +286,7 @@ $ ls
 5626152 d.ext
 7214296 k
     "#;
-    let nodes = parse_input(data);
+    let nodes = parse_input(data).unwrap();
     let tot = find_total_size_of_nodes_with_maximum_size(&nodes[0], 100_000, &nodes);
 
     assert_eq!(95_437, tot);
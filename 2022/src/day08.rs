@@ -1,4 +1,4 @@
-use crate::{println, util::Day};
+use crate::{parse::ParseError, println, util::Day};
 use heapless::Vec;
 
 pub const DAY_08: Day<i32> = Day {
@@ -8,13 +8,13 @@ pub const DAY_08: Day<i32> = Day {
     tests: &[test_parsing, test_count_visible, test_visibility_score],
 };
 
-fn do_part_1() -> i32 {
+fn do_part_1() -> Result<i32, ParseError<'static>> {
     let trees = parse_trees::<99, 99>(INPUT);
     let visible = trees.count_visible();
-    return visible;
+    return Ok(visible);
 }
 
-fn do_part_2() -> i32 {
+fn do_part_2() -> Result<i32, ParseError<'static>> {
     let trees = parse_trees::<99, 99>(INPUT);
     let mut max: i32 = 0;
 
@@ -24,7 +24,7 @@ fn do_part_2() -> i32 {
         }
     }
 
-    return max;
+    return Ok(max);
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1,14 +1,20 @@
-use crate::util::Day;
+use crate::parse::{numbered_lines, ParseError, ScanField};
+use crate::{scan, util::Day};
 use heapless::{FnvIndexSet, Vec};
 
 pub const DAY_09: Day<i32> = Day {
     year: 2022,
     day: 9,
     parts: &[do_part::<2>, do_part::<10>],
-    tests: &[parses_correctly, do_move_1, do_move_with_many_propagations],
+    tests: &[
+        parses_correctly,
+        test_parse_errors,
+        do_move_1,
+        do_move_with_many_propagations,
+    ],
 };
 
-fn do_part<const N: usize>() -> i32 {
+fn do_part<const N: usize>() -> Result<i32, ParseError<'static>> {
     let mut rope = Rope::<N>::new();
     let moves = parse_moves(INPUT);
 
@@ -20,6 +26,8 @@ fn do_part<const N: usize>() -> i32 {
     let mut visited_tail_points_b: FnvIndexSet<Point, 2048> = FnvIndexSet::new();
 
     for mv in moves {
+        let mv = mv?;
+
         for _ in 0..mv.steps {
             rope.move_to_direction(mv.direction);
 
@@ -41,26 +49,15 @@ fn do_part<const N: usize>() -> i32 {
         }
     }
 
-    return (visited_tail_points_a.len() + visited_tail_points_b.len()) as i32;
+    return Ok((visited_tail_points_a.len() + visited_tail_points_b.len()) as i32);
 }
 
-fn parse_moves<'a>(data: &'a str) -> impl Iterator<Item = Move> + 'a {
-    let lines = data.trim().lines();
-    let iter = crate::util::FnIterator::new(lines, |line| {
-        let (d, steps) = line.split_once(" ").unwrap();
-        let direction = match d {
-            "R" => Direction::Right,
-            "U" => Direction::Up,
-            "L" => Direction::Left,
-            "D" => Direction::Down,
-            _ => panic!("Bad {}", d),
-        };
-
-        let mv = Move {
-            direction,
-            steps: steps.parse().unwrap(),
-        };
-        return mv;
+fn parse_moves<'a>(data: &'a str) -> impl Iterator<Item = Result<Move, ParseError<'a>>> + 'a {
+    let lines = numbered_lines(data);
+    let iter = crate::util::FnIterator::new(lines, |(n, line)| {
+        let (direction, steps) = scan!((n, line), "{} {}", Direction, i16)?;
+        let mv = Move { direction, steps };
+        return Ok(mv);
     });
 
     return iter;
@@ -74,6 +71,27 @@ enum Direction {
     Left,
 }
 
+impl<'a> ScanField<'a> for Direction {
+    fn scan_field(
+        line: usize,
+        source_line: &'a str,
+        text: &'a str,
+    ) -> Result<Self, ParseError<'a>> {
+        return match text {
+            "R" => Ok(Direction::Right),
+            "U" => Ok(Direction::Up),
+            "L" => Ok(Direction::Left),
+            "D" => Ok(Direction::Down),
+            _ => Err(ParseError::at_token(
+                line,
+                source_line,
+                text,
+                "'R', 'U', 'L' or 'D'",
+            )),
+        };
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct Move {
     direction: Direction,
@@ -186,15 +204,26 @@ R 2
 "#;
 
 fn parses_correctly() {
-    let moves: Vec<Move, 16> = parse_moves(TEST_INPUT).collect();
+    let moves: Vec<Move, 16> = parse_moves(TEST_INPUT).collect::<Result<_, _>>().unwrap();
 
     assert_eq!(8, moves.len());
     assert_eq!(Direction::Right, moves[0].direction);
     assert_eq!(4, moves[0].steps);
 }
 
+fn test_parse_errors() {
+    let err = parse_moves("R 4\nX 4").last().unwrap().unwrap_err();
+    assert_eq!(
+        (2, 1, "'R', 'U', 'L' or 'D'"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_moves("R 4\nU four").last().unwrap().unwrap_err();
+    assert_eq!((2, 3, "an integer"), (err.line, err.column, err.expected));
+}
+
 fn do_move_1() {
-    let moves: Vec<Move, 16> = parse_moves(TEST_INPUT).collect();
+    let moves: Vec<Move, 16> = parse_moves(TEST_INPUT).collect::<Result<_, _>>().unwrap();
     let mut rope = Rope::<2>::new();
 
     rope.move_steps(moves[0]);
@@ -215,7 +244,7 @@ fn do_move_1() {
 }
 
 fn do_move_with_many_propagations() {
-    let moves: Vec<Move, 16> = parse_moves(TEST_INPUT).collect();
+    let moves: Vec<Move, 16> = parse_moves(TEST_INPUT).collect::<Result<_, _>>().unwrap();
     let mut rope = Rope::<10>::new();
 
     rope.move_steps(moves[0]);
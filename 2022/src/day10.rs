@@ -1,5 +1,7 @@
 use crate::{
-    println,
+    ocr,
+    parse::{numbered_lines, ParseError},
+    println, scan,
     util::{Day, FnIterator},
 };
 use heapless::Vec;
@@ -10,9 +12,11 @@ pub const DAY_10: Day<heapless::String<512>> = Day {
     parts: &[do_part_1, do_part_2],
     tests: &[
         test_parsing,
+        test_parse_errors,
         test_execute_cycles_one_at_a_time,
         test_execute_cycles_batched,
         test_get_signal_strength,
+        test_read_crt,
     ],
 };
 
@@ -31,34 +35,44 @@ impl Instruction {
     }
 }
 
-fn parse_instructions<'a>(data: &'a str) -> impl Iterator<Item = Instruction> + 'a {
-    let lines = data.trim().lines();
-    let iter = FnIterator::new(lines, |line| {
-        let parts: Vec<&str, 8> = line.split_whitespace().collect();
-        let to_match: &[&str] = &parts;
-
-        return match to_match {
-            ["noop", ..] => Instruction::Noop,
-            ["addx", arg, ..] => {
-                let value: i32 = arg.parse().unwrap();
-                Instruction::Addx(value)
-            }
-            _ => panic!("Unknown instruction: {:?}", parts),
-        };
+fn parse_instructions<'a>(
+    data: &'a str,
+) -> impl Iterator<Item = Result<Instruction, ParseError<'a>>> + 'a {
+    let lines = numbered_lines(data);
+    let iter = FnIterator::new(lines, |(n, line)| {
+        let text = line.trim();
+
+        if text == "noop" {
+            return Ok(Instruction::Noop);
+        }
+        if text.starts_with("addx") {
+            let (value,) = scan!((n, line), "addx {}", i32)?;
+            return Ok(Instruction::Addx(value));
+        }
+        return Err(ParseError::at_token(n, line, text, "'noop' or 'addx'"));
     });
 
     return iter;
 }
 
-fn do_part_1() -> heapless::String<512> {
-    let instructions: Vec<Instruction, 256> = parse_instructions(INPUT).collect();
+fn do_part_1() -> Result<heapless::String<512>, ParseError<'static>> {
+    let instructions: Vec<Instruction, 256> =
+        parse_instructions(INPUT).collect::<Result<_, _>>()?;
     let sum = get_signal_strength_after_220_cycles(instructions);
 
-    return heapless::String::from(sum);
+    return Ok(heapless::String::from(sum));
 }
 
-fn do_part_2() -> heapless::String<512> {
-    let instructions: Vec<Instruction, 256> = parse_instructions(INPUT).collect();
+fn do_part_2() -> Result<heapless::String<512>, ParseError<'static>> {
+    let instructions: Vec<Instruction, 256> =
+        parse_instructions(INPUT).collect::<Result<_, _>>()?;
+    let crt = draw_crt(instructions);
+
+    // fall back to the raw drawing if the letters cannot be read.
+    return Ok(ocr::recognize_text(&crt).unwrap_or(crt));
+}
+
+fn draw_crt<const N: usize>(instructions: Vec<Instruction, N>) -> heapless::String<512> {
     let mut machine = Machine::new(instructions);
     let mut pixels = ['.'; 240];
 
@@ -90,8 +104,26 @@ fn do_part_2() -> heapless::String<512> {
     return s;
 }
 
+fn test_read_crt() {
+    let instructions: heapless::Vec<Instruction, 256> = parse_instructions(TEST_INPUT_2)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let crt = draw_crt(instructions);
+
+    assert_eq!(
+        "##..##..##..##..##..##..##..##..##..##..",
+        crt.lines().nth(1).unwrap()
+    );
+
+    // the example does not draw any letters.
+    let res: Option<heapless::String<16>> = ocr::recognize_text(&crt);
+    assert_eq!(None, res);
+}
+
 fn test_parsing() {
-    let instructions: heapless::Vec<Instruction, 16> = parse_instructions(TEST_INPUT_1).collect();
+    let instructions: heapless::Vec<Instruction, 16> = parse_instructions(TEST_INPUT_1)
+        .collect::<Result<_, _>>()
+        .unwrap();
 
     assert_eq!(
         [
@@ -103,6 +135,23 @@ fn test_parsing() {
     );
 }
 
+fn test_parse_errors() {
+    let err = parse_instructions("noop\naddx -x")
+        .last()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!((2, 6, "an integer"), (err.line, err.column, err.expected));
+
+    let err = parse_instructions("noop\n  mulx 3")
+        .last()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        (2, 3, "'noop' or 'addx'"),
+        (err.line, err.column, err.expected)
+    );
+}
+
 struct Machine<const N: usize> {
     current_instruction: Option<Instruction>,
     executed_cycles: i32,
@@ -171,7 +220,9 @@ fn get_signal_strength_after_220_cycles<const N: usize>(instructions: Vec<Instru
 }
 
 fn test_execute_cycles_one_at_a_time() {
-    let instructions: heapless::Vec<Instruction, 16> = parse_instructions(TEST_INPUT_1).collect();
+    let instructions: heapless::Vec<Instruction, 16> = parse_instructions(TEST_INPUT_1)
+        .collect::<Result<_, _>>()
+        .unwrap();
     let mut machine = Machine::new(instructions);
 
     machine.execute_cycles(1);
@@ -191,7 +242,9 @@ fn test_execute_cycles_one_at_a_time() {
 }
 
 fn test_execute_cycles_batched() {
-    let instructions: heapless::Vec<Instruction, 16> = parse_instructions(TEST_INPUT_1).collect();
+    let instructions: heapless::Vec<Instruction, 16> = parse_instructions(TEST_INPUT_1)
+        .collect::<Result<_, _>>()
+        .unwrap();
     let mut machine = Machine::new(instructions);
 
     machine.execute_cycles(5);
@@ -199,7 +252,9 @@ fn test_execute_cycles_batched() {
 }
 
 fn test_get_signal_strength() {
-    let instructions: heapless::Vec<Instruction, 256> = parse_instructions(TEST_INPUT_2).collect();
+    let instructions: heapless::Vec<Instruction, 256> = parse_instructions(TEST_INPUT_2)
+        .collect::<Result<_, _>>()
+        .unwrap();
     let sum = get_signal_strength_after_220_cycles(instructions);
 
     assert_eq!(13_140, sum);
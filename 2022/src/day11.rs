@@ -1,30 +1,39 @@
-use crate::{println, util::Day};
+use crate::{
+    parse::{numbered_lines, parse_token, ParseError, ScanField},
+    println, scan,
+    util::Day,
+};
 use heapless::{FnvIndexSet, Vec};
 
 pub const DAY_11: Day<i64> = Day {
     year: 2022,
     day: 11,
     parts: &[do_part_1, do_part_2],
-    tests: &[test_parsing, test_simluate, test_simluate_part_2],
+    tests: &[
+        test_parsing,
+        test_parse_errors,
+        test_simluate,
+        test_simluate_part_2,
+    ],
 };
 
-fn do_part_1() -> i64 {
-    let mut monkeys = parse_monkeys(INPUT);
+fn do_part_1() -> Result<i64, ParseError<'static>> {
+    let mut monkeys = parse_monkeys(INPUT)?;
     do_monkey_simulation(&mut monkeys, 20, &mut |w| w / 3);
 
     monkeys.sort_unstable_by(|a, b| b.inspected_items.cmp(&a.inspected_items));
 
-    return (monkeys[0].inspected_items as i64) * (monkeys[1].inspected_items as i64);
+    return Ok((monkeys[0].inspected_items as i64) * (monkeys[1].inspected_items as i64));
 }
 
-fn do_part_2() -> i64 {
-    let mut monkeys = parse_monkeys(INPUT);
+fn do_part_2() -> Result<i64, ParseError<'static>> {
+    let mut monkeys = parse_monkeys(INPUT)?;
     let factor: i64 = monkeys.iter().map(|m| m.test_divisible_by).product();
     do_monkey_simulation(&mut monkeys, 10_000, &mut |w| w % factor);
 
     monkeys.sort_unstable_by(|a, b| b.inspected_items.cmp(&a.inspected_items));
 
-    return (monkeys[0].inspected_items as i64) * (monkeys[1].inspected_items as i64);
+    return Ok((monkeys[0].inspected_items as i64) * (monkeys[1].inspected_items as i64));
 }
 
 fn do_monkey_simulation<F: FnMut(i64) -> i64>(
@@ -98,96 +107,87 @@ enum Argument {
     Integer(i64),
 }
 
-const PATTERN_MONKEY: &'static str = "Monkey ";
-const PATTERN_STARTING_ITEMS: &'static str = "Starting items: ";
-const PATTERN_OPERATION: &'static str = "Operation: new = ";
-const PATTERN_TEST_DIVISIBLE_BY: &'static str = "Test: divisible by ";
-const PATTERN_TEST_IF_TRUE: &'static str = "If true: throw to monkey ";
-const PATTERN_TEST_IF_FALSE: &'static str = "If false: throw to monkey ";
+impl<'a> ScanField<'a> for Argument {
+    fn scan_field(
+        line: usize,
+        source_line: &'a str,
+        text: &'a str,
+    ) -> Result<Self, ParseError<'a>> {
+        return match text {
+            "old" => Ok(Argument::Old),
+            _ => Ok(Argument::Integer(parse_token(
+                line,
+                source_line,
+                text,
+                "'old' or an integer",
+            )?)),
+        };
+    }
+}
 
-fn parse_monkeys(data: &str) -> heapless::Vec<Monkey, 16> {
-    let lines = data.trim().lines();
+fn parse_monkeys(data: &str) -> Result<heapless::Vec<Monkey, 16>, ParseError> {
     let mut monkeys: Vec<Monkey, 16> = Vec::new();
     let mut current_monkey: Option<Monkey> = None;
 
-    for line in lines {
-        let line = line.trim();
+    for (n, raw_line) in numbered_lines(data) {
+        let line = raw_line.trim();
+        let input = (n, raw_line);
 
-        if line.starts_with(PATTERN_MONKEY) {
+        if line.starts_with("Monkey ") {
             if let Some(m) = current_monkey {
                 monkeys.push(m).unwrap();
             }
 
             let mut m = Monkey::new();
-            let len = PATTERN_MONKEY.len();
-            let num: i32 = line[len..(len + 1)].parse().unwrap();
-            m.number = num;
+            (m.number,) = scan!(input, "Monkey {}:", i32)?;
             current_monkey = Some(m);
-        } else if line.starts_with(PATTERN_STARTING_ITEMS) {
-            let items: Vec<i64, 64> = line[PATTERN_STARTING_ITEMS.len()..]
-                .split(',')
-                .map(|n| n.trim().parse().unwrap())
-                .collect();
-
-            match current_monkey.as_mut() {
-                Some(m) => m.items = items,
-                None => panic!("No monkey!"),
-            };
-        } else if line.starts_with(PATTERN_OPERATION) {
-            let len = PATTERN_OPERATION.len();
-            let parts: Vec<&str, 4> = line[len..].split_whitespace().map(|pt| pt.trim()).collect();
-            let arg_1: Argument = match parts[0] {
-                "old" => Argument::Old,
-                _ => {
-                    let parsed: i64 = parts[0].parse().unwrap();
-                    Argument::Integer(parsed)
-                }
-            };
-            let arg_2: Argument = match parts[2] {
-                "old" => Argument::Old,
-                _ => {
-                    let parsed: i64 = parts[2].parse().unwrap();
-                    Argument::Integer(parsed)
-                }
-            };
-            let op: Operation = match parts[1] {
-                "+" => Operation::Add(arg_1, arg_2),
-                "*" => Operation::Multiply(arg_1, arg_2),
-                _ => panic!("Bad operation! {}", parts[1]),
-            };
-
-            match current_monkey.as_mut() {
-                Some(m) => m.operation = op,
-                None => panic!("No monkey!"),
-            };
-        } else if line.starts_with(PATTERN_TEST_DIVISIBLE_BY) {
-            let len = PATTERN_TEST_DIVISIBLE_BY.len();
-            let num = line[len..].parse().unwrap();
-
-            match current_monkey.as_mut() {
-                Some(m) => m.test_divisible_by = num,
-                None => panic!("No monkey!"),
-            };
-        } else if line.starts_with(PATTERN_TEST_IF_TRUE) {
-            let len = PATTERN_TEST_IF_TRUE.len();
-            let num = line[len..].parse().unwrap();
-
-            match current_monkey.as_mut() {
-                Some(m) => m.throw_to_if_true = num,
-                None => panic!("No monkey!"),
-            };
-        } else if line.starts_with(PATTERN_TEST_IF_FALSE) {
-            let len = PATTERN_TEST_IF_FALSE.len();
-            let num = line[len..].parse().unwrap();
-
-            match current_monkey.as_mut() {
-                Some(m) => m.throw_to_if_false = num,
-                None => panic!("No monkey!"),
-            };
-        } else if line == "" {
-            // do nothing.
-        } else {
-            panic!("Unrecognized line:\n{}", line);
+            continue;
+        }
+
+        let m = match current_monkey.as_mut() {
+            Some(m) => m,
+            None => {
+                return Err(ParseError::at_token(
+                    n,
+                    raw_line,
+                    line,
+                    "'Monkey <number>:'",
+                ))
+            }
+        };
+
+        let attribute = line.split(':').next().unwrap();
+
+        match attribute {
+            "Starting items" => {
+                (m.items,) = scan!(input, "Starting items: {,}", Vec<i64, 64>)?;
+            }
+            "Operation" => {
+                let (arg_1, op, arg_2) =
+                    scan!(input, "Operation: new = {} {} {}", Argument, &str, Argument)?;
+                m.operation = match op {
+                    "+" => Operation::Add(arg_1, arg_2),
+                    "*" => Operation::Multiply(arg_1, arg_2),
+                    _ => return Err(ParseError::at_token(n, raw_line, op, "'+' or '*'")),
+                };
+            }
+            "Test" => {
+                (m.test_divisible_by,) = scan!(input, "Test: divisible by {}", i64)?;
+            }
+            "If true" => {
+                (m.throw_to_if_true,) = scan!(input, "If true: throw to monkey {}", i32)?;
+            }
+            "If false" => {
+                (m.throw_to_if_false,) = scan!(input, "If false: throw to monkey {}", i32)?;
+            }
+            _ => {
+                return Err(ParseError::at_token(
+                    n,
+                    raw_line,
+                    line,
+                    "a monkey attribute",
+                ))
+            }
         }
     }
 
@@ -195,11 +195,11 @@ fn parse_monkeys(data: &str) -> heapless::Vec<Monkey, 16> {
         monkeys.push(m).unwrap();
     }
 
-    return monkeys;
+    return Ok(monkeys);
 }
 
 fn test_parsing() {
-    let monkeys = parse_monkeys(INPUT);
+    let monkeys = parse_monkeys(INPUT).unwrap();
 
     assert_eq!(8, monkeys.len());
     assert_eq!([85, 77, 77], monkeys[0].items);
@@ -214,8 +214,28 @@ fn test_parsing() {
     assert_eq!([50, 66, 61, 92, 64, 78], monkeys[7].items);
 }
 
+fn test_parse_errors() {
+    let err = parse_monkeys("Monkey 0:\n  Operation: new = old ^ 2").unwrap_err();
+    assert_eq!((2, 24, "'+' or '*'"), (err.line, err.column, err.expected));
+
+    let err = parse_monkeys("\n  Test: divisible by 3").unwrap_err();
+    assert_eq!(
+        (2, 3, "'Monkey <number>:'"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_monkeys("Monkey 0:\n  Starting items: 1, x, 3").unwrap_err();
+    assert_eq!((2, 22, "an integer"), (err.line, err.column, err.expected));
+
+    let err = parse_monkeys("Monkey 0:\n  Jump: to monkey 3").unwrap_err();
+    assert_eq!(
+        (2, 3, "a monkey attribute"),
+        (err.line, err.column, err.expected)
+    );
+}
+
 fn test_simluate() {
-    let mut monkeys = parse_monkeys(TEST_INPUT_BRUH);
+    let mut monkeys = parse_monkeys(TEST_INPUT_BRUH).unwrap();
 
     do_monkey_simulation(&mut monkeys, 1, &mut |w| w / 3);
 
@@ -226,7 +246,7 @@ fn test_simluate() {
 }
 
 fn test_simluate_part_2() {
-    let mut monkeys = parse_monkeys(TEST_INPUT_BRUH);
+    let mut monkeys = parse_monkeys(TEST_INPUT_BRUH).unwrap();
     let factor: i64 = monkeys.iter().map(|m| m.test_divisible_by).product();
 
     do_monkey_simulation(&mut monkeys, 20, &mut |w| w % factor);
@@ -1,3 +1,4 @@
+use crate::parse::{numbered_lines, ParseError};
 use crate::println;
 use crate::util::Day;
 use heapless::BinaryHeap;
@@ -6,17 +7,17 @@ pub const DAY_12: Day<i16> = Day {
     year: 2022,
     day: 12,
     parts: &[do_part_1, do_part_2],
-    tests: &[test_parsing, test_find_shortest_path],
+    tests: &[test_parsing, test_parse_errors, test_find_shortest_path],
 };
 
-fn do_part_1() -> i16 {
-    let map = parse_map::<114, 41>(INPUT);
+fn do_part_1() -> Result<i16, ParseError<'static>> {
+    let map = parse_map::<114, 41>(INPUT)?;
     let path = find_steps_of_shortest_path(map, map.start);
-    return path;
+    return Ok(path);
 }
 
-fn do_part_2() -> i16 {
-    let map = parse_map::<114, 41>(INPUT);
+fn do_part_2() -> Result<i16, ParseError<'static>> {
+    let map = parse_map::<114, 41>(INPUT)?;
     let mut min = i16::MAX;
     let mut i = 0;
 
@@ -36,7 +37,7 @@ fn do_part_2() -> i16 {
         }
     }
 
-    return min;
+    return Ok(min);
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -102,33 +103,59 @@ fn to_height(ch: char) -> i8 {
     return ((ch as u8) - b'a') as i8;
 }
 
-fn parse_map<const X: usize, const Y: usize>(data: &str) -> HeightMap<X, Y> {
-    let lines = data.trim().lines();
+// the map has to be exactly X squares wide and Y squares high.
+fn parse_map<const X: usize, const Y: usize>(data: &str) -> Result<HeightMap<X, Y>, ParseError> {
     let mut nodes = [[Node::ZERO; X]; Y];
     let mut y: i8 = 0;
     let mut start: Option<Point> = None;
     let mut end: Option<Point> = None;
+    let mut last = (1, "");
 
-    for line in lines {
+    for (n, line) in numbered_lines(data) {
+        let row = line.trim();
         let mut x: i8 = 0;
 
-        for ch in line.trim().chars() {
+        if y as usize == Y {
+            return Err(ParseError::at_token(n, line, row, "the end of the map"));
+        }
+
+        for (i, ch) in row.char_indices() {
             let is_start = ch == 'S';
             let is_end = ch == 'E';
             let height: i8;
 
+            if x as usize == X {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    &row[i..],
+                    "the end of the row",
+                ));
+            }
+
             if is_start {
-                assert_eq!(None, start);
+                if start.is_some() {
+                    return Err(ParseError::at_token(n, line, &row[i..], "a single 'S'"));
+                }
 
                 height = to_height('a');
                 start = Some(Point::new(x, y));
             } else if is_end {
-                assert_eq!(None, end);
+                if end.is_some() {
+                    return Err(ParseError::at_token(n, line, &row[i..], "a single 'E'"));
+                }
 
                 height = to_height('z');
                 end = Some(Point::new(x, y));
-            } else {
+            } else if ch.is_ascii_lowercase() {
                 height = to_height(ch);
+            } else {
+                return Err(ParseError::at_token(
+                    n,
+                    line,
+                    &row[i..],
+                    "a height from 'a' to 'z'",
+                ));
             }
 
             nodes[y as usize][x as usize] = Node {
@@ -141,17 +168,22 @@ fn parse_map<const X: usize, const Y: usize>(data: &str) -> HeightMap<X, Y> {
             x += 1;
         }
 
-        assert_eq!(X, x as usize);
+        if x as usize != X {
+            return Err(ParseError::at_end(n, line, "a longer row"));
+        }
+        last = (n, line);
 
         y += 1;
     }
 
-    assert_eq!(Y, y as usize);
+    if y as usize != Y {
+        return Err(ParseError::at_end(last.0, last.1, "another row"));
+    }
 
-    return HeightMap {
-        nodes: nodes,
-        start: start.unwrap(),
-        end: end.unwrap(),
+    return match (start, end) {
+        (Some(start), Some(end)) => Ok(HeightMap { nodes, start, end }),
+        (None, _) => Err(ParseError::at_end(last.0, last.1, "a start 'S'")),
+        (_, None) => Err(ParseError::at_end(last.0, last.1, "an end 'E'")),
     };
 }
 
@@ -233,54 +265,48 @@ fn find_steps_of_shortest_path<const X: usize, const Y: usize>(
     return map.get(map.end).distance;
 }
 
-fn path_to_string<const X: usize, const Y: usize>(path: &[Point]) -> heapless::String<512> {
-    let mut rows = [['.'; X]; Y];
-
-    for i in 0..path.len() {
-        let pt = path[i];
-        let maybe_next_pt = path.get(i + 1);
-        let ch: char;
-
-        if let Some(next_pt) = maybe_next_pt {
-            if next_pt.x > pt.x {
-                ch = '>';
-            } else if next_pt.x < pt.x {
-                ch = '<';
-            } else if next_pt.y > pt.y {
-                ch = 'v';
-            } else if next_pt.y < pt.y {
-                ch = '^';
-            } else {
-                panic!("Very bad!");
-            }
-        } else {
-            ch = 'E';
-        }
-        rows[pt.y as usize][pt.x as usize] = ch;
-    }
-
-    let mut s: heapless::String<512> = heapless::String::new();
-
-    for row in rows {
-        for ch in row {
-            s.push(ch).unwrap();
-        }
-        s.push_str("\n").unwrap();
-    }
-
-    return s;
-}
-
 fn test_parsing() {
-    let map = parse_map::<8, 5>(TEST_INPUT);
+    let map = parse_map::<8, 5>(TEST_INPUT).unwrap();
 
     assert_eq!(Point::new(0, 0), map.start);
     assert_eq!(0, map.nodes[0][0].height);
     assert_eq!(2, map.nodes[1][2].height);
 }
 
+fn test_parse_errors() {
+    let err = parse_map::<3, 2>("Sab\nc-E").unwrap_err();
+    assert_eq!(
+        (2, 2, "a height from 'a' to 'z'"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_map::<3, 2>("SaE\ncbE").unwrap_err();
+    assert_eq!((2, 3, "a single 'E'"), (err.line, err.column, err.expected));
+
+    let err = parse_map::<3, 2>("Sab\ncdEf").unwrap_err();
+    assert_eq!(
+        (2, 4, "the end of the row"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_map::<3, 2>("Sab\ncd").unwrap_err();
+    assert_eq!((2, 3, "a longer row"), (err.line, err.column, err.expected));
+
+    let err = parse_map::<3, 2>("Sab\ncdE\nfgh").unwrap_err();
+    assert_eq!(
+        (3, 1, "the end of the map"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_map::<3, 2>("Sab").unwrap_err();
+    assert_eq!((1, 4, "another row"), (err.line, err.column, err.expected));
+
+    let err = parse_map::<3, 2>("Sab\ncde").unwrap_err();
+    assert_eq!((2, 4, "an end 'E'"), (err.line, err.column, err.expected));
+}
+
 fn test_find_shortest_path() {
-    let map = parse_map::<8, 5>(TEST_INPUT);
+    let map = parse_map::<8, 5>(TEST_INPUT).unwrap();
     let path = find_steps_of_shortest_path(map, map.start);
 
     assert_eq!(31, path);
@@ -1,3 +1,4 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
 use crate::println;
 use crate::util::Day;
 use crate::util::FnIterator;
@@ -19,6 +20,7 @@ pub const DAY_13: Day<i32> = Day {
         test_find_sum_of_verified_indices,
         test_verify_equal_length_lists,
         test_find_indices_of_divider_packets,
+        test_parse_errors,
         // test_sort,
     ],
 };
@@ -105,64 +107,73 @@ impl Ord for Packet {
     }
 }
 
-fn do_part_1() -> i32 {
+fn do_part_1() -> Result<i32, ParseError<'static>> {
     unsafe {
         A::grow(&mut MEMORY);
     }
-    let sum = find_indices_of_verified_pairs::<256>(INPUT).iter().sum();
+    let sum = find_indices_of_verified_pairs::<256>(INPUT)?.iter().sum();
 
-    return sum;
+    return Ok(sum);
 }
 
-fn do_part_2() -> i32 {
+fn do_part_2() -> Result<i32, ParseError<'static>> {
     unsafe {
         A::grow(&mut MEMORY);
     }
-    let (a, b) = find_indices_of_divider_packets::<512>(INPUT);
+    let (a, b) = find_indices_of_divider_packets::<512>(INPUT)?;
 
-    return a * b;
+    return Ok(a * b);
 }
 
 struct PacketIterator<'a> {
-    lines: core::str::Lines<'a>,
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+    last: (usize, &'a str),
 }
 
 impl<'a> PacketIterator<'a> {
     fn new(data: &'a str) -> Self {
         return Self {
-            lines: data.trim().lines(),
+            lines: data.lines().enumerate(),
+            last: (1, ""),
         };
     }
 }
 
 impl<'a> Iterator for PacketIterator<'a> {
-    type Item = Packet;
+    type Item = Result<Packet, ParseError<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut maybe_line = self.lines.next();
-        while maybe_line.is_some() && maybe_line.unwrap().trim() == "" {
+        while maybe_line.is_some() && maybe_line.unwrap().1.trim() == "" {
             maybe_line = self.lines.next();
         }
         return match maybe_line {
-            Some(line) => Some(read_value(line)),
+            Some((i, line)) => {
+                self.last = (i + 1, line);
+                Some(read_value(i + 1, line))
+            }
             None => None,
         };
     }
 }
 
-fn find_indices_of_verified_pairs<const N: usize>(data: &str) -> Vec<i32, N> {
+fn find_indices_of_verified_pairs<const N: usize>(data: &str) -> Result<Vec<i32, N>, ParseError> {
     let mut k: i32 = 1;
     let mut iter = PacketIterator::new(data);
     let mut out: Vec<i32, N> = Vec::new();
 
     while let Some(left) = iter.next() {
-        let maybe_right = iter.next();
-
-        if maybe_right == None {
-            panic!("Could not find right packet!");
-        }
-
-        let right = maybe_right.unwrap();
+        let left = left?;
+        let right = match iter.next() {
+            Some(right) => right?,
+            None => {
+                return Err(ParseError::at_end(
+                    iter.last.0,
+                    iter.last.1,
+                    "a right packet",
+                ))
+            }
+        };
 
         if left.cmp(&right) == core::cmp::Ordering::Less {
             out.push(k).unwrap();
@@ -171,21 +182,28 @@ fn find_indices_of_verified_pairs<const N: usize>(data: &str) -> Vec<i32, N> {
         k += 1;
     }
 
-    return out;
+    return Ok(out);
 }
 
 const DIVIDER_PACKET_1: &'static str = "[[2]]";
 const DIVIDER_PACKET_2: &'static str = "[[6]]";
 
-fn find_indices_of_divider_packets<const N: usize>(data: &str) -> (i32, i32) {
-    let mut lines: Vec<&str, N> = data.trim().lines().filter(|line| *line != "").collect();
+fn find_indices_of_divider_packets<const N: usize>(data: &str) -> Result<(i32, i32), ParseError> {
+    let mut lines: Vec<&str, N> = Vec::new();
+
+    // the packets are read again for every comparison, so check them all once
+    // up front to keep the sort itself from running into a bad one.
+    for (n, line) in numbered_lines(data) {
+        read_value(n, line)?;
+        lines.push(line).unwrap();
+    }
     lines.push(DIVIDER_PACKET_1).unwrap();
     lines.push(DIVIDER_PACKET_2).unwrap();
 
     lines.sort_unstable_by(|a, b| {
         // we don't have enough ram to keep the parsed values in memory.
-        let packet_a = read_value(a);
-        let packet_b = read_value(b);
+        let packet_a = read_value(1, a).expect("Packets are checked before sorting.");
+        let packet_b = read_value(1, b).expect("Packets are checked before sorting.");
 
         return packet_a.cmp(&packet_b);
     });
@@ -206,52 +224,68 @@ fn find_indices_of_divider_packets<const N: usize>(data: &str) -> (i32, i32) {
         }
     }
 
-    return (index_a.unwrap(), index_b.unwrap());
+    return Ok((index_a.unwrap(), index_b.unwrap()));
 }
 
-fn read_list(data: &str, start_index: usize) -> (Elements, usize) {
+// the readers below take the line number and the whole line, so that errors
+// can point into it, and an index into the line where reading starts.
+fn read_list(line: usize, data: &str, start_index: usize) -> Result<(Elements, usize), ParseError> {
     let bytes = data.as_bytes();
-    let start_index = skip_whitespace(data, start_index);
-
-    assert_eq!(b'[', bytes[start_index]);
+    let start_index = skip_whitespace(line, data, start_index)?;
+
+    if bytes[start_index] != b'[' {
+        return Err(ParseError::at_token(
+            line,
+            data,
+            &data[start_index..],
+            "'['",
+        ));
+    }
 
     if let Some(end_index) = find_closing_delimeter(data, start_index) {
         let mut elements = Elements::new();
         let mut k: usize = start_index + 1;
 
         while k < end_index {
-            let (el, next_index) = read_value_from_index(data, k);
-            elements.push(el).unwrap();
-            k = next_index;
+            let (el, next_index) = read_value_from_index(line, data, k)?;
+            if elements.push(el).is_err() {
+                return Err(ParseError::at_token(
+                    line,
+                    data,
+                    &data[k..],
+                    "a shorter list",
+                ));
+            }
+            k = skip_whitespace(line, data, next_index)?;
 
             if bytes[k] == b',' {
                 k += 1;
+            } else if k != end_index {
+                return Err(ParseError::at_token(line, data, &data[k..], "',' or ']'"));
             }
         }
 
-        return (elements, end_index + 1);
+        return Ok((elements, end_index + 1));
     }
 
-    panic!("Unclosed list at index = {}", start_index);
+    return Err(ParseError::at_end(line, data, "']'"));
 }
 
-fn skip_whitespace(data: &str, start_index: usize) -> usize {
+fn skip_whitespace(line: usize, data: &str, start_index: usize) -> Result<usize, ParseError> {
     let bytes = data.as_bytes();
 
     for k in start_index..bytes.len() {
         if !bytes[k].is_ascii_whitespace() {
-            return k;
+            return Ok(k);
         }
     }
 
-    panic!("Reached end of string...");
+    return Err(ParseError::at_end(line, data, "a value"));
 }
 
-fn read_integer(data: &str, start_index: usize) -> (i8, usize) {
+fn read_integer(line: usize, data: &str, start_index: usize) -> Result<(i8, usize), ParseError> {
     let bytes = data.as_bytes();
-    let start_index = skip_whitespace(data, start_index);
-
-    assert_eq!(true, bytes[start_index].is_ascii_digit());
+    let start_index = skip_whitespace(line, data, start_index)?;
 
     let mut k: usize = start_index;
 
@@ -262,30 +296,50 @@ fn read_integer(data: &str, start_index: usize) -> (i8, usize) {
         k += 1;
     }
 
-    let found = data[start_index..k].parse().unwrap();
-    return (found, k);
+    if k == start_index {
+        return Err(ParseError::at_token(
+            line,
+            data,
+            &data[start_index..],
+            "an integer or '['",
+        ));
+    }
+
+    let found = parse_token(line, data, &data[start_index..k], "an integer")?;
+    return Ok((found, k));
 }
 
-fn read_value_from_index(data: &str, start_index: usize) -> (Packet, usize) {
+fn read_value_from_index(
+    line: usize,
+    data: &str,
+    start_index: usize,
+) -> Result<(Packet, usize), ParseError> {
     let bytes = data.as_bytes();
-    let start_index = skip_whitespace(data, start_index);
+    let start_index = skip_whitespace(line, data, start_index)?;
 
     if bytes[start_index] == b'[' {
-        let (elements, next) = read_list(data, start_index);
+        let (elements, next) = read_list(line, data, start_index)?;
         let boxed = A::alloc().unwrap().init(elements);
-        return (Packet::List(boxed), next);
+        return Ok((Packet::List(boxed), next));
     }
 
-    let (int, next) = read_integer(data, start_index);
-    return (Packet::Integer(int), next);
+    let (int, next) = read_integer(line, data, start_index)?;
+    return Ok((Packet::Integer(int), next));
 }
 
-fn read_value(data: &str) -> Packet {
-    let (found, next_index) = read_value_from_index(data, 0);
+fn read_value(line: usize, data: &str) -> Result<Packet, ParseError> {
+    let (found, next_index) = read_value_from_index(line, data, 0)?;
 
-    assert_eq!(data.len(), next_index);
+    if data[next_index..].trim() != "" {
+        return Err(ParseError::at_token(
+            line,
+            data,
+            data[next_index..].trim_start(),
+            "the end of the line",
+        ));
+    }
 
-    return found;
+    return Ok(found);
 }
 
 fn find_closing_delimeter(data: &str, start_index: usize) -> Option<usize> {
@@ -328,12 +382,12 @@ fn packet_to_string<const N: usize>(value: &Packet, buffer: &mut heapless::Strin
 
 fn test_read_integer() {
     let list = "[42, 69]";
-    let (a, n) = read_integer(list, 1);
+    let (a, n) = read_integer(1, list, 1).unwrap();
 
     assert_eq!(42, a);
     assert_eq!(3, n);
 
-    let (b, n) = read_integer(list, 5);
+    let (b, n) = read_integer(1, list, 5).unwrap();
 
     assert_eq!(69, b);
     assert_eq!(7, n);
@@ -345,7 +399,7 @@ fn test_read_list_of_integers() {
     }
 
     let list = "[42, 69]";
-    let (elements, _) = read_list(list, 0);
+    let (elements, _) = read_list(1, list, 0).unwrap();
 
     assert_eq!(2, elements.len());
     assert_eq!(Packet::Integer(42), elements[0]);
@@ -370,7 +424,7 @@ fn test_read_nested_stuff() {
         .unwrap();
     let expected = Packet::List(A::alloc().unwrap().init(elems));
 
-    let value = read_value(list);
+    let value = read_value(1, list).unwrap();
 
     assert_eq!(expected, value);
 }
@@ -380,7 +434,9 @@ fn test_verify_pairs() {
         A::grow(&mut MEMORY);
     }
 
-    let packets: Vec<Packet, 32> = PacketIterator::new(TEST_INPUT).collect();
+    let packets: Vec<Packet, 32> = PacketIterator::new(TEST_INPUT)
+        .collect::<Result<_, _>>()
+        .unwrap();
     let results: Vec<core::cmp::Ordering, 32> = packets
         .chunks(2)
         .map(|chunk| {
@@ -411,8 +467,8 @@ fn test_parse_weird_stuff() {
         A::grow(&mut MEMORY);
     }
 
-    let a = read_value("[[[[7,9]],[[0,9]],0,[[2,1,1,2,9],4],[[5]]],[]]");
-    let b = read_value("[[8]]");
+    let a = read_value(1, "[[[[7,9]],[[0,9]],0,[[2,1,1,2,9],4],[[5]]],[]]").unwrap();
+    let b = read_value(1, "[[8]]").unwrap();
 
     if let Packet::List(_) = a {
     } else {
@@ -430,7 +486,7 @@ fn test_find_sum_of_verified_indices() {
         A::grow(&mut MEMORY);
     }
 
-    let ok_indices = find_indices_of_verified_pairs::<16>(TEST_INPUT);
+    let ok_indices = find_indices_of_verified_pairs::<16>(TEST_INPUT).unwrap();
 
     assert_eq!([1, 2, 4, 6], ok_indices);
 }
@@ -440,8 +496,8 @@ fn test_verify_equal_length_lists() {
         A::grow(&mut MEMORY);
     }
     let data = "[1, 2, 3]";
-    let a = read_value(data);
-    let b = read_value(data);
+    let a = read_value(1, data).unwrap();
+    let b = read_value(1, data).unwrap();
     let ok = a.cmp(&b);
 
     assert_eq!(core::cmp::Ordering::Equal, ok);
@@ -506,12 +562,51 @@ fn test_find_indices_of_divider_packets() {
     unsafe {
         A::grow(&mut MEMORY);
     }
-    let (a, b) = find_indices_of_divider_packets::<32>(TEST_INPUT);
+    let (a, b) = find_indices_of_divider_packets::<32>(TEST_INPUT).unwrap();
 
     assert_eq!(10, a);
     assert_eq!(14, b);
 }
 
+fn test_parse_errors() {
+    unsafe {
+        A::grow(&mut MEMORY);
+    }
+
+    let err = read_value(3, "[1,[2,3]").unwrap_err();
+    assert_eq!((3, 9, "']'"), (err.line, err.column, err.expected));
+
+    let err = read_value(1, "[1,x]").unwrap_err();
+    assert_eq!(
+        (1, 4, "an integer or '['"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = read_value(1, "[1 2]").unwrap_err();
+    assert_eq!((1, 4, "',' or ']'"), (err.line, err.column, err.expected));
+
+    let err = read_value(1, "[300]").unwrap_err();
+    assert_eq!((1, 2, "an integer"), (err.line, err.column, err.expected));
+
+    let err = read_value(1, "[1]]").unwrap_err();
+    assert_eq!(
+        (1, 4, "the end of the line"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = find_indices_of_verified_pairs::<4>("[1]\n[2]\n\n[3]").unwrap_err();
+    assert_eq!(
+        (4, 4, "a right packet"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = find_indices_of_divider_packets::<4>("[1]\n[2,x]").unwrap_err();
+    assert_eq!(
+        (2, 4, "an integer or '['"),
+        (err.line, err.column, err.expected)
+    );
+}
+
 const TEST_INPUT: &'static str = r#"
 [1,1,3,1,1]
 [1,1,5,1,1]
@@ -1,7 +1,11 @@
 use heapless::FnvIndexMap;
 use heapless::Vec;
 
-use crate::{println, util::Day};
+use crate::{
+    parse::{numbered_lines, ParseError},
+    println, scan,
+    util::Day,
+};
 
 pub const DAY_14: Day<i32> = Day {
     year: 2022,
@@ -9,6 +13,7 @@ pub const DAY_14: Day<i32> = Day {
     parts: &[do_part_1, do_part_2],
     tests: &[
         test_parse_drawing,
+        test_parse_errors,
         test_drop_sand,
         test_drop_sand_several_times,
         test_drop_sand_with_floor,
@@ -17,8 +22,8 @@ pub const DAY_14: Day<i32> = Day {
 
 const SAND_DROP_POINT: Point = Point::new(500, 0);
 
-fn do_part_1() -> i32 {
-    let mut drawing = parse_drawing::<8192>(INPUT);
+fn do_part_1() -> Result<i32, ParseError<'static>> {
+    let mut drawing = parse_drawing::<8192>(INPUT)?;
     let mut k: i32 = 0;
 
     while let Some(pt) = drawing.drop_sand_and_get_resting_position(SAND_DROP_POINT, None) {
@@ -26,11 +31,11 @@ fn do_part_1() -> i32 {
         k += 1
     }
 
-    return k;
+    return Ok(k);
 }
 
-fn do_part_2() -> i32 {
-    let mut drawing = parse_drawing::<8192>(INPUT);
+fn do_part_2() -> Result<i32, ParseError<'static>> {
+    let mut drawing = parse_drawing::<8192>(INPUT)?;
     let mut k: i32 = 0;
     let floor = drawing.data.iter().map(|(pt, _)| (pt.y + 2)).max();
 
@@ -47,7 +52,7 @@ fn do_part_2() -> i32 {
         }
     }
 
-    return k;
+    return Ok(k);
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -210,23 +215,26 @@ impl Point {
     }
 }
 
-fn parse_drawing<const N: usize>(data: &str) -> Drawing<N> {
+fn parse_drawing<const N: usize>(data: &str) -> Result<Drawing<N>, ParseError> {
     let mut d: Drawing<N> = Drawing::new();
 
-    for line in data.trim().lines() {
+    for (n, line) in numbered_lines(data) {
         let mut maybe_prev_pt: Option<Point> = None;
 
-        for pt_str in line.trim().split(" -> ") {
-            let (x_str, y_str) = pt_str.split_once(',').unwrap();
-            let x: i16 = x_str.parse().unwrap();
-            let y: i16 = y_str.parse().unwrap();
+        for pt_str in line.split(" -> ") {
+            let (x, y) = scan!((n, line, pt_str), "{},{}", i16, i16)?;
 
             if let Some(prev_pt) = maybe_prev_pt {
                 let should_iter_x = prev_pt.x != x;
                 let should_iter_y = prev_pt.y != y;
 
                 if should_iter_x && should_iter_y {
-                    panic!("Diagonal line, wtf? X = {}, Y = {}", x, y);
+                    return Err(ParseError::at_token(
+                        n,
+                        line,
+                        pt_str.trim(),
+                        "a point in line with the previous one",
+                    ));
                 }
 
                 let from_x = crate::util::min(prev_pt.x, x);
@@ -246,11 +254,11 @@ fn parse_drawing<const N: usize>(data: &str) -> Drawing<N> {
         }
     }
 
-    return d;
+    return Ok(d);
 }
 
 fn test_parse_drawing() {
-    let m = parse_drawing::<512>(TEST_INPUT);
+    let m = parse_drawing::<512>(TEST_INPUT).unwrap();
     let as_str = m.window_to_string::<128>(494, 503, 0, 9, None);
 
     let expected = r#"
@@ -269,15 +277,29 @@ fn test_parse_drawing() {
     assert_eq!(expected.trim(), as_str);
 }
 
+fn test_parse_errors() {
+    let err = parse_drawing::<64>("498,4 -> 498,6\n503,4 -> 502,x").unwrap_err();
+    assert_eq!((2, 14, "an integer"), (err.line, err.column, err.expected));
+
+    let err = parse_drawing::<64>("498,4 -> 498,6 -> 496,8").unwrap_err();
+    assert_eq!(
+        (1, 19, "a point in line with the previous one"),
+        (err.line, err.column, err.expected)
+    );
+
+    let err = parse_drawing::<64>("498,4 -> 498").unwrap_err();
+    assert_eq!((1, 13, ","), (err.line, err.column, err.expected));
+}
+
 fn test_drop_sand() {
-    let m = parse_drawing::<512>(TEST_INPUT);
+    let m = parse_drawing::<512>(TEST_INPUT).unwrap();
     let pt = m.drop_sand_and_get_resting_position(SAND_DROP_POINT, None);
 
     assert_eq!(Some(Point::new(500, 8)), pt);
 }
 
 fn test_drop_sand_several_times() {
-    let mut m = parse_drawing::<512>(TEST_INPUT);
+    let mut m = parse_drawing::<512>(TEST_INPUT).unwrap();
 
     for _ in 0..22 {
         let pt = m.drop_sand_and_get_resting_position(SAND_DROP_POINT, None);
@@ -303,7 +325,7 @@ fn test_drop_sand_several_times() {
 }
 
 fn test_drop_sand_with_floor() {
-    let mut m = parse_drawing::<512>(TEST_INPUT);
+    let mut m = parse_drawing::<512>(TEST_INPUT).unwrap();
     let floor = Some(11);
 
     for _ in 0..93 {
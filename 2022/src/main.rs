@@ -16,6 +16,10 @@ mod day12;
 mod day13;
 mod day14;
 
+mod md5;
+mod memo;
+mod ocr;
+mod parse;
 mod util;
 
 use core::fmt::Write;
@@ -143,8 +147,23 @@ fn run_day<T: core::fmt::Display>(day: util::Day<T>) {
     }
 
     for (i, part) in day.parts.iter().enumerate() {
-        let result = part();
-        println!("Part {}: {}", i + 1, result);
+        match part() {
+            Ok(result) => println!("Part {}: {}", i + 1, result),
+            Err(err) => {
+                println!("Part {}: {}", i + 1, err);
+                return;
+            }
+        }
+    }
+}
+
+fn run_tests(suite: util::Tests) {
+    println!("########## {} tests ##########", suite.name);
+
+    for (i, test) in suite.tests.iter().enumerate() {
+        print!("Running test {}... ", i + 1);
+        test();
+        println!("OK");
     }
 }
 
@@ -325,6 +344,10 @@ fn main() -> ! {
             // run_day(day12::DAY_12);
             // run_day(day13::DAY_13);
             run_day(day14::DAY_14);
+            // run_tests(parse::TESTS);
+            // run_tests(memo::TESTS);
+            // run_tests(md5::TESTS);
+            // run_tests(ocr::TESTS);
         }
     }
 }
@@ -0,0 +1,224 @@
+use crate::util::Tests;
+use core::fmt;
+
+// md5 as described in rfc 1321. everything but the tests only uses core
+// and nothing is allocated, so the same code runs on the firmware.
+pub type Digest = [u8; 16];
+
+#[rustfmt::skip]
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+// the integer part of abs(sin(i + 1)) * 2^32.
+#[rustfmt::skip]
+const SINES: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee,
+    0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be,
+    0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa,
+    0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed,
+    0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c,
+    0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05,
+    0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039,
+    0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1,
+    0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+// a running hash. cloning it after feeding a common prefix lets the
+// prefix be hashed only once, like the secret key of an adventcoin.
+#[derive(Debug, Clone, Copy)]
+pub struct Context {
+    state: [u32; 4],
+    buffer: [u8; 64],
+    buffered: usize,
+    length: u64,
+}
+
+impl Context {
+    pub const fn new() -> Self {
+        return Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            buffer: [0; 64],
+            buffered: 0,
+            length: 0,
+        };
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let n = core::cmp::min(64 - self.buffered, data.len());
+            self.buffer[self.buffered..(self.buffered + n)].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+
+            if self.buffered < 64 {
+                return;
+            }
+            let block = self.buffer;
+            self.process(&block);
+            self.buffered = 0;
+        }
+
+        while data.len() >= 64 {
+            self.process(data[..64].try_into().unwrap());
+            data = &data[64..];
+        }
+
+        self.buffer[..data.len()].copy_from_slice(data);
+        self.buffered = data.len();
+    }
+
+    pub fn finish(mut self) -> Digest {
+        let bit_length = self.length.wrapping_mul(8);
+
+        // a single set bit, zeroes up to 56 bytes into a block and then the length.
+        let padding_len = if self.buffered < 56 {
+            56 - self.buffered
+        } else {
+            120 - self.buffered
+        };
+        let mut padding = [0u8; 64];
+        padding[0] = 0x80;
+        self.update(&padding[..padding_len]);
+        self.update(&bit_length.to_le_bytes());
+
+        let mut out = [0u8; 16];
+        for (i, word) in self.state.iter().enumerate() {
+            out[(i * 4)..(i * 4 + 4)].copy_from_slice(&word.to_le_bytes());
+        }
+        return out;
+    }
+
+    fn process(&mut self, block: &[u8; 64]) {
+        let mut words = [0u32; 16];
+        for (i, chunk) in block.chunks_exact(4).enumerate() {
+            words[i] = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(SINES[i])
+                .wrapping_add(words[g])
+                .rotate_left(SHIFTS[i]);
+
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        self.state[0] = self.state[0].wrapping_add(a);
+        self.state[1] = self.state[1].wrapping_add(b);
+        self.state[2] = self.state[2].wrapping_add(c);
+        self.state[3] = self.state[3].wrapping_add(d);
+    }
+}
+
+pub fn compute(data: &[u8]) -> Digest {
+    let mut ctx = Context::new();
+    ctx.update(data);
+    return ctx.finish();
+}
+
+// prints a digest the way md5sum does.
+pub struct Hex(pub Digest);
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+pub const TESTS: Tests = Tests {
+    name: "md5",
+    tests: &[
+        test_rfc_1321_test_suite,
+        test_update_in_pieces,
+        test_reuse_the_prefix,
+    ],
+};
+
+fn to_hex(digest: Digest) -> heapless::String<32> {
+    let mut out = heapless::String::new();
+    let _ = fmt::Write::write_fmt(&mut out, format_args!("{}", Hex(digest)));
+    return out;
+}
+
+pub fn test_rfc_1321_test_suite() {
+    let vectors = [
+        ("", "d41d8cd98f00b204e9800998ecf8427e"),
+        ("a", "0cc175b9c0f1b6a831c399e269772661"),
+        ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+        ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+        (
+            "abcdefghijklmnopqrstuvwxyz",
+            "c3fcd3d76192e4007dfb496cca67e13b",
+        ),
+        (
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            "d174ab98d277d9f5a5611c2c9f419d9f",
+        ),
+        (
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "57edf4a22be3c955ac49da2e2107b67a",
+        ),
+    ];
+
+    for (input, expected) in vectors {
+        assert_eq!(expected, to_hex(compute(input.as_bytes())));
+    }
+}
+
+pub fn test_update_in_pieces() {
+    let mut data = [0u8; 300];
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte = (i % 251) as u8;
+    }
+    let expected = compute(&data);
+
+    for piece_len in [1, 3, 55, 56, 63, 64, 65, 200] {
+        let mut ctx = Context::new();
+        for piece in data.chunks(piece_len) {
+            ctx.update(piece);
+        }
+        assert_eq!(expected, ctx.finish());
+    }
+}
+
+pub fn test_reuse_the_prefix() {
+    // the first adventcoin of the example secret, from 2015 day 4.
+    let mut prefix = Context::new();
+    prefix.update(b"abcdef");
+
+    let mut ctx = prefix;
+    ctx.update(b"609043");
+    let digest = ctx.finish();
+
+    assert_eq!(compute(b"abcdef609043"), digest);
+    assert_eq!("000001dbbfa3a5c83a2d506429c7b00e", to_hex(digest));
+}
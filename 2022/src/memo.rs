@@ -0,0 +1,141 @@
+use crate::util::Tests;
+use core::fmt;
+use core::marker::PhantomData;
+use heapless::FnvIndexMap;
+
+// anything that can remember values by key.
+pub trait Cache<K, V> {
+    fn lookup(&self, key: &K) -> Option<&V>;
+    fn store(&mut self, key: K, value: V);
+    fn entries(&self) -> usize;
+}
+
+// a full map just stops remembering new values, which is slower but still correct.
+impl<K: Eq + hash32::Hash, V, const N: usize> Cache<K, V> for FnvIndexMap<K, V, N> {
+    fn lookup(&self, key: &K) -> Option<&V> {
+        return self.get(key);
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        let _ = self.insert(key, value);
+    }
+
+    fn entries(&self) -> usize {
+        return self.len();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} hits, {} misses", self.hits, self.misses)
+    }
+}
+
+// wraps a cache for a recursive function. the function gets the memo
+// handed back to it, so it can recurse through the same cache.
+pub struct Memo<K, V, C> {
+    cache: C,
+    stats: Stats,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K: Eq + hash32::Hash, V: Clone, const N: usize> Memo<K, V, FnvIndexMap<K, V, N>> {
+    pub fn new() -> Self {
+        return Self::with_cache(FnvIndexMap::new());
+    }
+}
+
+impl<K, V: Clone, C: Cache<K, V>> Memo<K, V, C> {
+    pub fn with_cache(cache: C) -> Self {
+        return Self {
+            cache,
+            stats: Stats::default(),
+            _marker: PhantomData,
+        };
+    }
+
+    pub fn get_or_compute<F: FnOnce(&mut Self) -> V>(&mut self, key: K, compute: F) -> V {
+        if let Some(value) = self.cache.lookup(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.store(key, value.clone());
+
+        return value;
+    }
+
+    // seeds a value, which is neither a hit nor a miss.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.cache.store(key, value);
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        return self.cache.lookup(key);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.cache.entries();
+    }
+
+    pub fn stats(&self) -> Stats {
+        return self.stats;
+    }
+}
+
+pub const TESTS: Tests = Tests {
+    name: "memo",
+    tests: &[
+        test_memoize_a_recursive_function,
+        test_full_cache_still_computes,
+    ],
+};
+
+type PathMemo<const N: usize> = Memo<(i8, i8), u64, FnvIndexMap<(i8, i8), u64, N>>;
+
+fn count_paths<const N: usize>(x: i8, y: i8, memo: &mut PathMemo<N>) -> u64 {
+    if x == 0 || y == 0 {
+        return 1;
+    }
+    return memo.get_or_compute((x, y), |memo| {
+        return count_paths(x - 1, y, memo) + count_paths(x, y - 1, memo);
+    });
+}
+
+pub fn test_memoize_a_recursive_function() {
+    let mut memo: PathMemo<128> = Memo::new();
+
+    assert_eq!(184_756, count_paths(10, 10, &mut memo));
+    assert_eq!(100, memo.len());
+    assert_eq!(
+        Stats {
+            hits: 81,
+            misses: 100
+        },
+        memo.stats()
+    );
+
+    assert_eq!(184_756, count_paths(10, 10, &mut memo));
+    assert_eq!(
+        Stats {
+            hits: 82,
+            misses: 100
+        },
+        memo.stats()
+    );
+}
+
+pub fn test_full_cache_still_computes() {
+    let mut memo: PathMemo<16> = Memo::new();
+
+    assert_eq!(184_756, count_paths(10, 10, &mut memo));
+    assert_eq!(16, memo.len());
+}
@@ -0,0 +1,463 @@
+use crate::util::Tests;
+use core::fmt;
+use core::str::FromStr;
+
+// line and column are both 1-based, like in any text editor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub expected: &'static str,
+    pub source_line: &'a str,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(line: usize, source_line: &'a str, column: usize, expected: &'static str) -> Self {
+        return Self {
+            line,
+            column,
+            expected,
+            source_line,
+        };
+    }
+
+    // points the error at "token", which must be a slice of "source_line".
+    pub fn at_token(
+        line: usize,
+        source_line: &'a str,
+        token: &str,
+        expected: &'static str,
+    ) -> Self {
+        return Self::new(line, source_line, column_of(source_line, token), expected);
+    }
+
+    // points the error just past the end of the line.
+    pub fn at_end(line: usize, source_line: &'a str, expected: &'static str) -> Self {
+        return Self::new(line, source_line, source_line.len() + 1, expected);
+    }
+}
+
+impl<'a> fmt::Display for ParseError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut gutter: heapless::String<20> = heapless::String::new();
+        let _ = fmt::Write::write_fmt(&mut gutter, format_args!("{}", self.line));
+
+        writeln!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        writeln!(f, "{} | {}", gutter, self.source_line)?;
+
+        for _ in 0..gutter.len() {
+            f.write_str(" ")?;
+        }
+        f.write_str(" | ")?;
+        for _ in 1..self.column {
+            f.write_str(" ")?;
+        }
+        f.write_str("^")
+    }
+}
+
+pub fn column_of(source_line: &str, token: &str) -> usize {
+    let start = source_line.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).wrapping_sub(start);
+
+    assert!(
+        offset <= source_line.len(),
+        "Token is not a part of the line."
+    );
+
+    return offset + 1;
+}
+
+pub fn parse_token<'a, T: FromStr>(
+    line: usize,
+    source_line: &'a str,
+    token: &str,
+    expected: &'static str,
+) -> Result<T, ParseError<'a>> {
+    return token
+        .parse()
+        .map_err(|_| ParseError::at_token(line, source_line, token, expected));
+}
+
+// yields (line number, line) for every non-empty line, with line numbers
+// counted from the start of "input" so they match what is in the file.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    return input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+}
+
+// scan!(line, "move {} from {} to {}", i32, i32, i32) matches a line against a
+// pattern, where "{}" captures a single value and "{,}" a comma separated list.
+// "[s|es]" matches any one of the words in it, for wording that changes.
+// the input is either a line, a (line number, line) pair from numbered_lines,
+// or a (line number, line, piece of the line) triple.
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:literal) => {
+        $crate::parse::scan($input, $pattern)
+    };
+    ($input:expr, $pattern:literal, $($t:ty),+ $(,)?) => {
+        $crate::parse::scan::<($($t,)+), _>($input, $pattern)
+    };
+}
+
+// the line number, the whole line, and the part of it that has to match.
+pub trait ScanInput<'a> {
+    fn numbered(self) -> (usize, &'a str, &'a str);
+}
+
+impl<'a> ScanInput<'a> for &'a str {
+    fn numbered(self) -> (usize, &'a str, &'a str) {
+        return (1, self, self);
+    }
+}
+
+impl<'a> ScanInput<'a> for (usize, &'a str) {
+    fn numbered(self) -> (usize, &'a str, &'a str) {
+        return (self.0, self.1, self.1);
+    }
+}
+
+// a piece of a line, which must be a slice of it, so that errors still
+// point into the whole line.
+impl<'a> ScanInput<'a> for (usize, &'a str, &'a str) {
+    fn numbered(self) -> (usize, &'a str, &'a str) {
+        return self;
+    }
+}
+
+// a value that can be read from a captured piece of a line.
+pub trait ScanField<'a>: Sized {
+    fn scan_field(line: usize, source_line: &'a str, text: &'a str)
+        -> Result<Self, ParseError<'a>>;
+}
+
+macro_rules! impl_scan_field_with_from_str {
+    ($expected:literal, $($t:ty),+) => {
+        $(
+            impl<'a> ScanField<'a> for $t {
+                fn scan_field(line: usize, source_line: &'a str, text: &'a str) -> Result<Self, ParseError<'a>> {
+                    return parse_token(line, source_line, text, $expected);
+                }
+            }
+        )+
+    };
+}
+
+impl_scan_field_with_from_str!(
+    "an integer",
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    usize
+);
+impl_scan_field_with_from_str!("a single character", char);
+
+impl<'a> ScanField<'a> for &'a str {
+    fn scan_field(_: usize, _: &'a str, text: &'a str) -> Result<Self, ParseError<'a>> {
+        return Ok(text);
+    }
+}
+
+impl<'a, T: ScanField<'a>, const N: usize> ScanField<'a> for heapless::Vec<T, N> {
+    fn scan_field(
+        line: usize,
+        source_line: &'a str,
+        text: &'a str,
+    ) -> Result<Self, ParseError<'a>> {
+        let mut out = heapless::Vec::new();
+        for item in text.split(',') {
+            let item = item.trim();
+            if item.is_empty() {
+                return Err(ParseError::at_token(line, source_line, item, "a list item"));
+            }
+            let value = T::scan_field(line, source_line, item)?;
+            if out.push(value).is_err() {
+                return Err(ParseError::at_token(
+                    line,
+                    source_line,
+                    item,
+                    "a shorter list",
+                ));
+            }
+        }
+        return Ok(out);
+    }
+}
+
+pub trait FromCaptures<'a>: Sized {
+    const COUNT: usize;
+    fn from_captures(
+        line: usize,
+        source_line: &'a str,
+        captures: &[&'a str],
+    ) -> Result<Self, ParseError<'a>>;
+}
+
+macro_rules! impl_from_captures {
+    ($count:literal; $($t:ident $i:tt),+) => {
+        impl<'a, $($t: ScanField<'a>),+> FromCaptures<'a> for ($($t,)+) {
+            const COUNT: usize = $count;
+
+            fn from_captures(line: usize, source_line: &'a str, captures: &[&'a str]) -> Result<Self, ParseError<'a>> {
+                return Ok(($($t::scan_field(line, source_line, captures[$i])?,)+));
+            }
+        }
+    };
+}
+
+impl_from_captures!(1; A 0);
+impl_from_captures!(2; A 0, B 1);
+impl_from_captures!(3; A 0, B 1, C 2);
+impl_from_captures!(4; A 0, B 1, C 2, D 3);
+impl_from_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+// the pattern is static so that a missing piece of it can be
+// handed out as the "expected" part of the error as is.
+pub fn scan<'a, T: FromCaptures<'a>, I: ScanInput<'a>>(
+    input: I,
+    pattern: &'static str,
+) -> Result<T, ParseError<'a>> {
+    let (line, source_line, part) = input.numbered();
+    let captures = match_pattern(line, source_line, part, pattern)?;
+
+    assert_eq!(
+        T::COUNT,
+        captures.len(),
+        "The pattern {:?} does not have one placeholder per field.",
+        pattern
+    );
+
+    return T::from_captures(line, source_line, &captures);
+}
+
+// the length of the start of "text" that matches "literal", where "[a|b]"
+// in the literal matches either "a" or "b", whichever matches more.
+fn match_literal(text: &str, literal: &str) -> Option<usize> {
+    let open = match literal.find('[') {
+        Some(open) => open,
+        None if text.starts_with(literal) => return Some(literal.len()),
+        None => return None,
+    };
+    let close = open
+        + literal[open..]
+            .find(']')
+            .expect("Unclosed alternative in pattern.");
+    if !text.starts_with(&literal[..open]) {
+        return None;
+    }
+
+    let after = &text[open..];
+    let rest = &literal[(close + 1)..];
+    return literal[(open + 1)..close]
+        .split('|')
+        .filter(|choice| after.starts_with(*choice))
+        .filter_map(|choice| {
+            let len = match_literal(&after[choice.len()..], rest)?;
+            return Some(open + choice.len() + len);
+        })
+        .max();
+}
+
+fn find_literal(text: &str, literal: &str) -> Option<usize> {
+    return text
+        .char_indices()
+        .map(|(i, _)| i)
+        .find(|i| match_literal(&text[*i..], literal).is_some());
+}
+
+fn match_pattern<'a>(
+    line: usize,
+    source_line: &'a str,
+    part: &'a str,
+    pattern: &'static str,
+) -> Result<heapless::Vec<&'a str, 8>, ParseError<'a>> {
+    let text = part.trim();
+    let error_at = |pos: usize, expected: &'static str| {
+        let column = column_of(source_line, text) + pos;
+        return ParseError::new(line, source_line, column, expected);
+    };
+
+    let mut captures = heapless::Vec::new();
+    let mut pos = 0;
+    let mut rest = pattern;
+
+    loop {
+        let literal_end = rest.find('{').unwrap_or(rest.len());
+        let literal = &rest[..literal_end];
+
+        let literal_len = match match_literal(&text[pos..], literal) {
+            Some(len) => len,
+            None => {
+                return Err(error_at(pos, literal));
+            }
+        };
+        pos += literal_len;
+        rest = &rest[literal_end..];
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let placeholder_end = rest.find('}').expect("Unclosed placeholder in pattern.");
+        rest = &rest[(placeholder_end + 1)..];
+
+        // a placeholder runs until the next literal shows up.
+        let next_literal = &rest[..rest.find('{').unwrap_or(rest.len())];
+        assert!(
+            !next_literal.is_empty() || rest.is_empty(),
+            "Placeholders must be separated by some text."
+        );
+
+        let end = if next_literal.is_empty() {
+            text.len()
+        } else {
+            match find_literal(&text[pos..], next_literal) {
+                Some(i) => pos + i,
+                None => {
+                    // blame whatever follows the value the placeholder most likely
+                    // wanted, past the part of the literal that did match.
+                    let value_len = text[pos..]
+                        .find(|ch: char| {
+                            !(ch.is_alphanumeric() || ch == '-' || ch == '.' || ch == '_')
+                        })
+                        .unwrap_or(text.len() - pos);
+                    let matched_len = text[(pos + value_len)..]
+                        .bytes()
+                        .zip(next_literal.split('[').next().unwrap().bytes())
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    return Err(error_at(pos + value_len + matched_len, next_literal));
+                }
+            }
+        };
+
+        let captured = text[pos..end].trim();
+        if captured.is_empty() {
+            return Err(error_at(pos, "a value"));
+        }
+
+        captures
+            .push(captured)
+            .expect("Too many placeholders in pattern.");
+        pos = end;
+    }
+
+    if pos != text.len() {
+        return Err(error_at(pos, "the end of the line"));
+    }
+
+    return Ok(captures);
+}
+
+pub const TESTS: Tests = Tests {
+    name: "parse",
+    tests: &[
+        test_display_points_at_the_column,
+        test_numbered_lines_skip_blank_lines,
+        test_scan_typed_fields,
+        test_scan_reports_mismatches,
+        test_scan_alternatives,
+    ],
+};
+
+pub fn test_display_points_at_the_column() {
+    let line = "Test: divisible by x";
+    let err = ParseError::at_token(3, line, &line[19..], "an integer");
+    let mut s: heapless::String<128> = heapless::String::new();
+    let _ = fmt::Write::write_fmt(&mut s, format_args!("{}", err));
+
+    assert_eq!(
+        "line 3, column 20: expected an integer\n3 | Test: divisible by x\n  |                    ^",
+        s
+    );
+}
+
+pub fn test_numbered_lines_skip_blank_lines() {
+    let lines: heapless::Vec<(usize, &str), 4> = numbered_lines("\na\n\nb\n").collect();
+
+    assert_eq!([(2, "a"), (4, "b")], lines);
+}
+
+pub fn test_scan_typed_fields() {
+    let line = "Valve AA has flow rate=13; tunnels lead to valves DD, II, BB";
+    let (name, rate, valves) = crate::scan!(
+        line,
+        "Valve {} has flow rate={}; tunnels lead to valves {,}",
+        &str,
+        i32,
+        heapless::Vec<&str, 4>
+    )
+    .unwrap();
+
+    assert_eq!("AA", name);
+    assert_eq!(13, rate);
+    assert_eq!(["DD", "II", "BB"], valves);
+}
+
+pub fn test_scan_reports_mismatches() {
+    let err = crate::scan!(
+        (4, "move 1 form 2 to 3"),
+        "move {} from {} to {}",
+        i32,
+        i32,
+        i32
+    )
+    .unwrap_err();
+    assert_eq!((4, 9, " from "), (err.line, err.column, err.expected));
+
+    let err =
+        crate::scan!("move x from 2 to 3", "move {} from {} to {}", i32, i32, i32).unwrap_err();
+    assert_eq!((6, "an integer"), (err.column, err.expected));
+
+    let err = crate::scan!("1, 2,, 3", "{,}", heapless::Vec<i32, 4>).unwrap_err();
+    assert_eq!((6, "a list item"), (err.column, err.expected));
+
+    let err = crate::scan!("1, 2, 3", "{,}", heapless::Vec<i32, 2>).unwrap_err();
+    assert_eq!((7, "a shorter list"), (err.column, err.expected));
+
+    let err = crate::scan!("a = 1 !", "{} = {}", &str, i32).unwrap_err();
+    assert_eq!((5, "an integer"), (err.column, err.expected));
+}
+
+pub fn test_scan_alternatives() {
+    let (_, _, valves) = crate::scan!(
+        "Valve HH has flow rate=22; tunnel leads to valve GG",
+        "Valve {} has flow rate={}; [tunnel leads to valve|tunnels lead to valves] {,}",
+        &str,
+        i32,
+        heapless::Vec<&str, 4>
+    )
+    .unwrap();
+    assert_eq!(["GG"], valves);
+
+    let (_, _, valves) = crate::scan!(
+        "Valve AA has flow rate=0; tunnels lead to valves DD, II",
+        "Valve {} has flow rate={}; [tunnel leads to valve|tunnels lead to valves] {,}",
+        &str,
+        i32,
+        heapless::Vec<&str, 4>
+    )
+    .unwrap();
+    assert_eq!(["DD", "II"], valves);
+
+    let res: (i32,) = crate::scan!("3 pears", "{} [pear|pears]").unwrap();
+    assert_eq!((3,), res);
+
+    let err = crate::scan!("1 apple", "{} [pear|pears]", i32).unwrap_err();
+    assert_eq!((3, " [pear|pears]"), (err.column, err.expected));
+}
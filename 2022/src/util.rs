@@ -1,9 +1,18 @@
+use crate::parse::ParseError;
+
 type F<R> = fn() -> R;
 
+// a part either answers, or says where the puzzle input did not make sense.
 pub struct Day<'a, T> {
     pub year: i32,
     pub day: i32,
-    pub parts: &'a [F<T>],
+    pub parts: &'a [F<Result<T, ParseError<'static>>>],
+    pub tests: &'a [F<()>],
+}
+
+// the tests of a module that several days share, run on their own.
+pub struct Tests<'a> {
+    pub name: &'a str,
     pub tests: &'a [F<()>],
 }
 
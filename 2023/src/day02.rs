@@ -1,3 +1,5 @@
+use crate::parse::{numbered_lines, ParseError};
+use crate::scan;
 use std::collections::HashMap;
 
 pub fn part1() {
@@ -6,15 +8,23 @@ pub fn part1() {
         green: 13,
         blue: 14,
     };
-    let sum = sum_of_possible_games(&set, INPUT);
-    println!("D02P1: {}", sum);
+    match sum_of_possible_games(&set, INPUT) {
+        Ok(sum) => println!("D02P1: {}", sum),
+        Err(err) => println!("D02P1: {}", err),
+    };
 }
 
 pub fn part2() {
     let mut sum: i64 = 0;
 
-    for line in INPUT.trim().lines() {
-        let game = parse_game(line);
+    for (n, line) in numbered_lines(INPUT) {
+        let game = match parse_game(n, line) {
+            Ok(game) => game,
+            Err(err) => {
+                println!("D02P2: {}", err);
+                return;
+            }
+        };
         let set = find_smallest_possible_set(&game);
         let pow = set.red * set.green * set.blue;
         sum += pow;
@@ -23,6 +33,7 @@ pub fn part2() {
     println!("D02P2: {}", sum);
 }
 
+#[derive(Debug)]
 struct Game {
     id: i64,
     sets: Vec<CubeSet>,
@@ -42,23 +53,19 @@ fn is_game_possible(set: &CubeSet, game: &Game) -> bool {
         .all(|h| h.red <= set.red && h.green <= set.green && h.blue <= set.blue);
 }
 
-fn sum_of_possible_games(set: &CubeSet, games: &str) -> i64 {
+fn sum_of_possible_games(set: &CubeSet, games: &str) -> Result<i64, ParseError> {
     let mut sum: i64 = 0;
-    for line in games.trim().lines() {
-        let game = parse_game(line.trim());
+    for (n, line) in numbered_lines(games) {
+        let game = parse_game(n, line)?;
         if is_game_possible(set, &game) {
             sum += game.id;
         }
     }
-    return sum;
+    return Ok(sum);
 }
 
-fn parse_game(line: &str) -> Game {
-    let (game, hands) = line.split_once(":").unwrap();
-    let id: i64 = {
-        let (_, b) = game.split_once(" ").unwrap();
-        b.parse().unwrap()
-    };
+fn parse_game(n: usize, line: &str) -> Result<Game, ParseError> {
+    let (id, hands) = scan!((n, line), "Game {}: {}", i64, &str)?;
     let hands: Vec<CubeSet> = {
         let inner_hands = hands.split(";");
         let mut out: Vec<CubeSet> = Vec::new();
@@ -69,26 +76,23 @@ fn parse_game(line: &str) -> Game {
                 green: 0,
                 blue: 0,
             };
+            let mut seen = [false; 3];
 
             for chunk in h.split(",") {
-                let (num, color) = chunk.trim().split_once(" ").unwrap();
-
-                let actual_num: i64 = num.parse().unwrap();
-                match color {
-                    "red" => {
-                        assert_eq!(0, hand.red);
-                        hand.red = actual_num;
-                    }
-                    "green" => {
-                        assert_eq!(0, hand.green);
-                        hand.green = actual_num;
-                    }
-                    "blue" => {
-                        assert_eq!(0, hand.blue);
-                        hand.blue = actual_num;
-                    }
-                    _ => panic!("very bad color '{}'", color),
+                let (actual_num, color) = scan!((n, line, chunk), "{} {}", i64, &str)?;
+                let (index, value) = match color {
+                    "red" => (0, &mut hand.red),
+                    "green" => (1, &mut hand.green),
+                    "blue" => (2, &mut hand.blue),
+                    _ => return Err(ParseError::at_token(n, line, color, "red, green or blue")),
                 };
+
+                // a hand can show 0 cubes of a color, so the count alone cannot tell.
+                if seen[index] {
+                    return Err(ParseError::at_token(n, line, color, "each color once per hand"));
+                }
+                seen[index] = true;
+                *value = actual_num;
             }
 
             out.push(hand);
@@ -97,7 +101,7 @@ fn parse_game(line: &str) -> Game {
         out
     };
 
-    return Game { id: id, sets: hands };
+    return Ok(Game { id: id, sets: hands });
 }
 
 fn find_smallest_possible_set(game: &Game) -> CubeSet {
@@ -120,7 +124,7 @@ mod tests {
 
     #[test]
     fn should_parse_hand() {
-        let game = parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+        let game = parse_game(1, "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
 
         assert_eq!(1, game.id);
         assert_eq!(3, game.sets.len());
@@ -149,19 +153,41 @@ mod tests {
             green: 13,
             blue: 14,
         };
-        let sum = sum_of_possible_games(&set, games);
+        let sum = sum_of_possible_games(&set, games).unwrap();
         assert_eq!(8, sum);
     }
 
     #[test]
     fn should_find_smallest_possible_set() {
-        let game = parse_game("Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red");
+        let game = parse_game(1, "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red").unwrap();
         let set = find_smallest_possible_set(&game);
 
         assert_eq!(14, set.red);
         assert_eq!(3, set.green);
         assert_eq!(15, set.blue);
     }
+
+    #[test]
+    fn should_report_bad_games() {
+        let err = parse_game(3, "Game 3: 3 blue, 4 purple").unwrap_err();
+        assert_eq!((3, 19, "red, green or blue".to_string()), (err.line, err.column, err.expected));
+
+        let err = parse_game(1, "Game x: 3 blue").unwrap_err();
+        assert_eq!((1, 6, "an integer".to_string()), (err.line, err.column, err.expected));
+
+        let err = parse_game(1, "Game 1: 3 blue, x red").unwrap_err();
+        assert_eq!((1, 17, "an integer".to_string()), (err.line, err.column, err.expected));
+
+        let err = parse_game(1, "Game 1: 3 blue, 4 blue").unwrap_err();
+        assert_eq!((1, 19, "each color once per hand".to_string()), (err.line, err.column, err.expected));
+
+        let err = parse_game(1, "Game 1: 0 red, 3 red").unwrap_err();
+        assert_eq!((1, 18, "each color once per hand".to_string()), (err.line, err.column, err.expected));
+
+        let games = "\nGame 1: 1 red\n\nGame 2: red";
+        let err = sum_of_possible_games(&CubeSet { red: 1, green: 1, blue: 1 }, games).unwrap_err();
+        assert_eq!((4, 12, "' '".to_string()), (err.line, err.column, err.expected));
+    }
 }
 
 const INPUT: &'static str = r###"
@@ -1,13 +1,18 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
 use std::str::from_utf8;
 
 pub fn part1() {
-    let sum = find_sum_of_part_numbers(INPUT);
-    println!("D03P1: {}", sum);
+    match find_sum_of_part_numbers(INPUT) {
+        Ok(sum) => println!("D03P1: {}", sum),
+        Err(err) => println!("D03P1: {}", err),
+    };
 }
 
 pub fn part2() {
-    let sum = find_sum_of_gear_ratios(INPUT);
-    println!("D03P2: {}", sum);
+    match find_sum_of_gear_ratios(INPUT) {
+        Ok(sum) => println!("D03P2: {}", sum),
+        Err(err) => println!("D03P2: {}", err),
+    };
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -22,12 +27,14 @@ impl Point {
     }
 }
 
-pub fn find_sum_of_part_numbers(schema: &str) -> i64 {
-    let lines: Vec<&str> = schema.trim().lines().collect();
+pub fn find_sum_of_part_numbers(schema: &str) -> Result<i64, ParseError> {
+    let lines: Vec<(usize, &str)> = numbered_lines(schema).collect();
     let mut sum: i64 = 0;
 
     for line_index in 0..lines.len() {
-        let bytes = lines[line_index].trim().as_bytes();
+        let (n, source_line) = lines[line_index];
+        let text = source_line.trim();
+        let bytes = text.as_bytes();
         let mut k: usize = 0;
 
         while k < bytes.len() {
@@ -80,17 +87,13 @@ pub fn find_sum_of_part_numbers(schema: &str) -> i64 {
                 });
             }
 
-            let s = &bytes[k..end_index];
-            let num: i64 = core::str::from_utf8(s)
-                .unwrap()
-                .parse()
-                .unwrap();
+            let num: i64 = parse_token(n, source_line, &text[k..end_index], "an integer")?;
 
             for pt in points_to_scan {
                 if pt.x < 0 || pt.y < 0 {
                     continue;
                 }
-                if let Some(byte) = lines.get(pt.y as usize).and_then(|line| line.trim().as_bytes().get(pt.x as usize)) {
+                if let Some(byte) = lines.get(pt.y as usize).and_then(|(_, line)| line.trim().as_bytes().get(pt.x as usize)) {
                     let is_symbol = !byte.is_ascii_digit() && *byte != b'.';
                     if is_symbol {
                         sum += num;
@@ -103,7 +106,7 @@ pub fn find_sum_of_part_numbers(schema: &str) -> i64 {
         }
     }
 
-    return sum;
+    return Ok(sum);
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -113,13 +116,13 @@ struct Gear {
     num2: i64,
 }
 
-pub fn find_sum_of_gear_ratios(schema: &str) -> i64 {
-    let lines: Vec<&str> = schema.trim().lines().collect();
+pub fn find_sum_of_gear_ratios(schema: &str) -> Result<i64, ParseError> {
+    let lines: Vec<(usize, &str)> = numbered_lines(schema).collect();
     let mut gears: Vec<Gear> = Vec::new();
 
     for line_index in 0..lines.len() {
-        let line = lines[line_index].trim().as_bytes();
-        for col_index in 0..lines[line_index].trim().len() {
+        let line = lines[line_index].1.trim().as_bytes();
+        for col_index in 0..line.len() {
             let byte = line[col_index];
 
             if byte != b'*' {
@@ -143,11 +146,12 @@ pub fn find_sum_of_gear_ratios(schema: &str) -> i64 {
                 if pt.x < 0 || pt.y < 0 {
                     continue;
                 }
-                let line = lines.get(pt.y as usize);
-                if line.is_none() {
-                    continue;
-                }
-                let line = line.unwrap().trim().as_bytes();
+                let (n, source_line) = match lines.get(pt.y as usize) {
+                    Some(numbered) => *numbered,
+                    None => continue,
+                };
+                let text = source_line.trim();
+                let line = text.as_bytes();
 
                 let is_num = line.get(pt.x as usize)
                     .map(|x| x.is_ascii_digit())
@@ -181,9 +185,8 @@ pub fn find_sum_of_gear_ratios(schema: &str) -> i64 {
                         x_end += 1;
                     }
 
-                    let s = &line[x_start as usize..=x_end as usize];
-                    let str = core::str::from_utf8(s).unwrap();
-                    let num: i64 = str.parse().unwrap();
+                    let s = &text[x_start as usize..=x_end as usize];
+                    let num: i64 = parse_token(n, source_line, s, "an integer")?;
 
                     let pt = Point::new(x_start, pt.y);
 
@@ -208,7 +211,7 @@ pub fn find_sum_of_gear_ratios(schema: &str) -> i64 {
         }
     }
 
-    return gears.iter().map(|gear| gear.num1 * gear.num2).sum();
+    return Ok(gears.iter().map(|gear| gear.num1 * gear.num2).sum());
 }
 
 #[cfg(test)]
@@ -230,17 +233,28 @@ mod tests {
 
     #[test]
     fn should_find_part_numbers() {
-        let x = find_sum_of_part_numbers(TEST_INPUT);
+        let x = find_sum_of_part_numbers(TEST_INPUT).unwrap();
 
         assert_eq!(4361, x);
     }
 
     #[test]
     fn should_find_gears() {
-        let sum = find_sum_of_gear_ratios(TEST_INPUT);
+        let sum = find_sum_of_gear_ratios(TEST_INPUT).unwrap();
 
         assert_eq!(467835, sum);
     }
+
+    #[test]
+    fn should_report_numbers_that_are_too_big() {
+        let schema = "467..114..\n...*......\n..99999999999999999999";
+
+        let err = find_sum_of_part_numbers(schema).unwrap_err();
+        assert_eq!((3, 3, "an integer".to_string()), (err.line, err.column, err.expected));
+
+        let err = find_sum_of_gear_ratios(schema).unwrap_err();
+        assert_eq!((3, 3, "an integer".to_string()), (err.line, err.column, err.expected));
+    }
 }
 
 const INPUT: &'static str = r###"
@@ -1,10 +1,18 @@
+use crate::parse::{numbered_lines, parse_token, ParseError};
+use crate::scan;
 use std::collections::HashMap;
 
 pub fn part1() {
     let mut points: i64 = 0;
 
-    for line in INPUT.trim().lines() {
-        let card = parse_card(line);
+    for (n, line) in numbered_lines(INPUT) {
+        let card = match parse_card(n, line) {
+            Ok(card) => card,
+            Err(err) => {
+                println!("DAY04P1: {}", err);
+                return;
+            }
+        };
         points += card.points();
     }
 
@@ -13,9 +21,16 @@ pub fn part1() {
 
 pub fn part2() {
     // this needs '--release' or a pretty beefy coomputer.
-    let cards: Vec<Card> = INPUT.trim().lines()
-        .map(parse_card)
-        .collect();
+    let cards: Vec<Card> = match numbered_lines(INPUT)
+        .map(|(n, line)| parse_card(n, line))
+        .collect()
+    {
+        Ok(cards) => cards,
+        Err(err) => {
+            println!("DAY04P2: {}", err);
+            return;
+        }
+    };
     let num_won = get_number_of_won_cards(&cards);
     println!("DAY04P2: {}", num_won);
 }
@@ -49,36 +64,23 @@ impl Card {
     }
 }
 
-fn parse_card(card: &str) -> Card {
-    let (a, b) = card.split_once("|").unwrap();
-    let (card_and_id, a) = a.split_once(":").unwrap();
-    let id: i64 = {
-        let num: i64 = card_and_id.split_ascii_whitespace()
-            .last()
-            .unwrap()
-            .parse()
-            .unwrap();
-        num
-    };
-    let winning: Vec<i64> = {
-        
-        let nums: Vec<i64> = a.trim().split_ascii_whitespace()
-            .map(|n| n.trim().parse::<i64>().unwrap())
-            .collect();
-        nums
-    };
-    let on_hand: Vec<i64> = {
-        let nums: Vec<i64> = b.trim().split_ascii_whitespace()
-            .map(|n| n.trim().parse::<i64>().unwrap())
-            .collect();
-        nums
-    };
+fn parse_card(n: usize, line: &str) -> Result<Card, ParseError> {
+    let (id, a, b) = scan!((n, line), "Card {}: {} | {}", i64, &str, &str)?;
+    let winning: Vec<i64> = parse_numbers(n, line, a)?;
+    let on_hand: Vec<i64> = parse_numbers(n, line, b)?;
 
-    return Card {
+    return Ok(Card {
         id,
         winning,
         on_hand,
-    };
+    });
+}
+
+// the numbers are separated by one or more spaces, so "{,}" does not fit.
+fn parse_numbers(n: usize, line: &str, nums: &str) -> Result<Vec<i64>, ParseError> {
+    return nums.split_ascii_whitespace()
+        .map(|num| parse_token(n, line, num, "an integer"))
+        .collect();
 }
 
 // brute forcing p2 as i don't have the brainpower to dynprog
@@ -126,7 +128,7 @@ mod tests {
 
     #[test]
     fn should_parse_card() {
-        let card = parse_card("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53");
+        let card = parse_card(1, "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
         assert_eq!(1, card.id);
         assert_eq!(41, card.winning[0]);
         assert_eq!(48, card.winning[1]);
@@ -146,14 +148,26 @@ mod tests {
 
     #[test]
     fn should_calculate_points() {
-        let card = parse_card("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53");
+        let card = parse_card(1, "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
         assert_eq!(8, card.points());
     }
 
+    #[test]
+    fn should_report_bad_cards() {
+        let err = parse_card(4, "Card 4: 41 48 8x 86 17 | 83 86  6 31 17  9 48 53").unwrap_err();
+        assert_eq!((4, 15, "an integer".to_string()), (err.line, err.column, err.expected));
+
+        let err = parse_card(1, "Card 1: 41 48 83 86 17 / 83 86  6 31 17  9 48 53").unwrap_err();
+        assert_eq!((1, 12, "' | '".to_string()), (err.line, err.column, err.expected));
+
+        let err = parse_card(1, "Card one: 41 | 83").unwrap_err();
+        assert_eq!((1, 6, "an integer".to_string()), (err.line, err.column, err.expected));
+    }
+
     #[test]
     fn should_calculate_total_number_of_cards() {
-        let cards: Vec<Card> = TEST_INPUT.trim().lines()
-            .map(|line| parse_card(line.trim()))
+        let cards: Vec<Card> = numbered_lines(TEST_INPUT)
+            .map(|(n, line)| parse_card(n, line).unwrap())
             .collect();
         let res = get_number_of_won_cards(&cards);
         assert_eq!(30, res);
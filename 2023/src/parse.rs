@@ -0,0 +1,415 @@
+use std::fmt;
+use std::str::FromStr;
+
+// line and column are both 1-based, like in any text editor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub source_line: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, source_line: &str, column: usize, expected: &str) -> Self {
+        return Self {
+            line,
+            column,
+            expected: expected.to_string(),
+            source_line: source_line.to_string(),
+        };
+    }
+
+    // points the error at "token", which must be a slice of "source_line".
+    pub fn at_token(line: usize, source_line: &str, token: &str, expected: &str) -> Self {
+        return Self::new(line, source_line, column_of(source_line, token), expected);
+    }
+
+    // points the error just past the end of the line.
+    pub fn at_end(line: usize, source_line: &str, expected: &str) -> Self {
+        return Self::new(line, source_line, source_line.len() + 1, expected);
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = self.line.to_string();
+        let padding = " ".repeat(gutter.len());
+        let caret_offset = " ".repeat(self.column.saturating_sub(1));
+
+        writeln!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        writeln!(f, "{} | {}", gutter, self.source_line)?;
+        write!(f, "{} | {}^", padding, caret_offset)
+    }
+}
+
+pub fn column_of(source_line: &str, token: &str) -> usize {
+    let start = source_line.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).wrapping_sub(start);
+
+    assert!(offset <= source_line.len(), "Token is not a part of the line.");
+
+    return offset + 1;
+}
+
+pub fn parse_token<T: FromStr>(
+    line: usize,
+    source_line: &str,
+    token: &str,
+    expected: &str,
+) -> Result<T, ParseError> {
+    return token
+        .parse()
+        .map_err(|_| ParseError::at_token(line, source_line, token, expected));
+}
+
+// yields (line number, line) for every non-empty line, with line numbers
+// counted from the start of "input" so they match what is in the file.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    return input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+}
+
+// scan!(line, "move {} from {} to {}", i32, i32, i32) matches a line against a
+// pattern, where "{}" captures a single value and "{,}" a comma separated list.
+// "[s|es]" matches any one of the words in it, for wording that changes.
+// the input is either a line, a (line number, line) pair from numbered_lines,
+// or a (line number, line, piece of the line) triple.
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:literal) => {
+        $crate::parse::scan($input, $pattern)
+    };
+    ($input:expr, $pattern:literal, $($t:ty),+ $(,)?) => {
+        $crate::parse::scan::<($($t,)+), _>($input, $pattern)
+    };
+}
+
+// the line number, the whole line, and the part of it that has to match.
+pub trait ScanInput<'a> {
+    fn numbered(self) -> (usize, &'a str, &'a str);
+}
+
+impl<'a> ScanInput<'a> for &'a str {
+    fn numbered(self) -> (usize, &'a str, &'a str) {
+        return (1, self, self);
+    }
+}
+
+impl<'a> ScanInput<'a> for (usize, &'a str) {
+    fn numbered(self) -> (usize, &'a str, &'a str) {
+        return (self.0, self.1, self.1);
+    }
+}
+
+// a piece of a line, which must be a slice of it, so that errors still
+// point into the whole line.
+impl<'a> ScanInput<'a> for (usize, &'a str, &'a str) {
+    fn numbered(self) -> (usize, &'a str, &'a str) {
+        return self;
+    }
+}
+
+// a value that can be read from a captured piece of a line.
+pub trait ScanField<'a>: Sized {
+    fn scan_field(line: usize, source_line: &'a str, text: &'a str) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_scan_field_with_from_str {
+    ($expected:literal, $($t:ty),+) => {
+        $(
+            impl<'a> ScanField<'a> for $t {
+                fn scan_field(line: usize, source_line: &'a str, text: &'a str) -> Result<Self, ParseError> {
+                    return parse_token(line, source_line, text, $expected);
+                }
+            }
+        )+
+    };
+}
+
+impl_scan_field_with_from_str!("an integer", i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_scan_field_with_from_str!("a number", f32, f64);
+impl_scan_field_with_from_str!("a single character", char);
+
+impl<'a> ScanField<'a> for &'a str {
+    fn scan_field(_: usize, _: &'a str, text: &'a str) -> Result<Self, ParseError> {
+        return Ok(text);
+    }
+}
+
+impl<'a> ScanField<'a> for String {
+    fn scan_field(_: usize, _: &'a str, text: &'a str) -> Result<Self, ParseError> {
+        return Ok(text.to_string());
+    }
+}
+
+impl<'a, T: ScanField<'a>> ScanField<'a> for Vec<T> {
+    fn scan_field(line: usize, source_line: &'a str, text: &'a str) -> Result<Self, ParseError> {
+        let mut out = Vec::new();
+        for item in text.split(',') {
+            let item = item.trim();
+            if item.is_empty() {
+                return Err(ParseError::at_token(line, source_line, item, "a list item"));
+            }
+            out.push(T::scan_field(line, source_line, item)?);
+        }
+        return Ok(out);
+    }
+}
+
+pub trait FromCaptures<'a>: Sized {
+    const COUNT: usize;
+    fn from_captures(line: usize, source_line: &'a str, captures: &[&'a str]) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_captures {
+    ($count:literal; $($t:ident $i:tt),+) => {
+        impl<'a, $($t: ScanField<'a>),+> FromCaptures<'a> for ($($t,)+) {
+            const COUNT: usize = $count;
+
+            fn from_captures(line: usize, source_line: &'a str, captures: &[&'a str]) -> Result<Self, ParseError> {
+                return Ok(($($t::scan_field(line, source_line, captures[$i])?,)+));
+            }
+        }
+    };
+}
+
+impl_from_captures!(1; A 0);
+impl_from_captures!(2; A 0, B 1);
+impl_from_captures!(3; A 0, B 1, C 2);
+impl_from_captures!(4; A 0, B 1, C 2, D 3);
+impl_from_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_captures!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_captures!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+pub fn scan<'a, T: FromCaptures<'a>, I: ScanInput<'a>>(input: I, pattern: &str) -> Result<T, ParseError> {
+    let (line, source_line, part) = input.numbered();
+    let captures = match_pattern(line, source_line, part, pattern)?;
+
+    assert_eq!(
+        T::COUNT,
+        captures.len(),
+        "The pattern {:?} does not have one placeholder per field.",
+        pattern
+    );
+
+    return T::from_captures(line, source_line, &captures);
+}
+
+// the length of the start of "text" that matches "literal", where "[a|b]"
+// in the literal matches either "a" or "b", whichever matches more.
+fn match_literal(text: &str, literal: &str) -> Option<usize> {
+    let open = match literal.find('[') {
+        Some(open) => open,
+        None if text.starts_with(literal) => return Some(literal.len()),
+        None => return None,
+    };
+    let close = open + literal[open..].find(']').expect("Unclosed alternative in pattern.");
+    if !text.starts_with(&literal[..open]) {
+        return None;
+    }
+
+    let after = &text[open..];
+    let rest = &literal[(close + 1)..];
+    return literal[(open + 1)..close]
+        .split('|')
+        .filter(|choice| after.starts_with(*choice))
+        .filter_map(|choice| {
+            let len = match_literal(&after[choice.len()..], rest)?;
+            return Some(open + choice.len() + len);
+        })
+        .max();
+}
+
+fn find_literal(text: &str, literal: &str) -> Option<usize> {
+    return text
+        .char_indices()
+        .map(|(i, _)| i)
+        .find(|i| match_literal(&text[*i..], literal).is_some());
+}
+
+fn match_pattern<'a>(line: usize, source_line: &'a str, part: &'a str, pattern: &str) -> Result<Vec<&'a str>, ParseError> {
+    let text = part.trim();
+    let error_at = |pos: usize, expected: &str| {
+        let column = column_of(source_line, text) + pos;
+        return ParseError::new(line, source_line, column, expected);
+    };
+
+    let mut captures = Vec::new();
+    let mut pos = 0;
+    let mut rest = pattern;
+
+    loop {
+        let literal_end = rest.find('{').unwrap_or(rest.len());
+        let literal = &rest[..literal_end];
+
+        let literal_len = match match_literal(&text[pos..], literal) {
+            Some(len) => len,
+            None => {
+                return Err(error_at(pos, &format!("'{}'", literal)));
+            }
+        };
+        pos += literal_len;
+        rest = &rest[literal_end..];
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let placeholder_end = rest.find('}').expect("Unclosed placeholder in pattern.");
+        rest = &rest[(placeholder_end + 1)..];
+
+        // a placeholder runs until the next literal shows up.
+        let next_literal = &rest[..rest.find('{').unwrap_or(rest.len())];
+        assert!(
+            !next_literal.is_empty() || rest.is_empty(),
+            "Placeholders must be separated by some text."
+        );
+
+        let end = if next_literal.is_empty() {
+            text.len()
+        } else {
+            match find_literal(&text[pos..], next_literal) {
+                Some(i) => pos + i,
+                None => {
+                    // blame whatever follows the value the placeholder most likely
+                    // wanted, past the part of the literal that did match.
+                    let value_len = text[pos..]
+                        .find(|ch: char| !(ch.is_alphanumeric() || ch == '-' || ch == '.' || ch == '_'))
+                        .unwrap_or(text.len() - pos);
+                    let matched_len = text[(pos + value_len)..]
+                        .bytes()
+                        .zip(next_literal.split('[').next().unwrap().bytes())
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    return Err(error_at(pos + value_len + matched_len, &format!("'{}'", next_literal)));
+                }
+            }
+        };
+
+        let captured = text[pos..end].trim();
+        if captured.is_empty() {
+            return Err(error_at(pos, "a value"));
+        }
+
+        captures.push(captured);
+        pos = end;
+    }
+
+    if pos != text.len() {
+        return Err(error_at(pos, "the end of the line"));
+    }
+
+    return Ok(captures);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_points_at_the_column() {
+        let line = "123 -> x";
+        let err = ParseError::at_token(3, line, &line[4..6], "a wire name");
+        let expected = "line 3, column 5: expected a wire name\n3 | 123 -> x\n  |     ^";
+
+        assert_eq!(5, err.column);
+        assert_eq!(expected, err.to_string());
+    }
+
+    #[test]
+    fn parse_token_reports_position() {
+        let line = "x AND yy";
+        let err = parse_token::<u16>(12, line, &line[6..], "an integer").unwrap_err();
+
+        assert_eq!(12, err.line);
+        assert_eq!(7, err.column);
+        assert_eq!("an integer", err.expected);
+    }
+
+    #[test]
+    fn numbered_lines_skip_blank_lines() {
+        let lines: Vec<(usize, &str)> = numbered_lines("\na\n\nb\n").collect();
+
+        assert_eq!(vec![(2, "a"), (4, "b")], lines);
+    }
+
+    #[test]
+    fn scan_typed_fields() {
+        let line = "Valve AA has flow rate=13; tunnels lead to valves DD, II, BB";
+        let (name, rate, valves) = crate::scan!(
+            line,
+            "Valve {} has flow rate={}; tunnels lead to valves {,}",
+            &str,
+            i32,
+            Vec<String>
+        )
+        .unwrap();
+
+        assert_eq!("AA", name);
+        assert_eq!(13, rate);
+        assert_eq!(vec!["DD", "II", "BB"], valves);
+    }
+
+    #[test]
+    fn scan_alternatives() {
+        let (_, _, valves) = crate::scan!(
+            "Valve HH has flow rate=22; tunnel leads to valve GG",
+            "Valve {} has flow rate={}; [tunnel leads to valve|tunnels lead to valves] {,}",
+            &str,
+            i32,
+            Vec<String>
+        )
+        .unwrap();
+        assert_eq!(vec!["GG"], valves);
+
+        let (_, _, valves) = crate::scan!(
+            "Valve AA has flow rate=0; tunnels lead to valves DD, II",
+            "Valve {} has flow rate={}; [tunnel leads to valve|tunnels lead to valves] {,}",
+            &str,
+            i32,
+            Vec<String>
+        )
+        .unwrap();
+        assert_eq!(vec!["DD", "II"], valves);
+
+        let res: (i32,) = crate::scan!("3 pears", "{} [pear|pears]").unwrap();
+        assert_eq!((3,), res);
+
+        let err = crate::scan!("1 apple", "{} [pear|pears]", i32).unwrap_err();
+        assert_eq!((3, "' [pear|pears]'".to_string()), (err.column, err.expected));
+    }
+
+    #[test]
+    fn scan_with_inferred_types() {
+        let res: (i64, i64, char) = crate::scan!("  -3 to 7: x", "{} to {}: {}").unwrap();
+
+        assert_eq!((-3, 7, 'x'), res);
+    }
+
+    #[test]
+    fn scan_reports_mismatches() {
+        let err = crate::scan!((4, "move 1 form 2 to 3"), "move {} from {} to {}", i32, i32, i32).unwrap_err();
+        assert_eq!((4, 9, "' from '".to_string()), (err.line, err.column, err.expected));
+
+        let err = crate::scan!("move x from 2 to 3", "move {} from {} to {}", i32, i32, i32).unwrap_err();
+        assert_eq!((6, "an integer".to_string()), (err.column, err.expected));
+
+        let err = crate::scan!("1, 2,, 3", "{,}", Vec<i32>).unwrap_err();
+        assert_eq!((6, "a list item".to_string()), (err.column, err.expected));
+
+        let err = crate::scan!("a = 1 !", "{} = {}", &str, i32).unwrap_err();
+        assert_eq!((5, "an integer".to_string()), (err.column, err.expected));
+
+        let err = crate::scan!("a =", "{} = {}", &str, i32).unwrap_err();
+        assert_eq!((4, "' = '".to_string()), (err.column, err.expected));
+    }
+}